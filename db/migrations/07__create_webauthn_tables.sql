@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS webauthn_credentials
+(
+    id            BIGSERIAL PRIMARY KEY,
+    user_id       BIGINT                   NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    credential_id VARCHAR(1366)            NOT NULL UNIQUE,
+    public_key    BYTEA                    NOT NULL,
+    sign_count    BIGINT                   NOT NULL DEFAULT 0,
+    name          VARCHAR(100)             NOT NULL,
+    aaguid        VARCHAR(36),
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc'),
+    last_used_at  TIMESTAMP WITH TIME ZONE
+);
+
+CREATE TABLE IF NOT EXISTS webauthn_challenges
+(
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    BIGINT REFERENCES users (id) ON DELETE CASCADE,
+    challenge  VARCHAR(100)             NOT NULL UNIQUE,
+    ceremony   VARCHAR(20)              NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    used       BOOLEAN                  NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc')
+);
+
+CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user_id ON webauthn_credentials (user_id);
+CREATE INDEX IF NOT EXISTS idx_webauthn_challenges_challenge ON webauthn_challenges (challenge);
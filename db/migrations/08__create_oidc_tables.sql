@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS user_identities
+(
+    id            BIGSERIAL PRIMARY KEY,
+    user_id       BIGINT                   NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    provider      VARCHAR(50)              NOT NULL,
+    subject       VARCHAR(255)             NOT NULL,
+    email         VARCHAR(255)             NOT NULL,
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc'),
+    last_login_at TIMESTAMP WITH TIME ZONE,
+    CONSTRAINT uq_user_identities_provider_subject UNIQUE (provider, subject)
+);
+
+CREATE TABLE IF NOT EXISTS oidc_login_states
+(
+    id            BIGSERIAL PRIMARY KEY,
+    provider      VARCHAR(50)              NOT NULL,
+    state         VARCHAR(100)             NOT NULL UNIQUE,
+    nonce         VARCHAR(100)             NOT NULL,
+    code_verifier VARCHAR(128)             NOT NULL,
+    remember_me   BOOLEAN                  NOT NULL DEFAULT FALSE,
+    expires_at    TIMESTAMP WITH TIME ZONE NOT NULL,
+    used          BOOLEAN                  NOT NULL DEFAULT FALSE,
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc')
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_identities_user_id ON user_identities (user_id);
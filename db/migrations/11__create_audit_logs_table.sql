@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS audit_logs
+(
+    id          BIGSERIAL PRIMARY KEY,
+    actor_id    BIGINT                   REFERENCES users (id) ON DELETE SET NULL,
+    action      VARCHAR(50)              NOT NULL,
+    target_type VARCHAR(50)              NOT NULL,
+    target_id   BIGINT,
+    details     JSONB                    NOT NULL DEFAULT '{}'::jsonb,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc')
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_actor_id ON audit_logs (actor_id);
+CREATE INDEX IF NOT EXISTS idx_audit_logs_target ON audit_logs (target_type, target_id);
+CREATE INDEX IF NOT EXISTS idx_audit_logs_created_at ON audit_logs (created_at);
@@ -0,0 +1,6 @@
+-- Set when a user deletes their own account; the account is purged once this passes.
+-- Accounts deactivated by an administrator keep it NULL and are never purged.
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS purge_after TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_users_purge_after ON users (purge_after) WHERE purge_after IS NOT NULL;
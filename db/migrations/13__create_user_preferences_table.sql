@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS user_preferences
+(
+    user_id     BIGINT PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    preferences JSONB                    NOT NULL DEFAULT '{}'::jsonb,
+    updated_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc')
+);
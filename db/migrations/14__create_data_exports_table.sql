@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS data_exports
+(
+    id                  BIGSERIAL PRIMARY KEY,
+    user_id             BIGINT                   NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    status              VARCHAR(20)              NOT NULL,
+    download_token_hash VARCHAR(64)              NOT NULL UNIQUE,
+    archive             BYTEA,
+    created_at          TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc'),
+    completed_at        TIMESTAMP WITH TIME ZONE,
+    expires_at          TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_data_exports_user_id ON data_exports (user_id);
+-- Only one export per user can be in progress at a time.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_data_exports_user_id_pending ON data_exports (user_id) WHERE status = 'Pending';
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS invites
+(
+    id          BIGSERIAL PRIMARY KEY,
+    email       VARCHAR(255)             NOT NULL,
+    token_hash  VARCHAR(64)              NOT NULL UNIQUE,
+    role        VARCHAR(20),
+    invited_by  BIGINT                   REFERENCES users (id) ON DELETE SET NULL,
+    accepted_by BIGINT                   REFERENCES users (id) ON DELETE SET NULL,
+    expires_at  TIMESTAMP WITH TIME ZONE NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE,
+    revoked_at  TIMESTAMP WITH TIME ZONE,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc')
+);
+
+CREATE INDEX IF NOT EXISTS idx_invites_email ON invites (LOWER(email));
+CREATE INDEX IF NOT EXISTS idx_invites_created_at ON invites (created_at);
@@ -0,0 +1,25 @@
+-- Audit entries must outlive the accounts they mention, so the actor is kept as a plain id
+-- instead of being nulled out (which would also break the hash of the entry).
+ALTER TABLE audit_logs DROP CONSTRAINT IF EXISTS audit_logs_actor_id_fkey;
+
+ALTER TABLE audit_logs
+    ADD COLUMN IF NOT EXISTS ip_address VARCHAR(45),
+    ADD COLUMN IF NOT EXISTS user_agent TEXT,
+    ADD COLUMN IF NOT EXISTS prev_hash  VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS hash       VARCHAR(64);
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_ip_address ON audit_logs (ip_address);
+
+CREATE OR REPLACE FUNCTION reject_audit_log_changes() RETURNS trigger AS
+$$
+BEGIN
+    RAISE EXCEPTION 'audit_logs is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_logs_append_only ON audit_logs;
+CREATE TRIGGER audit_logs_append_only
+    BEFORE UPDATE OR DELETE
+    ON audit_logs
+    FOR EACH ROW
+EXECUTE FUNCTION reject_audit_log_changes();
@@ -0,0 +1,57 @@
+ALTER TABLE applications ADD COLUMN IF NOT EXISTS search_vector TSVECTOR;
+
+-- The `simple` configuration does no stemming, which suits company names and keeps queries
+-- predictable across languages. Punctuation in websites is split out so that `acme` matches
+-- `https://jobs.acme.com`.
+CREATE OR REPLACE FUNCTION application_search_vector(p_company TEXT, p_position TEXT, p_website TEXT, p_notes TEXT)
+    RETURNS TSVECTOR AS
+$$
+SELECT setweight(to_tsvector('simple', coalesce(p_company, '')), 'A')
+           || setweight(to_tsvector('simple', coalesce(p_position, '')), 'A')
+           || setweight(to_tsvector('simple', regexp_replace(coalesce(p_website, ''), '[^[:alnum:]]+', ' ', 'g')), 'B')
+           || setweight(to_tsvector('simple', coalesce(p_notes, '')), 'C')
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION application_notes(p_application_id BIGINT) RETURNS TEXT AS
+$$
+SELECT string_agg(notes, ' ' ORDER BY created_at)
+FROM application_statuses
+WHERE application_id = p_application_id
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION refresh_application_search_vector() RETURNS trigger AS
+$$
+BEGIN
+    NEW.search_vector := application_search_vector(NEW.company, NEW.position, NEW.website, application_notes(NEW.id));
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS applications_search_vector ON applications;
+CREATE TRIGGER applications_search_vector
+    BEFORE INSERT OR UPDATE OF company, position, website
+    ON applications
+    FOR EACH ROW
+EXECUTE FUNCTION refresh_application_search_vector();
+
+CREATE OR REPLACE FUNCTION refresh_status_application_search_vector() RETURNS trigger AS
+$$
+BEGIN
+    UPDATE applications
+    SET search_vector = application_search_vector(company, position, website, application_notes(id))
+    WHERE id = CASE WHEN TG_OP = 'DELETE' THEN OLD.application_id ELSE NEW.application_id END;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS application_statuses_search_vector ON application_statuses;
+CREATE TRIGGER application_statuses_search_vector
+    AFTER INSERT OR UPDATE OF notes OR DELETE
+    ON application_statuses
+    FOR EACH ROW
+EXECUTE FUNCTION refresh_status_application_search_vector();
+
+UPDATE applications
+SET search_vector = application_search_vector(company, position, website, application_notes(id));
+
+CREATE INDEX IF NOT EXISTS idx_applications_search_vector ON applications USING GIN (search_vector);
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS saved_views
+(
+    id         BIGSERIAL PRIMARY KEY,
+    user_id    BIGINT                   NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name       VARCHAR(100)             NOT NULL,
+    filter     JSONB                    NOT NULL DEFAULT '{}',
+    columns    JSONB                    NOT NULL DEFAULT '[]',
+    show_count BOOLEAN                  NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc'),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT (now() AT TIME ZONE 'utc')
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_saved_views_user_name ON saved_views (user_id, LOWER(name));
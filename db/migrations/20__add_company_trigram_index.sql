@@ -0,0 +1,4 @@
+-- Trigram matching backs fuzzy company suggestions, e.g. `Google` for `gogle` or `google inc`.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_applications_company_trgm ON applications USING GIN (LOWER(company) gin_trgm_ops);
@@ -0,0 +1,25 @@
+-- Keys used to spot applications made twice to the same role. Companies lose punctuation and a
+-- trailing legal form, so `Acme, Inc.` and `acme` compare equal.
+CREATE OR REPLACE FUNCTION normalize_company(p_company TEXT) RETURNS TEXT AS
+$$
+SELECT btrim(regexp_replace(
+        btrim(regexp_replace(lower(p_company), '[^[:alnum:]]+', ' ', 'g')),
+        '\s+(inc|llc|ltd|limited|gmbh|corp|corporation|co|company|plc|ag|sa|bv)$', ''))
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION normalize_position(p_position TEXT) RETURNS TEXT AS
+$$
+SELECT btrim(regexp_replace(lower(p_position), '[^[:alnum:]]+', ' ', 'g'))
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Host of a website without scheme, credentials, port or a leading `www.`, NULL when there is none.
+CREATE OR REPLACE FUNCTION website_domain(p_website TEXT) RETURNS TEXT AS
+$$
+SELECT NULLIF(regexp_replace(
+        substring(lower(btrim(p_website)) FROM '^(?:[a-z][a-z0-9+.-]*://)?(?:[^@/]*@)?([^/?#:]*)'),
+        '^www\.', ''), '')
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE INDEX IF NOT EXISTS idx_applications_duplicate_keys
+    ON applications (created_by, normalize_position(position), normalize_company(company))
+    WHERE deleted = false;
@@ -0,0 +1,5 @@
+-- Tags are stored trimmed and lowercased. Archived applications stay out of the list unless asked for.
+ALTER TABLE applications ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE applications ADD COLUMN IF NOT EXISTS archived_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_applications_tags ON applications USING GIN (tags);
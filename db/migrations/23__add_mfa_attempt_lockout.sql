@@ -0,0 +1,5 @@
+-- Consecutive rejected codes at the MFA challenge. Reaching the limit locks verification until
+-- `locked_until` and starts the count again.
+ALTER TABLE user_mfa
+    ADD COLUMN IF NOT EXISTS failed_attempts INT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS locked_until    TIMESTAMP WITH TIME ZONE;
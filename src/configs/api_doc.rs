@@ -18,12 +18,66 @@ use utoipa::{OpenApi};
     paths(
         crate::handlers::user_handler::register_user,
         crate::handlers::user_handler::get_user_data,
+        crate::handlers::user_handler::update_profile,
+        crate::handlers::user_handler::delete_account,
+        crate::handlers::user_handler::get_preferences,
+        crate::handlers::user_handler::update_preferences,
+        crate::handlers::user_handler::cancel_account_deletion,
+        crate::handlers::user_handler::list_own_audit_logs,
+        crate::handlers::data_export_handler::request_data_export,
+        crate::handlers::data_export_handler::list_data_exports,
+        crate::handlers::data_export_handler::download_data_export,
         crate::handlers::auth_handler::login,
+        crate::handlers::auth_handler::refresh,
         crate::handlers::auth_handler::forgot_password,
         crate::handlers::auth_handler::reset_password,
+        crate::handlers::auth_handler::change_password,
+        crate::handlers::auth_handler::check_password_strength,
+        crate::handlers::auth_handler::change_email,
+        crate::handlers::auth_handler::confirm_email_change,
+        crate::handlers::mfa_handler::enroll_mfa,
+        crate::handlers::mfa_handler::confirm_mfa,
+        crate::handlers::mfa_handler::verify_mfa,
+        crate::handlers::mfa_handler::disable_mfa,
+        crate::handlers::passkey_handler::passkey_registration_options,
+        crate::handlers::passkey_handler::register_passkey,
+        crate::handlers::passkey_handler::passkey_login_options,
+        crate::handlers::passkey_handler::login_with_passkey,
+        crate::handlers::passkey_handler::list_passkeys,
+        crate::handlers::passkey_handler::rename_passkey,
+        crate::handlers::passkey_handler::revoke_passkey,
+        crate::handlers::personal_access_token_handler::create_personal_access_token,
+        crate::handlers::personal_access_token_handler::list_personal_access_tokens,
+        crate::handlers::personal_access_token_handler::revoke_personal_access_token,
+        crate::handlers::saved_view_handler::create_saved_view,
+        crate::handlers::saved_view_handler::list_saved_views,
+        crate::handlers::saved_view_handler::get_saved_view,
+        crate::handlers::saved_view_handler::update_saved_view,
+        crate::handlers::saved_view_handler::delete_saved_view,
+        crate::handlers::session_handler::list_sessions,
+        crate::handlers::session_handler::revoke_other_sessions,
+        crate::handlers::session_handler::revoke_session,
+        crate::handlers::oidc_handler::list_oidc_providers,
+        crate::handlers::oidc_handler::oidc_authorize,
+        crate::handlers::oidc_handler::oidc_callback,
+        crate::handlers::oidc_handler::list_identities,
+        crate::handlers::oidc_handler::unlink_identity,
+        crate::handlers::admin_handler::list_users,
+        crate::handlers::admin_handler::change_user_role,
+        crate::handlers::admin_handler::deactivate_user,
+        crate::handlers::admin_handler::reactivate_user,
+        crate::handlers::admin_handler::force_password_reset,
+        crate::handlers::admin_handler::list_audit_logs,
+        crate::handlers::admin_handler::verify_audit_logs,
+        crate::handlers::admin_handler::create_invite,
+        crate::handlers::admin_handler::list_invites,
+        crate::handlers::admin_handler::revoke_invite,
         crate::handlers::application_handler::register_application,
         crate::handlers::application_handler::add_application_status,
         crate::handlers::application_handler::fetch_applications_for_user_with_filters,
+        crate::handlers::application_handler::suggest_companies,
+        crate::handlers::application_handler::find_duplicate_applications,
+        crate::handlers::application_handler::bulk_update_applications,
         crate::handlers::dashboard_handler::get_dashboard_stats,
         crate::handlers::dashboard_handler::get_success_rate,
         crate::handlers::dashboard_handler::get_chart_data,
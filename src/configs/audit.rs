@@ -0,0 +1,17 @@
+use std::env::var;
+use tracing::info;
+
+const MIN_KEY_LENGTH: usize = 32;
+
+/// Reads `AUDIT_LOG_HMAC_KEY`, the secret the audit log hash chain is keyed with. It is kept out
+/// of the database, so someone who can rewrite `audit_logs` still cannot recompute the chain.
+pub fn load_audit_log_key() -> Vec<u8> {
+    let key = var("AUDIT_LOG_HMAC_KEY").expect("AUDIT_LOG_HMAC_KEY must be set");
+
+    if key.len() < MIN_KEY_LENGTH {
+        panic!("AUDIT_LOG_HMAC_KEY must be at least {} bytes long", MIN_KEY_LENGTH);
+    }
+
+    info!("Audit log hash chain is keyed");
+    key.into_bytes()
+}
@@ -1,4 +1,9 @@
+pub(crate) mod audit;
 pub(crate) mod database;
 pub(crate) mod router;
 mod api_doc;
-pub(crate) mod routes;
\ No newline at end of file
+pub(crate) mod routes;
+pub(crate) mod oidc;
+pub(crate) mod registration;
+pub(crate) mod session;
+pub(crate) mod webauthn;
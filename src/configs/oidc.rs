@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::env::var;
+use tracing::info;
+
+#[derive(Clone, Debug)]
+pub struct OidcProviderConfig {
+    pub name: String,
+    pub display_name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub scopes: String,
+}
+
+/// Loads the providers listed in `OIDC_PROVIDERS` (comma separated). Each provider `foo`
+/// is configured through `OIDC_FOO_ISSUER`, `OIDC_FOO_CLIENT_ID`, `OIDC_FOO_REDIRECT_URI`
+/// and the optional `OIDC_FOO_CLIENT_SECRET`, `OIDC_FOO_SCOPES` and `OIDC_FOO_DISPLAY_NAME`.
+pub fn load_oidc_providers() -> HashMap<String, OidcProviderConfig> {
+    let names = var("OIDC_PROVIDERS").unwrap_or_default();
+
+    names
+        .split(',')
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let key = |suffix: &str| format!("OIDC_{}_{}", name.to_uppercase(), suffix);
+            let required = |suffix: &str| {
+                let key = key(suffix);
+                var(&key).unwrap_or_else(|_| panic!("{} must be set", key))
+            };
+
+            let provider = OidcProviderConfig {
+                display_name: var(key("DISPLAY_NAME")).unwrap_or_else(|_| name.clone()),
+                issuer: required("ISSUER").trim_end_matches('/').to_string(),
+                client_id: required("CLIENT_ID"),
+                client_secret: var(key("CLIENT_SECRET")).ok(),
+                redirect_uri: required("REDIRECT_URI"),
+                scopes: var(key("SCOPES")).unwrap_or_else(|_| "openid email profile".to_string()),
+                name: name.clone(),
+            };
+
+            info!("Configured OIDC provider '{}' ({})", provider.name, provider.issuer);
+            (name, provider)
+        })
+        .collect()
+}
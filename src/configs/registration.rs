@@ -0,0 +1,55 @@
+use std::env::var;
+use tracing::info;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegistrationMode {
+    Open,
+    Closed,
+    InviteOnly,
+    DomainAllowlist,
+}
+
+#[derive(Clone, Debug)]
+pub struct RegistrationPolicy {
+    pub mode: RegistrationMode,
+    pub allowed_domains: Vec<String>,
+}
+
+impl RegistrationPolicy {
+    /// Whether someone may sign up with this email without presenting an invite.
+    pub fn admits_without_invite(&self, email: &str) -> bool {
+        match self.mode {
+            RegistrationMode::Open => true,
+            RegistrationMode::Closed | RegistrationMode::InviteOnly => false,
+            RegistrationMode::DomainAllowlist => email
+                .rsplit_once('@')
+                .is_some_and(|(_, domain)| self.allowed_domains.contains(&domain.to_lowercase())),
+        }
+    }
+}
+
+/// Reads `REGISTRATION_MODE` (`open`, `closed`, `invite_only` or `domain_allowlist`, defaults
+/// to `open`). The allowlist mode also needs `REGISTRATION_ALLOWED_DOMAINS` (comma separated).
+pub fn load_registration_policy() -> RegistrationPolicy {
+    let mode = match var("REGISTRATION_MODE").unwrap_or_default().trim().to_lowercase().as_str() {
+        "" | "open" => RegistrationMode::Open,
+        "closed" => RegistrationMode::Closed,
+        "invite_only" => RegistrationMode::InviteOnly,
+        "domain_allowlist" => RegistrationMode::DomainAllowlist,
+        other => panic!("REGISTRATION_MODE '{}' is not supported", other),
+    };
+
+    let allowed_domains: Vec<String> = var("REGISTRATION_ALLOWED_DOMAINS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|domain| domain.trim().trim_start_matches('@').to_lowercase())
+        .filter(|domain| !domain.is_empty())
+        .collect();
+
+    if mode == RegistrationMode::DomainAllowlist && allowed_domains.is_empty() {
+        panic!("REGISTRATION_ALLOWED_DOMAINS must be set when REGISTRATION_MODE is domain_allowlist");
+    }
+
+    info!("Registration mode: {:?}", mode);
+    RegistrationPolicy { mode, allowed_domains }
+}
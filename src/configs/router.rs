@@ -1,17 +1,17 @@
 use crate::configs::api_doc::ApiDoc;
-use crate::configs::routes::{ADD_APPLICATION, ADD_APPLICATION_STATUS, FORGOT_PASSWORD, GET_APPLICATIONS_FOR_USER, GET_CHART_DATA, GET_DASHBOARD_STATS, GET_SUCCESS_RATE, LOGIN, LOGOUT, RESET_PASSWORD, USER_DATA, USER_REGISTER};
-use crate::handlers::application_handler::{add_application_status, fetch_applications_for_user_with_filters, register_application, ApplicationHandler};
-use crate::handlers::auth_handler::{forgot_password, login, logout, reset_password, AuthHandler};
-use crate::handlers::user_handler::{get_user_data, register_user, UserHandler};
+use crate::configs::routes::{ADD_APPLICATION, ADD_APPLICATION_STATUS, ADMIN_AUDIT_LOGS, ADMIN_AUDIT_LOGS_VERIFY, ADMIN_INVITE, ADMIN_INVITES, ADMIN_USERS, ADMIN_USER_DEACTIVATE, ADMIN_USER_FORCE_PASSWORD_RESET, ADMIN_USER_REACTIVATE, ADMIN_USER_ROLE, APPLICATION_BULK, APPLICATION_COMPANY_SUGGESTIONS, APPLICATION_DUPLICATES, APPLICATION_VIEW, APPLICATION_VIEWS, CHANGE_EMAIL, CHANGE_PASSWORD, CONFIRM_EMAIL_CHANGE, FORGOT_PASSWORD, GET_APPLICATIONS_FOR_USER, GET_CHART_DATA, GET_DASHBOARD_STATS, GET_SUCCESS_RATE, LOGIN, LOGOUT, MFA_CONFIRM, MFA_DISABLE, MFA_ENROLL, MFA_VERIFY, OIDC_AUTHORIZE, OIDC_CALLBACK, OIDC_PROVIDERS, PASSKEY_LOGIN, PASSKEY_LOGIN_OPTIONS, PASSKEY_REGISTER, PASSKEY_REGISTER_OPTIONS, PASSWORD_STRENGTH, REFRESH, RESET_PASSWORD, USER_AUDIT_LOGS, USER_SESSION, USER_SESSIONS, USER_CANCEL_DELETION, USER_DATA, USER_EXPORTS, USER_EXPORT_DOWNLOAD, USER_IDENTITIES, USER_IDENTITY, USER_PREFERENCES, USER_PASSKEY, USER_PASSKEYS, USER_REGISTER, USER_TOKEN, USER_TOKENS};
+use crate::handlers::application_handler::{add_application_status, bulk_update_applications, fetch_applications_for_user_with_filters, find_duplicate_applications, register_application, suggest_companies, ApplicationHandler};
+use crate::handlers::auth_handler::{change_email, change_password, check_password_strength, confirm_email_change, forgot_password, login, logout, refresh, reset_password, AuthHandler};
+use crate::handlers::user_handler::{cancel_account_deletion, delete_account, get_preferences, get_user_data, list_own_audit_logs, register_user, update_preferences, update_profile, UserHandler};
 use crate::repositories::application_repository::ApplicationRepository;
 use crate::repositories::user_repository::UserRepository;
 use crate::services::application_service::ApplicationService;
 use crate::services::auth_service::AuthService;
 use crate::services::user_service::UserService;
-use axum::routing::{get, post};
-use axum::Router;
+use axum::routing::{delete, get, patch, post};
+use axum::{middleware, Extension, Router};
 use dotenvy::var;
-use http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use http::header::{HeaderName, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use http::Method;
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -22,8 +22,44 @@ use crate::handlers::dashboard_handler::{get_chart_data, get_dashboard_stats, ge
 use crate::repositories::token_repository::TokenRepository;
 use crate::services::dashboard_service::DashboardService;
 use crate::services::email_service::EmailService;
+use crate::handlers::mfa_handler::{confirm_mfa, disable_mfa, enroll_mfa, verify_mfa, MfaHandler};
+use crate::repositories::mfa_repository::MfaRepository;
+use crate::services::mfa_service::MfaService;
+use crate::handlers::passkey_handler::{list_passkeys, login_with_passkey, passkey_login_options, passkey_registration_options, register_passkey, rename_passkey, revoke_passkey, PasskeyHandler};
+use crate::repositories::passkey_repository::PasskeyRepository;
+use crate::services::passkey_service::PasskeyService;
+use crate::configs::oidc::load_oidc_providers;
+use crate::configs::webauthn::load_webauthn_config;
+use crate::configs::registration::load_registration_policy;
+use crate::repositories::invite_repository::InviteRepository;
+use crate::services::registration_service::RegistrationService;
+use crate::handlers::oidc_handler::{list_identities, list_oidc_providers, oidc_authorize, oidc_callback, unlink_identity, OidcHandler};
+use crate::repositories::identity_repository::IdentityRepository;
+use crate::services::oidc_service::OidcService;
+use crate::handlers::personal_access_token_handler::{create_personal_access_token, list_personal_access_tokens, revoke_personal_access_token, PersonalAccessTokenHandler};
+use crate::repositories::personal_access_token_repository::PersonalAccessTokenRepository;
+use crate::services::personal_access_token_service::PersonalAccessTokenService;
+use crate::handlers::saved_view_handler::{create_saved_view, delete_saved_view, get_saved_view, list_saved_views, update_saved_view, SavedViewHandler};
+use crate::repositories::saved_view_repository::SavedViewRepository;
+use crate::services::saved_view_service::SavedViewService;
+use crate::repositories::email_change_repository::EmailChangeRepository;
+use crate::handlers::admin_handler::{change_user_role, create_invite, deactivate_user, force_password_reset, list_audit_logs, list_invites, list_users, reactivate_user, revoke_invite, verify_audit_logs, AdminHandler};
+use crate::repositories::audit_log_repository::AuditLogRepository;
+use crate::services::admin_service::AdminService;
+use crate::services::audit_service::AuditService;
+use crate::repositories::preferences_repository::PreferencesRepository;
+use crate::handlers::data_export_handler::{download_data_export, list_data_exports, request_data_export, DataExportHandler};
+use crate::repositories::data_export_repository::DataExportRepository;
+use crate::services::data_export_service::DataExportService;
+use crate::services::password_hash_service::PasswordHashService;
+use crate::configs::session::{load_session_config, CSRF_TOKEN_HEADER};
+use crate::middlewares::csrf_protection::csrf_protection;
+use crate::handlers::session_handler::{list_sessions, revoke_other_sessions, revoke_session, SessionHandler};
+use crate::repositories::session_repository::SessionRepository;
+use crate::services::session_service::SessionService;
+use crate::services::password_policy_service::PasswordPolicyService;
 
-pub fn app_router(db_pool: Arc<PgPool>) -> Router {
+pub fn app_router(db_pool: Arc<PgPool>, audit_log_repo: Arc<AuditLogRepository>) -> Router {
     
     let frontend_urls = var("FRONTEND_URLS").expect("FRONTEND_URLS must be set");
     
@@ -34,45 +70,163 @@ pub fn app_router(db_pool: Arc<PgPool>) -> Router {
 
     let cors = CorsLayer::new()
         .allow_origin(origins)
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
         .allow_credentials(true)
-        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
+        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE, HeaderName::from_static(CSRF_TOKEN_HEADER)]);
 
-    let user_repo = UserRepository::new(db_pool.clone());
+    let user_repo = UserRepository::new(db_pool.clone(), audit_log_repo.clone());
     let token_repo = TokenRepository::new(db_pool.clone());
+    let mfa_repo = MfaRepository::new(db_pool.clone());
+    let passkey_repo = PasskeyRepository::new(db_pool.clone());
+    let identity_repo = IdentityRepository::new(db_pool.clone());
+    let personal_access_token_repo = PersonalAccessTokenRepository::new(db_pool.clone());
+    let email_change_repo = EmailChangeRepository::new(db_pool.clone());
+    let preferences_repo = PreferencesRepository::new(db_pool.clone());
+    let data_export_repo = DataExportRepository::new(db_pool.clone());
+    let invite_repo = InviteRepository::new(db_pool.clone(), audit_log_repo.clone());
+    let session_repo = SessionRepository::new(db_pool.clone());
     let email_service = EmailService::new();
+    let registration_service = RegistrationService::new(load_registration_policy(), invite_repo.clone());
+    let password_policy = PasswordPolicyService::new();
+    let password_hasher = PasswordHashService::new();
+    let session_config = Arc::new(load_session_config());
+    let audit_service = AuditService::new(audit_log_repo.clone());
+    let session_service = SessionService::new(session_repo.clone(), preferences_repo.clone(), email_service.clone(), audit_service.clone());
     
-    let user_service = UserService::new(user_repo.clone());
+    let user_service = UserService::new(user_repo.clone(), preferences_repo.clone(), registration_service.clone(), password_policy.clone(), password_hasher.clone(), audit_service.clone());
     let user_handler = Arc::new(UserHandler {
         user_service: user_service.clone(),
     });
     let user_handler_router = Router::new()
         .route(USER_REGISTER, post(register_user))
-        .route(USER_DATA, get(get_user_data))
+        .route(USER_DATA, get(get_user_data).patch(update_profile).delete(delete_account))
+        .route(USER_PREFERENCES, get(get_preferences).patch(update_preferences))
+        .route(USER_CANCEL_DELETION, post(cancel_account_deletion))
+        .route(USER_AUDIT_LOGS, get(list_own_audit_logs))
         .with_state(user_handler);
 
-    let auth_service = AuthService::new(user_repo.clone(), token_repo.clone(), email_service.clone());
-    let auth_handler = Arc::new(AuthHandler { auth_service });
+    let auth_service = AuthService::new(user_repo.clone(), token_repo.clone(), email_service.clone(), mfa_repo.clone(), email_change_repo.clone(), password_policy, password_hasher.clone(), session_service.clone(), audit_service.clone(), preferences_repo.clone());
+    let auth_handler = Arc::new(AuthHandler { auth_service, session_config: session_config.clone() });
     let auth_handler_router = Router::new()
         .route(LOGIN, post(login))
         .route(FORGOT_PASSWORD, post(forgot_password))
         .route(RESET_PASSWORD, post(reset_password))
         .route(LOGOUT, post(logout))
+        .route(REFRESH, post(refresh))
+        .route(CHANGE_PASSWORD, post(change_password))
+        .route(PASSWORD_STRENGTH, post(check_password_strength))
+        .route(CHANGE_EMAIL, post(change_email))
+        .route(CONFIRM_EMAIL_CHANGE, post(confirm_email_change))
     .with_state(auth_handler);
 
+    let mfa_service = MfaService::new(user_repo.clone(), mfa_repo.clone(), password_hasher.clone(), session_service.clone(), audit_service.clone());
+    let mfa_handler = Arc::new(MfaHandler { mfa_service, session_config: session_config.clone() });
+    let mfa_handler_router = Router::new()
+        .route(MFA_ENROLL, post(enroll_mfa))
+        .route(MFA_CONFIRM, post(confirm_mfa))
+        .route(MFA_VERIFY, post(verify_mfa))
+        .route(MFA_DISABLE, post(disable_mfa))
+        .with_state(mfa_handler);
+
+    // Without a relying party the passkey endpoints are not mounted at all.
+    let passkey_handler_router = load_webauthn_config()
+        .map(|webauthn_config| {
+            let passkey_service = PasskeyService::new(user_repo.clone(), passkey_repo.clone(), session_service.clone(), audit_service.clone(), webauthn_config);
+            let passkey_handler = Arc::new(PasskeyHandler { passkey_service, session_config: session_config.clone() });
+            Router::new()
+                .route(PASSKEY_REGISTER_OPTIONS, post(passkey_registration_options))
+                .route(PASSKEY_REGISTER, post(register_passkey))
+                .route(PASSKEY_LOGIN_OPTIONS, post(passkey_login_options))
+                .route(PASSKEY_LOGIN, post(login_with_passkey))
+                .route(USER_PASSKEYS, get(list_passkeys))
+                .route(USER_PASSKEY, patch(rename_passkey).delete(revoke_passkey))
+                .with_state(passkey_handler)
+        })
+        .unwrap_or_default();
+
+    let oidc_service = OidcService::new(user_repo.clone(), identity_repo.clone(), registration_service, password_hasher.clone(), session_service.clone(), audit_service.clone(), load_oidc_providers());
+    let oidc_handler = Arc::new(OidcHandler { oidc_service, session_config });
+    let oidc_handler_router = Router::new()
+        .route(OIDC_PROVIDERS, get(list_oidc_providers))
+        .route(OIDC_AUTHORIZE, get(oidc_authorize))
+        .route(OIDC_CALLBACK, post(oidc_callback))
+        .route(USER_IDENTITIES, get(list_identities))
+        .route(USER_IDENTITY, delete(unlink_identity))
+        .with_state(oidc_handler);
+
+    let token_service = PersonalAccessTokenService::new(user_repo.clone(), personal_access_token_repo.clone(), audit_service.clone());
+    let token_handler = Arc::new(PersonalAccessTokenHandler { token_service: token_service.clone() });
+    let token_handler_router = Router::new()
+        .route(USER_TOKENS, post(create_personal_access_token).get(list_personal_access_tokens))
+        .route(USER_TOKEN, delete(revoke_personal_access_token))
+        .with_state(token_handler);
+
+    let session_handler = Arc::new(SessionHandler { session_service });
+    let session_handler_router = Router::new()
+        .route(USER_SESSIONS, get(list_sessions).delete(revoke_other_sessions))
+        .route(USER_SESSION, delete(revoke_session))
+        .with_state(session_handler);
+
+    let admin_service = AdminService::new(user_repo.clone(), audit_service, invite_repo, email_service.clone(), password_hasher);
+    let admin_handler = Arc::new(AdminHandler { admin_service });
+    let admin_handler_router = Router::new()
+        .route(ADMIN_USERS, get(list_users))
+        .route(ADMIN_USER_ROLE, patch(change_user_role))
+        .route(ADMIN_USER_DEACTIVATE, post(deactivate_user))
+        .route(ADMIN_USER_REACTIVATE, post(reactivate_user))
+        .route(ADMIN_USER_FORCE_PASSWORD_RESET, post(force_password_reset))
+        .route(ADMIN_AUDIT_LOGS, get(list_audit_logs))
+        .route(ADMIN_AUDIT_LOGS_VERIFY, get(verify_audit_logs))
+        .route(ADMIN_INVITES, post(create_invite).get(list_invites))
+        .route(ADMIN_INVITE, delete(revoke_invite))
+        .with_state(admin_handler);
+
     let swagger_router = Router::new()
         .merge(SwaggerUi::new("/").url("/api-docs/openapi.json", ApiDoc::openapi()));
 
 
     let application_repo = ApplicationRepository::new(db_pool.clone());
-    let application_service = ApplicationService::new(application_repo);
+    let saved_view_repo = SavedViewRepository::new(db_pool.clone());
+    let application_service = ApplicationService::new(application_repo.clone(), preferences_repo.clone(), saved_view_repo.clone());
     let application_handler = Arc::new(ApplicationHandler {application_service: application_service.clone()});
     let application_handler_router = Router::new()
         .route(ADD_APPLICATION, post(register_application))
         .route(ADD_APPLICATION_STATUS, post(add_application_status))
         .route(GET_APPLICATIONS_FOR_USER, get(fetch_applications_for_user_with_filters))
+        .route(APPLICATION_COMPANY_SUGGESTIONS, get(suggest_companies))
+        .route(APPLICATION_DUPLICATES, get(find_duplicate_applications))
+        .route(APPLICATION_BULK, post(bulk_update_applications))
         .with_state(application_handler);
+
+    let saved_view_service = SavedViewService::new(saved_view_repo.clone(), application_repo.clone());
+    let saved_view_handler = Arc::new(SavedViewHandler { saved_view_service });
+    let saved_view_handler_router = Router::new()
+        .route(APPLICATION_VIEWS, post(create_saved_view).get(list_saved_views))
+        .route(APPLICATION_VIEW, get(get_saved_view).patch(update_saved_view).delete(delete_saved_view))
+        .with_state(saved_view_handler);
     
+    let data_export_service = DataExportService::new(
+        user_repo.clone(),
+        application_repo,
+        token_repo,
+        personal_access_token_repo,
+        preferences_repo,
+        passkey_repo.clone(),
+        identity_repo.clone(),
+        mfa_repo.clone(),
+        email_change_repo.clone(),
+        session_repo.clone(),
+        saved_view_repo.clone(),
+        audit_log_repo.clone(),
+        data_export_repo,
+        email_service,
+    );
+    let data_export_handler = Arc::new(DataExportHandler { data_export_service });
+    let data_export_handler_router = Router::new()
+        .route(USER_EXPORTS, post(request_data_export).get(list_data_exports))
+        .route(USER_EXPORT_DOWNLOAD, get(download_data_export))
+        .with_state(data_export_handler);
+
     let dashboard_service = DashboardService::new(application_service);
     let dashboard_handler = Arc::new(DashboardHandler {dashboard_service});
     let dashboard_handler_router = Router::new()
@@ -85,7 +239,20 @@ pub fn app_router(db_pool: Arc<PgPool>) -> Router {
         .merge(user_handler_router)
         .merge(swagger_router)
         .merge(auth_handler_router)
+        .merge(mfa_handler_router)
+        .merge(passkey_handler_router)
+        .merge(oidc_handler_router)
+        .merge(token_handler_router)
+        .merge(session_handler_router)
+        .merge(admin_handler_router)
         .merge(application_handler_router)
+        .merge(saved_view_handler_router)
         .merge(dashboard_handler_router)
+        .merge(data_export_handler_router)
+        // Lets the `Claims` extractor resolve personal access tokens and check token and session revocation on any route.
+        .layer(Extension(token_service))
+        .layer(Extension(user_repo))
+        .layer(Extension(session_repo))
+        .layer(middleware::from_fn(csrf_protection))
         .layer(cors)
 }
@@ -1,16 +1,65 @@
 pub const LOGIN: &str = "/api/v1/auth/login";
 pub const LOGOUT: &str = "/api/v1/auth/logout";
+pub const REFRESH: &str = "/api/v1/auth/refresh";
 
 pub const USER_DATA: &str = "/api/v1/user/me";
 pub const USER_REGISTER: &str = "/api/v1/user/register";
+pub const USER_PREFERENCES: &str = "/api/v1/user/me/preferences";
+pub const USER_CANCEL_DELETION: &str = "/api/v1/user/cancel-deletion";
+pub const USER_EXPORTS: &str = "/api/v1/user/exports";
+pub const USER_EXPORT_DOWNLOAD: &str = "/api/v1/user/exports/download";
 
 pub const FORGOT_PASSWORD: &str = "/api/v1/auth/forgot-password";
 pub const RESET_PASSWORD: &str = "/api/v1/auth/reset-password";
+pub const CHANGE_PASSWORD: &str = "/api/v1/auth/change-password";
+pub const PASSWORD_STRENGTH: &str = "/api/v1/auth/password-strength";
+pub const CHANGE_EMAIL: &str = "/api/v1/auth/change-email";
+pub const CONFIRM_EMAIL_CHANGE: &str = "/api/v1/auth/change-email/confirm";
+
+pub const MFA_ENROLL: &str = "/api/v1/auth/mfa/enroll";
+pub const MFA_CONFIRM: &str = "/api/v1/auth/mfa/confirm";
+pub const MFA_VERIFY: &str = "/api/v1/auth/mfa/verify";
+pub const MFA_DISABLE: &str = "/api/v1/auth/mfa/disable";
+
+pub const PASSKEY_REGISTER_OPTIONS: &str = "/api/v1/auth/passkeys/register/options";
+pub const PASSKEY_REGISTER: &str = "/api/v1/auth/passkeys/register";
+pub const PASSKEY_LOGIN_OPTIONS: &str = "/api/v1/auth/passkeys/login/options";
+pub const PASSKEY_LOGIN: &str = "/api/v1/auth/passkeys/login";
+
+pub const OIDC_PROVIDERS: &str = "/api/v1/auth/oidc/providers";
+pub const OIDC_AUTHORIZE: &str = "/api/v1/auth/oidc/{provider}/authorize";
+pub const OIDC_CALLBACK: &str = "/api/v1/auth/oidc/{provider}/callback";
+
+pub const USER_PASSKEYS: &str = "/api/v1/user/passkeys";
+pub const USER_PASSKEY: &str = "/api/v1/user/passkeys/{id}";
+pub const USER_IDENTITIES: &str = "/api/v1/user/identities";
+pub const USER_IDENTITY: &str = "/api/v1/user/identities/{id}";
+
+pub const USER_TOKENS: &str = "/api/v1/user/tokens";
+pub const USER_TOKEN: &str = "/api/v1/user/tokens/{id}";
+pub const USER_SESSIONS: &str = "/api/v1/user/sessions";
+pub const USER_SESSION: &str = "/api/v1/user/sessions/{id}";
+pub const USER_AUDIT_LOGS: &str = "/api/v1/user/audit-logs";
+
+pub const ADMIN_USERS: &str = "/api/v1/admin/users";
+pub const ADMIN_USER_ROLE: &str = "/api/v1/admin/users/{id}/role";
+pub const ADMIN_USER_DEACTIVATE: &str = "/api/v1/admin/users/{id}/deactivate";
+pub const ADMIN_USER_REACTIVATE: &str = "/api/v1/admin/users/{id}/reactivate";
+pub const ADMIN_USER_FORCE_PASSWORD_RESET: &str = "/api/v1/admin/users/{id}/force-password-reset";
+pub const ADMIN_AUDIT_LOGS: &str = "/api/v1/admin/audit-logs";
+pub const ADMIN_AUDIT_LOGS_VERIFY: &str = "/api/v1/admin/audit-logs/verify";
+pub const ADMIN_INVITES: &str = "/api/v1/admin/invites";
+pub const ADMIN_INVITE: &str = "/api/v1/admin/invites/{id}";
 
 pub const ADD_APPLICATION: &str = "/api/v1/application";
 pub const GET_APPLICATIONS_FOR_USER: &str = "/api/v1/application";
 
 pub const ADD_APPLICATION_STATUS: &str = "/api/v1/application/status";
+pub const APPLICATION_VIEWS: &str = "/api/v1/application/views";
+pub const APPLICATION_VIEW: &str = "/api/v1/application/views/{id}";
+pub const APPLICATION_COMPANY_SUGGESTIONS: &str = "/api/v1/application/companies/suggestions";
+pub const APPLICATION_DUPLICATES: &str = "/api/v1/application/duplicates";
+pub const APPLICATION_BULK: &str = "/api/v1/application/bulk";
 
 pub const GET_DASHBOARD_STATS: &str = "/api/v1/dashboard/stats";
 pub const GET_SUCCESS_RATE: &str = "/api/v1/dashboard/success-rate";
@@ -0,0 +1,110 @@
+use crate::utils::jwt::JwtToken;
+use crate::utils::token_util::generate_token;
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use std::env::var;
+use time::Duration;
+use tracing::info;
+
+pub const ACCESS_TOKEN_COOKIE: &str = "access_token";
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+pub const CSRF_TOKEN_COOKIE: &str = "csrf_token";
+pub const CSRF_TOKEN_HEADER: &str = "x-csrf-token";
+
+/// The refresh token is only ever needed by the auth endpoints, so it is not sent anywhere else.
+const REFRESH_TOKEN_COOKIE_PATH: &str = "/api/v1/auth";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionMode {
+    /// Tokens are returned in the response body and sent back in the `Authorization` header.
+    Bearer,
+    /// Tokens are set as HttpOnly cookies and never exposed to scripts.
+    Cookie,
+}
+
+#[derive(Clone, Debug)]
+pub struct SessionConfig {
+    pub mode: SessionMode,
+    pub secure: bool,
+    pub same_site: SameSite,
+    pub domain: Option<String>,
+}
+
+impl SessionConfig {
+    /// In cookie mode, moves the tokens into cookies along with a fresh CSRF token, and strips
+    /// them from the body. In bearer mode the token is returned untouched.
+    pub fn issue(&self, jar: CookieJar, mut token: JwtToken) -> (CookieJar, JwtToken) {
+        if self.mode == SessionMode::Bearer {
+            return (jar, token);
+        }
+
+        let csrf_token = generate_token();
+        let access_token = std::mem::take(&mut token.access_token);
+        let refresh_token = std::mem::take(&mut token.refresh_token);
+
+        let jar = jar
+            .add(self.cookie(ACCESS_TOKEN_COOKIE, access_token, "/", token.expires_in, true))
+            .add(self.cookie(REFRESH_TOKEN_COOKIE, refresh_token, REFRESH_TOKEN_COOKIE_PATH, token.refresh_expires_in, true))
+            // Readable by scripts on purpose: the client echoes it in the `X-CSRF-Token` header.
+            .add(self.cookie(CSRF_TOKEN_COOKIE, csrf_token.clone(), "/", token.refresh_expires_in, false));
+
+        token.csrf_token = Some(csrf_token);
+        (jar, token)
+    }
+
+    pub fn clear(&self, jar: CookieJar) -> CookieJar {
+        if self.mode == SessionMode::Bearer {
+            return jar;
+        }
+
+        jar.remove(self.cookie(ACCESS_TOKEN_COOKIE, String::new(), "/", 0, true))
+            .remove(self.cookie(REFRESH_TOKEN_COOKIE, String::new(), REFRESH_TOKEN_COOKIE_PATH, 0, true))
+            .remove(self.cookie(CSRF_TOKEN_COOKIE, String::new(), "/", 0, false))
+    }
+
+    fn cookie(&self, name: &'static str, value: String, path: &'static str, max_age_in_minutes: i64, http_only: bool) -> Cookie<'static> {
+        let mut cookie = Cookie::build((name, value))
+            .path(path)
+            .http_only(http_only)
+            .secure(self.secure)
+            .same_site(self.same_site)
+            .max_age(Duration::minutes(max_age_in_minutes));
+
+        if let Some(domain) = &self.domain {
+            cookie = cookie.domain(domain.clone());
+        }
+
+        cookie.build()
+    }
+}
+
+/// Reads `SESSION_MODE` (`bearer` or `cookie`, defaults to `bearer`). Cookie mode also reads
+/// `SESSION_COOKIE_SECURE` (defaults to `true`), `SESSION_COOKIE_SAME_SITE` (`strict`, `lax`
+/// or `none`, defaults to `lax`) and `SESSION_COOKIE_DOMAIN`.
+pub fn load_session_config() -> SessionConfig {
+    let mode = match var("SESSION_MODE").unwrap_or_default().trim().to_lowercase().as_str() {
+        "" | "bearer" => SessionMode::Bearer,
+        "cookie" => SessionMode::Cookie,
+        other => panic!("SESSION_MODE '{}' is not supported", other),
+    };
+
+    let secure = var("SESSION_COOKIE_SECURE")
+        .ok()
+        .map(|value| value.parse().expect("SESSION_COOKIE_SECURE must be true or false"))
+        .unwrap_or(true);
+
+    let same_site = match var("SESSION_COOKIE_SAME_SITE").unwrap_or_default().trim().to_lowercase().as_str() {
+        "" | "lax" => SameSite::Lax,
+        "strict" => SameSite::Strict,
+        "none" => SameSite::None,
+        other => panic!("SESSION_COOKIE_SAME_SITE '{}' is not supported", other),
+    };
+
+    if same_site == SameSite::None && !secure {
+        panic!("SESSION_COOKIE_SAME_SITE=none requires SESSION_COOKIE_SECURE=true");
+    }
+
+    let domain = var("SESSION_COOKIE_DOMAIN").ok().filter(|domain| !domain.trim().is_empty());
+
+    info!("Session mode: {:?}", mode);
+    SessionConfig { mode, secure, same_site, domain }
+}
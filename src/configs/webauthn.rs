@@ -0,0 +1,30 @@
+use std::env::var;
+use tracing::{info, warn};
+
+#[derive(Clone, Debug)]
+pub struct WebauthnConfig {
+    pub rp_id: String,
+    pub rp_name: String,
+    pub origin: String,
+}
+
+/// Reads `WEBAUTHN_RP_ID`, `WEBAUTHN_ORIGIN` and the optional `WEBAUTHN_RP_NAME` (defaults to
+/// `AppliQ`). Passkeys are disabled when neither the relying party ID nor the origin is set.
+pub fn load_webauthn_config() -> Option<WebauthnConfig> {
+    let value = |key: &str| var(key).ok().map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+
+    let (rp_id, origin) = match (value("WEBAUTHN_RP_ID"), value("WEBAUTHN_ORIGIN")) {
+        (Some(rp_id), Some(origin)) => (rp_id, origin.trim_end_matches('/').to_string()),
+        (None, None) => {
+            warn!("Passkeys are disabled because WEBAUTHN_RP_ID and WEBAUTHN_ORIGIN are not set");
+            return None;
+        }
+        (Some(_), None) => panic!("WEBAUTHN_ORIGIN must be set when WEBAUTHN_RP_ID is set"),
+        (None, Some(_)) => panic!("WEBAUTHN_RP_ID must be set when WEBAUTHN_ORIGIN is set"),
+    };
+
+    let rp_name = value("WEBAUTHN_RP_NAME").unwrap_or_else(|| "AppliQ".to_string());
+
+    info!("Passkeys enabled for relying party '{}' ({})", rp_id, origin);
+    Some(WebauthnConfig { rp_id, rp_name, origin })
+}
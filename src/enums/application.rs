@@ -37,3 +37,75 @@ pub enum ApplicationType {
     Email,
     Website,
 }
+
+/// Columns the application list can be sorted by.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ApplicationSortField {
+    CreatedAt,
+    Company,
+    Position,
+    Status,
+    LastActivity,
+    TimeInStage,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// Columns a saved view can show in the application list.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ApplicationColumn {
+    Company,
+    Position,
+    Website,
+    ApplicationType,
+    Status,
+    CreatedAt,
+    StatusHistory,
+    Tags,
+}
+
+/// Dimensions the application list can return facet counts for.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ApplicationFacet {
+    Status,
+    ApplicationType,
+    Company,
+}
+
+impl ApplicationFacet {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApplicationFacet::Status => "status",
+            ApplicationFacet::ApplicationType => "applicationType",
+            ApplicationFacet::Company => "company",
+        }
+    }
+}
+
+/// What two applications to the same position have in common. Duplicates always share the
+/// company; a shared website is reported in addition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateMatch {
+    Company,
+    Website,
+}
+
+/// What happened to one application of a bulk request.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkOutcome {
+    Updated,
+    /// The action would not change the application, e.g. archiving an archived one.
+    Skipped,
+    /// The application does not exist or belongs to someone else.
+    NotFound,
+}
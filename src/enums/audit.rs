@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Type, Clone, ToSchema, Debug, PartialEq)]
+#[sqlx(type_name = "VARCHAR")]
+pub enum AuditAction {
+    RoleChanged,
+    AccountDeactivated,
+    AccountReactivated,
+    PasswordResetForced,
+    InviteCreated,
+    InviteRevoked,
+    UserRegistered,
+    LoginSucceeded,
+    LoginFailed,
+    Logout,
+    PasswordChanged,
+    PasswordResetRequested,
+    PasswordReset,
+    EmailChangeRequested,
+    EmailChanged,
+    SessionRevoked,
+    OtherSessionsRevoked,
+    AccessTokenCreated,
+    AccessTokenRevoked,
+    AccountDeletionRequested,
+    AccountDeletionCancelled,
+    MfaEnabled,
+    MfaDisabled,
+    MfaVerificationFailed,
+    RecoveryCodeUsed,
+    PasskeyRegistered,
+    PasskeyRevoked,
+    IdentityLinked,
+    IdentityUnlinked,
+}
+
+#[derive(Serialize, Deserialize, Type, Clone, ToSchema, Debug, PartialEq)]
+#[sqlx(type_name = "VARCHAR")]
+pub enum AuditTargetType {
+    User,
+    Invite,
+    Session,
+    AccessToken,
+    Passkey,
+    Identity,
+}
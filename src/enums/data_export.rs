@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Type, Clone, ToSchema, Debug, PartialEq)]
+#[sqlx(type_name = "VARCHAR")]
+pub enum DataExportStatus {
+    Pending,
+    Ready,
+    Failed,
+    Expired,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Derived from the invite timestamps, it is not stored.
+#[derive(Serialize, Deserialize, Clone, ToSchema, Debug, PartialEq)]
+pub enum InviteStatus {
+    Pending,
+    Accepted,
+    Revoked,
+    Expired,
+}
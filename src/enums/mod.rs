@@ -1,2 +1,8 @@
 pub(crate) mod roles;
-pub(crate) mod application;
\ No newline at end of file
+pub(crate) mod application;
+pub(crate) mod passkey;
+pub(crate) mod token_scope;
+pub(crate) mod audit;
+pub(crate) mod preferences;
+pub(crate) mod data_export;
+pub(crate) mod invite;
\ No newline at end of file
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "VARCHAR")]
+pub enum Ceremony {
+    Registration,
+    Authentication,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Clone, Copy, ToSchema, Debug, PartialEq)]
+pub enum TokenScope {
+    #[serde(rename = "read")]
+    Read,
+    #[serde(rename = "applications:write")]
+    ApplicationsWrite,
+    #[serde(rename = "dashboard")]
+    Dashboard,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::ApplicationsWrite => "applications:write",
+            TokenScope::Dashboard => "dashboard",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(TokenScope::Read),
+            "applications:write" => Some(TokenScope::ApplicationsWrite),
+            "dashboard" => Some(TokenScope::Dashboard),
+            _ => None,
+        }
+    }
+}
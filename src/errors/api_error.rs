@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -6,4 +7,7 @@ pub struct ApiError {
     #[serde(rename = "statusCode")]
     pub status_code: u16,
     pub message: String,
+    /// Extra information for some errors, such as the records a conflict was found with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
 }
@@ -2,7 +2,7 @@ use thiserror::Error;
 use http::StatusCode;
 use sqlx::Error;
 use utoipa::ToSchema;
-use validator::ValidationErrors;
+use validator::{ValidationErrors, ValidationErrorsKind};
 use crate::errors::api_error::ApiError;
 
 #[derive(Error, Debug, ToSchema)]
@@ -19,6 +19,10 @@ pub enum AppError {
     #[error("Resource already exists: {0}")]
     ResourceExists(String),
 
+    /// A conflict the client can resolve, with details describing what it conflicts with.
+    #[error("Conflict: {message}")]
+    Conflict { message: String, details: serde_json::Value },
+
     #[error("Invalid request detected: {0}")]
     BadRequest(String),
 
@@ -37,6 +41,9 @@ pub enum AppError {
     #[error("Email error: {0}")]
     EmailError(String),
 
+    #[error("Permission denied: {0}")]
+    Forbidden(String),
+
 }
 
 impl AppError {
@@ -45,42 +52,62 @@ impl AppError {
             AppError::DatabaseError(msg) => ApiError {
                 status_code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
                 message: format!("{}", msg),
+                details: None,
             },
             AppError::ValidationError(msg) => ApiError {
                 status_code: StatusCode::BAD_REQUEST.as_u16(),
                 message: format!("{}", msg),
+                details: None,
             },
             AppError::AuthError(msg) => ApiError {
                 status_code: StatusCode::UNAUTHORIZED.as_u16(),
                 message: format!("{}", msg),
+                details: None,
             },
             AppError::ResourceExists(msg) => ApiError {
                 status_code: StatusCode::CONFLICT.as_u16(),
                 message: format!("{}", msg),
+                details: None,
+            },
+            AppError::Conflict { message, details } => ApiError {
+                status_code: StatusCode::CONFLICT.as_u16(),
+                message: message.clone(),
+                details: Some(details.clone()),
             },
             AppError::BadRequest(msg) => ApiError {
                 status_code: StatusCode::BAD_REQUEST.as_u16(),
                 message: format!("{}", msg),
+                details: None,
             },
             AppError::ResourceNotFound(msg) => ApiError {
                 status_code: StatusCode::NOT_FOUND.as_u16(),
                 message: format!("{}", msg),
+                details: None,
             },
             AppError::InternalServerError(msg) => ApiError {
                 status_code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
                 message: format!("{}", msg),
+                details: None,
             },
             AppError::MissingToken(msg) => ApiError {
                 status_code: StatusCode::FORBIDDEN.as_u16(),
                 message: format!("{}", msg),
+                details: None,
             },
             AppError::InvalidToken(msg) => ApiError {
                 status_code: StatusCode::UNAUTHORIZED.as_u16(),
                 message: format!("{}", msg),
+                details: None,
             },
             AppError::EmailError(msg) => ApiError {
                 status_code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
                 message: format!("{}", msg),
+                details: None,
+            },
+            AppError::Forbidden(msg) => ApiError {
+                status_code: StatusCode::FORBIDDEN.as_u16(),
+                message: msg.clone(),
+                details: None,
             },
         }
     }
@@ -95,10 +122,21 @@ impl From<Error> for AppError {
 
 pub fn extract_validation_errors(errors: &ValidationErrors) -> String {
     errors
-        .field_errors()
+        .errors()
         .values()
-        .flat_map(|errors| {
-            errors.iter().map(|e| e.message.clone().unwrap_or_default())
+        .map(|kind| match kind {
+            ValidationErrorsKind::Field(errors) => errors
+                .iter()
+                .map(|e| e.message.clone().unwrap_or_default().to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            // Nested structs, such as the filter of a saved view.
+            ValidationErrorsKind::Struct(errors) => extract_validation_errors(errors),
+            ValidationErrorsKind::List(errors) => errors
+                .values()
+                .map(|errors| extract_validation_errors(errors))
+                .collect::<Vec<_>>()
+                .join(", "),
         })
         .collect::<Vec<_>>()
         .join(", ")
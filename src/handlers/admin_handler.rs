@@ -0,0 +1,374 @@
+use crate::configs::routes::{
+    ADMIN_AUDIT_LOGS, ADMIN_AUDIT_LOGS_VERIFY, ADMIN_INVITE, ADMIN_INVITES, ADMIN_USERS, ADMIN_USER_DEACTIVATE,
+    ADMIN_USER_FORCE_PASSWORD_RESET, ADMIN_USER_REACTIVATE, ADMIN_USER_ROLE,
+};
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::enums::roles::Role;
+use crate::errors::api_error::ApiError;
+use crate::middlewares::admin_claims_extractor::AdminClaims;
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::payloads::admin::{
+    AdminUserFilter, AdminUserResponse, AuditChainVerificationResponse, AuditLogFilter, AuditLogResponse,
+    ChangeRoleRequest,
+};
+use crate::payloads::invite::{CreateInviteRequest, InviteFilter, InviteResponse};
+use crate::payloads::pagination::Page;
+use crate::services::admin_service::AdminService;
+use crate::utils::api_response::{ApiResponse, EmptyResponse};
+use axum::extract::{OriginalUri, Path, Query, State};
+use axum::Json;
+use axum_macros::debug_handler;
+use http::StatusCode;
+use std::sync::Arc;
+use tracing::error;
+
+pub struct AdminHandler {
+    pub admin_service: Arc<AdminService>,
+}
+
+#[utoipa::path(get, path = ADMIN_USERS, params(
+        ("search" = Option<String>, Query, description = "Search by email, first name or last name"),
+        ("role" = Option<Role>, Query, description = "Filter by role"),
+        ("deactivated" = Option<bool>, Query, description = "Filter by deactivation state"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("size" = Option<i64>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Users retrieved", body = ApiResponse<Page<AdminUserResponse>>),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "List and search users")]
+#[debug_handler]
+pub async fn list_users(
+    State(handler): State<Arc<AdminHandler>>,
+    _admin: AdminClaims,
+    OriginalUri(uri): OriginalUri,
+    Query(filter): Query<AdminUserFilter>,
+) -> Result<(StatusCode, Json<ApiResponse<Page<AdminUserResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.list_users(filter).await {
+        Ok(users) => Ok((StatusCode::OK, Json(ApiResponse::new("Users retrieved.", users.with_links(&uri))))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(patch, path = ADMIN_USER_ROLE, request_body = ChangeRoleRequest,
+    params(
+        ("id" = i64, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, description = "Role changed", body = ApiResponse<AdminUserResponse>),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 404, description = "User not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "Change a user's role")]
+#[debug_handler]
+pub async fn change_user_role(
+    State(handler): State<Arc<AdminHandler>>,
+    AdminClaims(claims): AdminClaims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+    Json(req): Json<ChangeRoleRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<AdminUserResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.change_role(claims.subject, id, req, &client).await {
+        Ok(user) => Ok((StatusCode::OK, Json(ApiResponse::new("Role changed.", user)))),
+        Err(err) => {
+            error!("Failed to change role of user {id}: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = ADMIN_USER_DEACTIVATE,
+    params(
+        ("id" = i64, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, description = "User deactivated", body = ApiResponse<EmptyResponse>),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 404, description = "User not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "Deactivate a user")]
+#[debug_handler]
+pub async fn deactivate_user(
+    State(handler): State<Arc<AdminHandler>>,
+    AdminClaims(claims): AdminClaims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.deactivate_user(claims.subject, id, &client).await {
+        Ok(_) => Ok((StatusCode::OK, Json(ApiResponse::new("User deactivated.", ())))),
+        Err(err) => {
+            error!("Failed to deactivate user {id}: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = ADMIN_USER_REACTIVATE,
+    params(
+        ("id" = i64, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, description = "User reactivated", body = ApiResponse<AdminUserResponse>),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 404, description = "User not found", body = ApiError),
+        (status = 409, description = "Email already used by another active account", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "Reactivate a user")]
+#[debug_handler]
+pub async fn reactivate_user(
+    State(handler): State<Arc<AdminHandler>>,
+    AdminClaims(claims): AdminClaims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<AdminUserResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.reactivate_user(claims.subject, id, &client).await {
+        Ok(user) => Ok((StatusCode::OK, Json(ApiResponse::new("User reactivated.", user)))),
+        Err(err) => {
+            error!("Failed to reactivate user {id}: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = ADMIN_USER_FORCE_PASSWORD_RESET,
+    params(
+        ("id" = i64, Path, description = "User id")
+    ),
+    responses(
+        (status = 200, description = "Password reset forced", body = ApiResponse<EmptyResponse>),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 404, description = "User not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "Force a password reset")]
+#[debug_handler]
+pub async fn force_password_reset(
+    State(handler): State<Arc<AdminHandler>>,
+    AdminClaims(claims): AdminClaims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.force_password_reset(claims.subject, id, &client).await {
+        Ok(_) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Password reset forced. The user has been emailed a reset link.", ())),
+        )),
+        Err(err) => {
+            error!("Failed to force password reset for user {id}: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = ADMIN_AUDIT_LOGS, params(
+        ("actorId" = Option<i64>, Query, description = "Filter by who performed the action"),
+        ("action" = Option<AuditAction>, Query, description = "Filter by action"),
+        ("targetType" = Option<AuditTargetType>, Query, description = "Filter by the kind of record affected"),
+        ("targetId" = Option<i64>, Query, description = "Filter by the affected record"),
+        ("userId" = Option<i64>, Query, description = "Filter by a user, either as actor or as target"),
+        ("ipAddress" = Option<String>, Query, description = "Filter by client IP address"),
+        ("from" = Option<String>, Query, description = "Only entries at or after this time (RFC 3339)"),
+        ("to" = Option<String>, Query, description = "Only entries at or before this time (RFC 3339)"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("size" = Option<i64>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Audit logs retrieved", body = ApiResponse<Page<AuditLogResponse>>),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "List audit logs")]
+#[debug_handler]
+pub async fn list_audit_logs(
+    State(handler): State<Arc<AdminHandler>>,
+    _admin: AdminClaims,
+    OriginalUri(uri): OriginalUri,
+    Query(filter): Query<AuditLogFilter>,
+) -> Result<(StatusCode, Json<ApiResponse<Page<AuditLogResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.list_audit_logs(filter).await {
+        Ok(logs) => Ok((StatusCode::OK, Json(ApiResponse::new("Audit logs retrieved.", logs.with_links(&uri))))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = ADMIN_AUDIT_LOGS_VERIFY,
+    responses(
+        (status = 200, description = "Audit log chain checked", body = ApiResponse<AuditChainVerificationResponse>),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "Verify that the audit log has not been tampered with")]
+#[debug_handler]
+pub async fn verify_audit_logs(
+    State(handler): State<Arc<AdminHandler>>,
+    _admin: AdminClaims,
+) -> Result<(StatusCode, Json<ApiResponse<AuditChainVerificationResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.verify_audit_chain().await {
+        Ok(result) => Ok((StatusCode::OK, Json(ApiResponse::new("Audit log chain checked.", result)))),
+        Err(err) => {
+            error!("Failed to verify audit log chain: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = ADMIN_INVITES, request_body = CreateInviteRequest,
+    responses(
+        (status = 201, description = "Invite created and emailed", body = ApiResponse<InviteResponse>),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 409, description = "An account with this email already exists", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "Invite someone to register")]
+#[debug_handler]
+pub async fn create_invite(
+    State(handler): State<Arc<AdminHandler>>,
+    AdminClaims(claims): AdminClaims,
+    client: ClientInfo,
+    Json(req): Json<CreateInviteRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<InviteResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.create_invite(claims.subject, req, &client).await {
+        Ok(invite) => Ok((StatusCode::CREATED, Json(ApiResponse::new("Invite sent.", invite)))),
+        Err(err) => {
+            error!("Failed to create invite: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = ADMIN_INVITES, params(
+        ("email" = Option<String>, Query, description = "Search by invited email"),
+        ("pending" = Option<bool>, Query, description = "Only pending (true) or only accepted, revoked and expired (false) invites"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("size" = Option<i64>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Invites retrieved", body = ApiResponse<Page<InviteResponse>>),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "List invites")]
+#[debug_handler]
+pub async fn list_invites(
+    State(handler): State<Arc<AdminHandler>>,
+    _admin: AdminClaims,
+    OriginalUri(uri): OriginalUri,
+    Query(filter): Query<InviteFilter>,
+) -> Result<(StatusCode, Json<ApiResponse<Page<InviteResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.list_invites(filter).await {
+        Ok(invites) => Ok((StatusCode::OK, Json(ApiResponse::new("Invites retrieved.", invites.with_links(&uri))))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(delete, path = ADMIN_INVITE,
+    params(
+        ("id" = i64, Path, description = "Invite id")
+    ),
+    responses(
+        (status = 200, description = "Invite revoked", body = ApiResponse<EmptyResponse>),
+        (status = 403, description = "Administrator access is required", body = ApiError),
+        (status = 404, description = "Pending invite not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin Handler",
+    summary = "Revoke an invite")]
+#[debug_handler]
+pub async fn revoke_invite(
+    State(handler): State<Arc<AdminHandler>>,
+    AdminClaims(claims): AdminClaims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.admin_service.revoke_invite(claims.subject, id, &client).await {
+        Ok(_) => Ok((StatusCode::OK, Json(ApiResponse::new("Invite revoked.", ())))),
+        Err(err) => {
+            error!("Failed to revoke invite {id}: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
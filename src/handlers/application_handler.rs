@@ -1,18 +1,22 @@
-use crate::configs::routes::{ADD_APPLICATION, ADD_APPLICATION_STATUS, GET_APPLICATIONS_FOR_USER};
-use crate::enums::application::Status;
+use crate::configs::routes::{
+    ADD_APPLICATION, ADD_APPLICATION_STATUS, APPLICATION_BULK, APPLICATION_COMPANY_SUGGESTIONS,
+    APPLICATION_DUPLICATES, GET_APPLICATIONS_FOR_USER,
+};
 use crate::errors::api_error::ApiError;
 use crate::payloads::application::{
     ApplicationFilter, ApplicationRequest, ApplicationStatusRequest, ApplicationStatusResponse,
-    ApplicationsResponse,
+    ApplicationsResponse, CompanySuggestion, CompanySuggestionQuery, CreatedApplicationResponse,
+    BulkApplicationRequest, BulkApplicationResponse, DuplicateCluster, DuplicateScanQuery,
 };
+use crate::payloads::pagination::Page;
 use crate::services::application_service::ApplicationService;
 use crate::utils::api_response::ApiResponse;
 use crate::utils::jwt::Claims;
-use axum::extract::{Query, State};
+use axum::extract::{OriginalUri, State};
 use axum::Json;
+use axum_extra::extract::Query;
 use axum_macros::debug_handler;
 use http::StatusCode;
-use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct ApplicationHandler {
@@ -21,8 +25,9 @@ pub struct ApplicationHandler {
 
 #[utoipa::path(post, path = ADD_APPLICATION, request_body = ApplicationRequest,
     responses(
-        (status = 201, description = "Application successfully registered", body = ApiResponse<ApplicationsResponse>),
+        (status = 201, description = "Application successfully registered, listing existing companies with a similar name", body = ApiResponse<CreatedApplicationResponse>),
         (status = 400, description = "Invalid request data", body = ApiError),
+        (status = 409, description = "A recent application to the same position exists, listed under details.duplicates", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError)
     ),
     security(
@@ -35,19 +40,20 @@ pub async fn register_application(
     State(handler): State<Arc<ApplicationHandler>>,
     claims: Claims,
     Json(req): Json<ApplicationRequest>,
-) -> Result<(StatusCode, Json<ApiResponse<ApplicationsResponse>>), (StatusCode, Json<ApiError>)> {
+) -> Result<(StatusCode, Json<ApiResponse<CreatedApplicationResponse>>), (StatusCode, Json<ApiError>)> {
     match handler
         .application_service
         .create_application(req, claims.subject)
         .await
     {
-        Ok(application_data) => Ok((
-            StatusCode::CREATED,
-            Json(ApiResponse::new(
-                "Application registered.",
-                application_data,
-            )),
-        )),
+        Ok(application_data) => {
+            let message = if application_data.similar_companies.is_empty() {
+                "Application registered."
+            } else {
+                "Application registered. Similar company names already exist, check for a typo."
+            };
+            Ok((StatusCode::CREATED, Json(ApiResponse::new(message, application_data))))
+        }
 
         Err(err) => {
             let api_error = err.to_api_error();
@@ -100,15 +106,30 @@ pub async fn add_application_status(
 }
 
 #[utoipa::path(get, path = GET_APPLICATIONS_FOR_USER, params(
-        ("search" = Option<String>, Query, description = "Search by company or position"),
-        ("status" = Option<Status>, Query, description = "Filter by application status"),
-        ("from" = Option<DateTime<Utc>>, Query, description = "Filter from this date (inclusive)"),
-        ("to" = Option<DateTime<Utc>>, Query, description = "Filter to this date (inclusive)"),
+        ("search" = Option<String>, Query, description = "Full-text search over company, position, website and status notes, matching word prefixes. Results are ordered by relevance"),
+        ("status" = Option<String>, Query, description = "Current status, comma separated to match any of several, e.g. `Interview,Test`"),
+        ("excludeStatus" = Option<String>, Query, description = "Leave out applications currently in any of these statuses, comma separated"),
+        ("applicationType" = Option<String>, Query, description = "Application type, comma separated, e.g. `Email,Website`"),
+        ("excludeApplicationType" = Option<String>, Query, description = "Leave out these application types, comma separated"),
+        ("company" = Option<Vec<String>>, Query, explode = true, description = "Exact company names (case insensitive), repeat the parameter to match any of several, e.g. `company=Acme, Inc.&company=Globex`"),
+        ("excludeCompany" = Option<Vec<String>>, Query, explode = true, description = "Leave out these companies, one per parameter"),
+        ("tags" = Option<Vec<String>>, Query, explode = true, description = "Has any of these tags, one per parameter"),
+        ("interviewType" = Option<String>, Query, description = "Had an interview of any of these types, comma separated, e.g. `Technical,Hr`"),
+        ("excludeInterviewType" = Option<String>, Query, description = "Never had an interview of any of these types, comma separated"),
+        ("from" = Option<DateTime<Utc>>, Query, description = "Created at or after this date"),
+        ("to" = Option<DateTime<Utc>>, Query, description = "Created at or before this date"),
+        ("lastActivityAfter" = Option<DateTime<Utc>>, Query, description = "Last status change (or creation) at or after this date"),
+        ("lastActivityBefore" = Option<DateTime<Utc>>, Query, description = "Last status change (or creation) at or before this date"),
+        ("appliedWithinDays" = Option<i64>, Query, description = "Created within the last N days (1 to 3650)"),
+        ("sort" = Option<String>, Query, description = "Comma separated sort keys in order of precedence: createdAt, company, position, status, lastActivity, timeInStage, each optionally followed by :asc or :desc, e.g. status,lastActivity:desc"),
+        ("facets" = Option<String>, Query, description = "Also count results per value of these dimensions, comma separated: status, applicationType, company. Each is counted ignoring its own filters"),
+        ("view" = Option<i64>, Query, description = "Saved view to start from; filters set on the request take precedence over the view's"),
+        ("cursor" = Option<String>, Query, description = "Enables cursor pagination: empty for the first page, then nextCursor or prevCursor from the previous response"),
         ("page" = Option<i64>, Query, description = "Page number"),
         ("size" = Option<i64>, Query, description = "Page size")
     ),
     responses(
-        (status = 200, description = "Applications retrieved", body = HashMap<String, serde_json::Value>),
+        (status = 200, description = "Applications retrieved", body = ApiResponse<Page<ApplicationsResponse>>),
         (status = 500, description = "Internal server error", body = ApiError)
     ),
     security(
@@ -120,15 +141,16 @@ pub async fn add_application_status(
 pub async fn fetch_applications_for_user_with_filters(
     State(handler): State<Arc<ApplicationHandler>>,
     claims: Claims,
+    OriginalUri(uri): OriginalUri,
     Query(filter): Query<ApplicationFilter>,
-) -> Result<(StatusCode, Json<ApiResponse<HashMap<String, serde_json::Value>>>), (StatusCode, Json<ApiError>)>
+) -> Result<(StatusCode, Json<ApiResponse<Page<ApplicationsResponse>>>), (StatusCode, Json<ApiError>)>
 {
     match handler
         .application_service
         .fetch_applications_for_user_with_filters(claims.subject, filter)
         .await
     {
-        Ok(applications) => Ok((StatusCode::OK, Json(ApiResponse::new("Applications retrieved", applications)))),
+        Ok(applications) => Ok((StatusCode::OK, Json(ApiResponse::new("Applications retrieved", applications.with_links(&uri))))),
 
         Err(err) => {
             let api_error = err.to_api_error();
@@ -138,3 +160,95 @@ pub async fn fetch_applications_for_user_with_filters(
         }
     }
 }
+
+#[utoipa::path(get, path = APPLICATION_COMPANY_SUGGESTIONS,
+    params(
+        ("search" = String, Query, description = "What has been typed so far"),
+        ("limit" = Option<i64>, Query, description = "Maximum number of suggestions, 10 by default")
+    ),
+    responses(
+        (status = 200, description = "Companies from past applications, best match first", body = ApiResponse<Vec<CompanySuggestion>>),
+        (status = 400, description = "Invalid request data", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Application Handler",
+    summary = "Suggest company names from past applications, tolerating typos")]
+#[debug_handler]
+pub async fn suggest_companies(
+    State(handler): State<Arc<ApplicationHandler>>,
+    claims: Claims,
+    Query(query): Query<CompanySuggestionQuery>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<CompanySuggestion>>>), (StatusCode, Json<ApiError>)> {
+    match handler.application_service.suggest_companies(claims.subject, query).await {
+        Ok(suggestions) => Ok((StatusCode::OK, Json(ApiResponse::new("Company suggestions retrieved.", suggestions)))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = APPLICATION_DUPLICATES,
+    params(
+        ("windowDays" = Option<i32>, Query, description = "Maximum days between two applications for them to count as duplicates, 90 by default")
+    ),
+    responses(
+        (status = 200, description = "Clusters of likely duplicate applications, most recent first", body = ApiResponse<Vec<DuplicateCluster>>),
+        (status = 400, description = "Invalid request data", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Application Handler",
+    summary = "Find applications made more than once to the same role")]
+#[debug_handler]
+pub async fn find_duplicate_applications(
+    State(handler): State<Arc<ApplicationHandler>>,
+    claims: Claims,
+    Query(query): Query<DuplicateScanQuery>,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<DuplicateCluster>>>), (StatusCode, Json<ApiError>)> {
+    match handler.application_service.find_duplicates(claims.subject, query).await {
+        Ok(clusters) => Ok((StatusCode::OK, Json(ApiResponse::new("Duplicate applications retrieved.", clusters)))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = APPLICATION_BULK, request_body = BulkApplicationRequest,
+    responses(
+        (status = 200, description = "Action applied in one transaction, with the outcome for each application", body = ApiResponse<BulkApplicationResponse>),
+        (status = 400, description = "Invalid request data, or a filter matching too many applications", body = ApiError),
+        (status = 404, description = "Saved view not found", body = ApiError),
+        (status = 500, description = "Internal server error, nothing was changed", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Application Handler",
+    summary = "Add a status to, delete, restore, tag, untag, archive or unarchive many applications at once")]
+#[debug_handler]
+pub async fn bulk_update_applications(
+    State(handler): State<Arc<ApplicationHandler>>,
+    claims: Claims,
+    Json(req): Json<BulkApplicationRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<BulkApplicationResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.application_service.bulk_update(claims.subject, req).await {
+        Ok(response) => Ok((StatusCode::OK, Json(ApiResponse::new("Bulk action applied.", response)))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
@@ -1,23 +1,30 @@
-use crate::configs::routes::{FORGOT_PASSWORD, LOGIN, LOGOUT, RESET_PASSWORD};
+use crate::configs::routes::{CHANGE_EMAIL, CHANGE_PASSWORD, CONFIRM_EMAIL_CHANGE, FORGOT_PASSWORD, LOGIN, LOGOUT, PASSWORD_STRENGTH, REFRESH, RESET_PASSWORD};
+use crate::configs::session::{SessionConfig, REFRESH_TOKEN_COOKIE};
 use crate::errors::api_error::ApiError;
-use crate::payloads::auth::{LoginRequest, ForgotPasswordRequest, ResetPasswordRequest};
+use crate::errors::app_error::AppError;
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::middlewares::jwt_claims_extractor::bearer_token;
+use crate::payloads::auth::{ChangeEmailRequest, ChangePasswordRequest, ConfirmEmailChangeRequest, LoginRequest, LoginResponse, ForgotPasswordRequest, ResetPasswordRequest};
+use crate::payloads::password::{PasswordStrengthRequest, PasswordStrengthResponse};
 use crate::services::auth_service::AuthService;
 use crate::utils::api_response::{ApiResponse, EmptyResponse};
 use crate::utils::jwt::{Claims, JwtToken};
 use axum::Json;
 use axum::extract::State;
-use http::StatusCode;
+use axum_extra::extract::cookie::CookieJar;
+use http::{HeaderMap, StatusCode};
 use std::sync::Arc;
 use axum_macros::debug_handler;
 use tracing::error;
 
 pub struct AuthHandler {
     pub auth_service: Arc<AuthService>,
+    pub session_config: Arc<SessionConfig>,
 }
 
 #[utoipa::path(post, path = LOGIN, request_body = LoginRequest,
     responses(
-        (status = 200, description = "Login successful", body = ApiResponse<JwtToken>),
+        (status = 200, description = "Login successful, or an MFA challenge when a second factor is required", body = ApiResponse<LoginResponse>),
         (status = 400, description = "Invalid credentials", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError)
     ),
@@ -25,13 +32,24 @@ pub struct AuthHandler {
     summary = "Login user")]
 pub async fn login(
     State(handler): State<Arc<AuthHandler>>,
+    jar: CookieJar,
+    client: ClientInfo,
     Json(req): Json<LoginRequest>,
-) -> Result<(StatusCode, Json<ApiResponse<JwtToken>>), (StatusCode, Json<ApiError>)> {
-    match handler.auth_service.login(req).await {
-        Ok(token) => Ok((
+) -> Result<(StatusCode, CookieJar, Json<ApiResponse<LoginResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.auth_service.login(req, &client).await {
+        Ok(LoginResponse::MfaRequired(challenge)) => Ok((
             StatusCode::OK,
-            Json(ApiResponse::new("Login successful.", token)),
+            jar,
+            Json(ApiResponse::new("MFA verification required.", LoginResponse::MfaRequired(challenge))),
         )),
+        Ok(LoginResponse::Authenticated(token)) => {
+            let (jar, token) = handler.session_config.issue(jar, token);
+            Ok((
+                StatusCode::OK,
+                jar,
+                Json(ApiResponse::new("Login successful.", LoginResponse::Authenticated(token))),
+            ))
+        }
 
         Err(err) => {
             error!("Failed to Login user: {err}");
@@ -44,6 +62,52 @@ pub async fn login(
     }
 }
 
+#[utoipa::path(post, path = REFRESH,
+    responses(
+        (status = 200, description = "New tokens for the session", body = ApiResponse<JwtToken>),
+        (status = 401, description = "Refresh token is invalid, expired or its session was revoked", body = ApiError),
+        (status = 403, description = "Missing refresh token, or missing CSRF token in cookie mode", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Auth Handler",
+    summary = "Exchange a refresh token for new tokens",
+    description = "Reads the refresh token from the `Authorization: Bearer` header if there is one, otherwise from the `refresh_token` cookie in cookie mode.")]
+pub async fn refresh(
+    State(handler): State<Arc<AuthHandler>>,
+    jar: CookieJar,
+    headers: HeaderMap,
+) -> Result<(StatusCode, CookieJar, Json<ApiResponse<JwtToken>>), (StatusCode, Json<ApiError>)> {
+    // Like the `Claims` extractor, an `Authorization` header rules out the cookie.
+    let refresh_token = match bearer_token(&headers) {
+        Some(token) => token.map(str::to_string),
+        None => jar
+            .get(REFRESH_TOKEN_COOKIE)
+            .map(|cookie| cookie.value().to_string())
+            .ok_or_else(|| AppError::MissingToken("Refresh token is missing.".into())),
+    };
+
+    let result = match refresh_token {
+        Ok(refresh_token) => handler.auth_service.refresh(&refresh_token).await,
+        Err(err) => Err(err),
+    };
+
+    match result {
+        Ok(token) => {
+            let (jar, token) = handler.session_config.issue(jar, token);
+            Ok((StatusCode::OK, jar, Json(ApiResponse::new("Tokens refreshed.", token))))
+        }
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
 #[utoipa::path(post, path = FORGOT_PASSWORD, request_body = ForgotPasswordRequest,
     responses(
         (status = 200, description = "Password reset instructions sent", body = ApiResponse<EmptyResponse>),
@@ -55,9 +119,10 @@ pub async fn login(
 #[debug_handler]
 pub async fn forgot_password(
     State(handler): State<Arc<AuthHandler>>,
+    client: ClientInfo,
     Json(req): Json<ForgotPasswordRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
-    match handler.auth_service.forgot_password(req).await {
+    match handler.auth_service.forgot_password(req, &client).await {
         Ok(_) => Ok((
             StatusCode::OK,
             Json(ApiResponse::new(
@@ -85,9 +150,10 @@ pub async fn forgot_password(
 #[debug_handler]
 pub async fn reset_password(
     State(handler): State<Arc<AuthHandler>>,
+    client: ClientInfo,
     Json(req): Json<ResetPasswordRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
-    match handler.auth_service.reset_password(req).await {
+    match handler.auth_service.reset_password(req, &client).await {
         Ok(_) => Ok((
             StatusCode::OK,
             Json(ApiResponse::new("Password has been reset successfully.", ())),
@@ -101,6 +167,32 @@ pub async fn reset_password(
     }
 }
 
+#[utoipa::path(post, path = PASSWORD_STRENGTH, request_body = PasswordStrengthRequest,
+    responses(
+        (status = 200, description = "Password strength estimated", body = ApiResponse<PasswordStrengthResponse>),
+        (status = 400, description = "Invalid request data", body = ApiError)
+    ),
+    tag = "Auth Handler",
+    summary = "Estimate password strength against the password policy")]
+#[debug_handler]
+pub async fn check_password_strength(
+    State(handler): State<Arc<AuthHandler>>,
+    Json(req): Json<PasswordStrengthRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<PasswordStrengthResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.auth_service.check_password_strength(req) {
+        Ok(strength) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Password strength estimated.", strength)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
 #[utoipa::path(post, path = LOGOUT,
     responses(
         (status = 200, description = "Logout successful", body = ApiResponse<EmptyResponse>),
@@ -114,11 +206,14 @@ pub async fn reset_password(
 #[debug_handler]
 pub async fn logout(
     State(handler): State<Arc<AuthHandler>>,
+    jar: CookieJar,
+    client: ClientInfo,
     claims: Claims,
-) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
-    match handler.auth_service.logout(claims.subject).await {
+) -> Result<(StatusCode, CookieJar, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.auth_service.logout(claims.subject, claims.session_id, &client).await {
         Ok(_) => Ok((
             StatusCode::OK,
+            handler.session_config.clear(jar),
             Json(ApiResponse::new("Logout successful.", ())),
         )),
         Err(err) => {
@@ -129,3 +224,102 @@ pub async fn logout(
         }
     }
 }
+
+#[utoipa::path(post, path = CHANGE_PASSWORD, request_body = ChangePasswordRequest,
+    responses(
+        (status = 200, description = "Password changed and other sessions revoked", body = ApiResponse<JwtToken>),
+        (status = 400, description = "Invalid request data or incorrect current password", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Auth Handler",
+    summary = "Change password")]
+#[debug_handler]
+pub async fn change_password(
+    State(handler): State<Arc<AuthHandler>>,
+    jar: CookieJar,
+    client: ClientInfo,
+    claims: Claims,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ApiResponse<JwtToken>>), (StatusCode, Json<ApiError>)> {
+    match handler.auth_service.change_password(claims.subject, claims.session_id, req, &client).await {
+        Ok(token) => {
+            let (jar, token) = handler.session_config.issue(jar, token);
+            Ok((
+                StatusCode::OK,
+                jar,
+                Json(ApiResponse::new("Password changed successfully. Other sessions have been signed out.", token)),
+            ))
+        }
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = CHANGE_EMAIL, request_body = ChangeEmailRequest,
+    responses(
+        (status = 200, description = "Confirmation email sent to the new address", body = ApiResponse<EmptyResponse>),
+        (status = 400, description = "Invalid request data or incorrect current password", body = ApiError),
+        (status = 409, description = "Email already in use", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Auth Handler",
+    summary = "Request an email change")]
+#[debug_handler]
+pub async fn change_email(
+    State(handler): State<Arc<AuthHandler>>,
+    client: ClientInfo,
+    claims: Claims,
+    Json(req): Json<ChangeEmailRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.auth_service.change_email(claims.subject, req, &client).await {
+        Ok(_) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("A confirmation link has been sent to your new email address.", ())),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = CONFIRM_EMAIL_CHANGE, request_body = ConfirmEmailChangeRequest,
+    responses(
+        (status = 200, description = "Email changed", body = ApiResponse<EmptyResponse>),
+        (status = 400, description = "Invalid or expired token", body = ApiError),
+        (status = 409, description = "Email already in use", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    tag = "Auth Handler",
+    summary = "Confirm an email change")]
+#[debug_handler]
+pub async fn confirm_email_change(
+    State(handler): State<Arc<AuthHandler>>,
+    client: ClientInfo,
+    Json(req): Json<ConfirmEmailChangeRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.auth_service.confirm_email_change(req, &client).await {
+        Ok(_) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Your email address has been changed.", ())),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::configs::routes::{USER_EXPORTS, USER_EXPORT_DOWNLOAD};
+use crate::errors::api_error::ApiError;
+use crate::payloads::data_export::{DataExportResponse, DownloadDataExportRequest};
+use crate::services::data_export_service::DataExportService;
+use crate::utils::api_response::ApiResponse;
+use crate::utils::jwt::Claims;
+use axum::extract::{Query, State};
+use axum::Json;
+use axum_macros::debug_handler;
+use http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use http::{HeaderName, StatusCode};
+use std::sync::Arc;
+use tracing::error;
+
+pub struct DataExportHandler {
+    pub data_export_service: Arc<DataExportService>,
+}
+
+#[utoipa::path(post, path = USER_EXPORTS,
+    responses(
+        (status = 202, description = "Export requested, a download link is emailed once it is ready", body = ApiResponse<DataExportResponse>),
+        (status = 404, description = "User not found", body = ApiError),
+        (status = 409, description = "An export is already being prepared", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Data Export Handler",
+    summary = "Request an export of all personal data")]
+#[debug_handler]
+pub async fn request_data_export(
+    State(handler): State<Arc<DataExportHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<DataExportResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.data_export_service.request_export(claims.subject).await {
+        Ok(export) => Ok((
+            StatusCode::ACCEPTED,
+            Json(ApiResponse::new("Export requested. You will receive an email once it is ready.", export)),
+        )),
+        Err(err) => {
+            error!("Failed to request data export: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = USER_EXPORTS,
+    responses(
+        (status = 200, description = "Data exports retrieved", body = ApiResponse<Vec<DataExportResponse>>),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Data Export Handler",
+    summary = "List requested data exports")]
+#[debug_handler]
+pub async fn list_data_exports(
+    State(handler): State<Arc<DataExportHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<DataExportResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.data_export_service.list_exports(claims.subject).await {
+        Ok(exports) => Ok((StatusCode::OK, Json(ApiResponse::new("Data exports retrieved.", exports)))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = USER_EXPORT_DOWNLOAD,
+    params(
+        ("token" = String, Query, description = "Download token from the notification email")
+    ),
+    responses(
+        (status = 200, description = "Zip archive with the exported data", content_type = "application/zip", body = Vec<u8>),
+        (status = 401, description = "Download link is invalid or has expired", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    tag = "Data Export Handler",
+    summary = "Download a data export")]
+#[debug_handler]
+pub async fn download_data_export(
+    State(handler): State<Arc<DataExportHandler>>,
+    Query(req): Query<DownloadDataExportRequest>,
+) -> Result<(StatusCode, [(HeaderName, String); 2], Vec<u8>), (StatusCode, Json<ApiError>)> {
+    match handler.data_export_service.download(&req.token).await {
+        Ok((file_name, archive)) => Ok((
+            StatusCode::OK,
+            [
+                (CONTENT_TYPE, "application/zip".to_string()),
+                (CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+            ],
+            archive,
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::configs::routes::{MFA_CONFIRM, MFA_DISABLE, MFA_ENROLL, MFA_VERIFY};
+use crate::configs::session::SessionConfig;
+use crate::errors::api_error::ApiError;
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::payloads::mfa::{
+    MfaConfirmRequest, MfaDisableRequest, MfaEnrollmentResponse, MfaVerifyRequest,
+    RecoveryCodesResponse,
+};
+use crate::services::mfa_service::MfaService;
+use crate::utils::api_response::{ApiResponse, EmptyResponse};
+use crate::utils::jwt::{Claims, JwtToken};
+use axum::extract::State;
+use axum::Json;
+use axum_extra::extract::cookie::CookieJar;
+use axum_macros::debug_handler;
+use http::StatusCode;
+use std::sync::Arc;
+use tracing::error;
+
+pub struct MfaHandler {
+    pub mfa_service: Arc<MfaService>,
+    pub session_config: Arc<SessionConfig>,
+}
+
+#[utoipa::path(post, path = MFA_ENROLL,
+    responses(
+        (status = 200, description = "MFA enrollment started", body = ApiResponse<MfaEnrollmentResponse>),
+        (status = 400, description = "MFA is already enabled", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "MFA Handler",
+    summary = "Start TOTP enrollment and return the secret, otpauth URI and QR code")]
+#[debug_handler]
+pub async fn enroll_mfa(
+    State(handler): State<Arc<MfaHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<MfaEnrollmentResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.mfa_service.enroll(claims.subject).await {
+        Ok(enrollment) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("MFA enrollment started.", enrollment)),
+        )),
+        Err(err) => {
+            error!("Failed to start MFA enrollment: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = MFA_CONFIRM, request_body = MfaConfirmRequest,
+    responses(
+        (status = 200, description = "MFA enabled, recovery codes returned once", body = ApiResponse<RecoveryCodesResponse>),
+        (status = 400, description = "Invalid code or no pending enrollment", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "MFA Handler",
+    summary = "Confirm TOTP enrollment with a code from the authenticator app")]
+#[debug_handler]
+pub async fn confirm_mfa(
+    State(handler): State<Arc<MfaHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Json(req): Json<MfaConfirmRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<RecoveryCodesResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.mfa_service.confirm(claims.subject, req, &client).await {
+        Ok(recovery_codes) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("MFA enabled.", recovery_codes)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = MFA_VERIFY, request_body = MfaVerifyRequest,
+    responses(
+        (status = 200, description = "Login successful", body = ApiResponse<JwtToken>),
+        (status = 401, description = "Invalid code or challenge token", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    tag = "MFA Handler",
+    summary = "Complete an MFA login with a TOTP code or a recovery code")]
+#[debug_handler]
+pub async fn verify_mfa(
+    State(handler): State<Arc<MfaHandler>>,
+    jar: CookieJar,
+    client: ClientInfo,
+    Json(req): Json<MfaVerifyRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ApiResponse<JwtToken>>), (StatusCode, Json<ApiError>)> {
+    match handler.mfa_service.verify(req, &client).await {
+        Ok(token) => {
+            let (jar, token) = handler.session_config.issue(jar, token);
+            Ok((StatusCode::OK, jar, Json(ApiResponse::new("Login successful.", token))))
+        }
+        Err(err) => {
+            error!("Failed to verify MFA challenge: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = MFA_DISABLE, request_body = MfaDisableRequest,
+    responses(
+        (status = 200, description = "MFA disabled", body = ApiResponse<EmptyResponse>),
+        (status = 401, description = "Password is incorrect", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "MFA Handler",
+    summary = "Disable MFA after re-entering the account password")]
+#[debug_handler]
+pub async fn disable_mfa(
+    State(handler): State<Arc<MfaHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Json(req): Json<MfaDisableRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.mfa_service.disable(claims.subject, req, &client).await {
+        Ok(_) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("MFA disabled.", ())),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
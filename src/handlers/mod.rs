@@ -2,3 +2,12 @@ pub(crate) mod user_handler;
 pub(crate) mod auth_handler;
 pub(crate) mod application_handler;
 pub(crate) mod dashboard_handler;
+pub(crate) mod mfa_handler;
+pub(crate) mod passkey_handler;
+
+pub(crate) mod oidc_handler;
+pub(crate) mod personal_access_token_handler;
+pub(crate) mod admin_handler;
+pub(crate) mod data_export_handler;
+pub(crate) mod session_handler;
+pub(crate) mod saved_view_handler;
\ No newline at end of file
@@ -0,0 +1,174 @@
+use crate::configs::routes::{OIDC_AUTHORIZE, OIDC_CALLBACK, OIDC_PROVIDERS, USER_IDENTITIES, USER_IDENTITY};
+use crate::configs::session::SessionConfig;
+use crate::errors::api_error::ApiError;
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::payloads::oidc::{
+    IdentityResponse, OidcAuthorizationResponse, OidcAuthorizeRequest, OidcCallbackRequest, OidcProviderResponse,
+};
+use crate::services::oidc_service::OidcService;
+use crate::utils::api_response::{ApiResponse, EmptyResponse};
+use crate::utils::jwt::{Claims, JwtToken};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use axum_extra::extract::cookie::CookieJar;
+use axum_macros::debug_handler;
+use http::StatusCode;
+use std::sync::Arc;
+use tracing::error;
+
+pub struct OidcHandler {
+    pub oidc_service: Arc<OidcService>,
+    pub session_config: Arc<SessionConfig>,
+}
+
+#[utoipa::path(get, path = OIDC_PROVIDERS,
+    responses(
+        (status = 200, description = "Configured identity providers", body = ApiResponse<Vec<OidcProviderResponse>>)
+    ),
+    tag = "OIDC Handler",
+    summary = "List single sign-on providers")]
+#[debug_handler]
+pub async fn list_oidc_providers(
+    State(handler): State<Arc<OidcHandler>>,
+) -> (StatusCode, Json<ApiResponse<Vec<OidcProviderResponse>>>) {
+    (
+        StatusCode::OK,
+        Json(ApiResponse::new(
+            "Identity providers fetched.",
+            handler.oidc_service.list_providers(),
+        )),
+    )
+}
+
+#[utoipa::path(get, path = OIDC_AUTHORIZE,
+    params(
+        ("provider" = String, Path, description = "Identity provider name"),
+        ("rememberMe" = Option<bool>, Query, description = "Issue a long-lived token after sign-in")
+    ),
+    responses(
+        (status = 200, description = "Authorization URL created", body = ApiResponse<OidcAuthorizationResponse>),
+        (status = 404, description = "Identity provider not found", body = ApiError),
+        (status = 500, description = "Identity provider unavailable", body = ApiError)
+    ),
+    tag = "OIDC Handler",
+    summary = "Start single sign-on")]
+#[debug_handler]
+pub async fn oidc_authorize(
+    State(handler): State<Arc<OidcHandler>>,
+    Path(provider): Path<String>,
+    Query(req): Query<OidcAuthorizeRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<OidcAuthorizationResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.oidc_service.authorize(&provider, req).await {
+        Ok(authorization) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Authorization URL created.", authorization)),
+        )),
+        Err(err) => {
+            error!("Failed to start OIDC login with '{provider}': {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = OIDC_CALLBACK, request_body = OidcCallbackRequest,
+    params(
+        ("provider" = String, Path, description = "Identity provider name")
+    ),
+    responses(
+        (status = 200, description = "Signed in", body = ApiResponse<JwtToken>),
+        (status = 400, description = "Invalid or expired login state", body = ApiError),
+        (status = 401, description = "Identity could not be verified", body = ApiError),
+        (status = 404, description = "Identity provider not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    tag = "OIDC Handler",
+    summary = "Finish single sign-on")]
+#[debug_handler]
+pub async fn oidc_callback(
+    State(handler): State<Arc<OidcHandler>>,
+    Path(provider): Path<String>,
+    jar: CookieJar,
+    client: ClientInfo,
+    Json(req): Json<OidcCallbackRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ApiResponse<JwtToken>>), (StatusCode, Json<ApiError>)> {
+    match handler.oidc_service.callback(&provider, req, &client).await {
+        Ok(token) => {
+            let (jar, token) = handler.session_config.issue(jar, token);
+            Ok((StatusCode::OK, jar, Json(ApiResponse::new("Login successful.", token))))
+        }
+        Err(err) => {
+            error!("OIDC login with '{provider}' failed: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = USER_IDENTITIES,
+    responses(
+        (status = 200, description = "Linked identities retrieved", body = ApiResponse<Vec<IdentityResponse>>),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "OIDC Handler",
+    summary = "List the identity provider accounts linked to the authenticated user")]
+#[debug_handler]
+pub async fn list_identities(
+    State(handler): State<Arc<OidcHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<IdentityResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.oidc_service.list_identities(claims.subject).await {
+        Ok(identities) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Identities retrieved.", identities)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(delete, path = USER_IDENTITY,
+    params(
+        ("id" = i64, Path, description = "Identity id")
+    ),
+    responses(
+        (status = 200, description = "Identity unlinked", body = ApiResponse<EmptyResponse>),
+        (status = 404, description = "Identity not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "OIDC Handler",
+    summary = "Unlink an identity provider account")]
+#[debug_handler]
+pub async fn unlink_identity(
+    State(handler): State<Arc<OidcHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.oidc_service.unlink_identity(claims.subject, id, &client).await {
+        Ok(_) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Identity unlinked.", ())),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
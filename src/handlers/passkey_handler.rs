@@ -0,0 +1,247 @@
+use crate::configs::routes::{
+    PASSKEY_LOGIN, PASSKEY_LOGIN_OPTIONS, PASSKEY_REGISTER, PASSKEY_REGISTER_OPTIONS, USER_PASSKEY,
+    USER_PASSKEYS,
+};
+use crate::configs::session::SessionConfig;
+use crate::errors::api_error::ApiError;
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::payloads::passkey::{
+    PasskeyLoginOptions, PasskeyLoginOptionsRequest, PasskeyLoginRequest,
+    PasskeyRegistrationOptions, PasskeyRegistrationRequest, PasskeyResponse, RenamePasskeyRequest,
+};
+use crate::services::passkey_service::PasskeyService;
+use crate::utils::api_response::{ApiResponse, EmptyResponse};
+use crate::utils::jwt::{Claims, JwtToken};
+use axum::extract::{Path, State};
+use axum::Json;
+use axum_extra::extract::cookie::CookieJar;
+use axum_macros::debug_handler;
+use http::StatusCode;
+use std::sync::Arc;
+use tracing::error;
+
+pub struct PasskeyHandler {
+    pub passkey_service: Arc<PasskeyService>,
+    pub session_config: Arc<SessionConfig>,
+}
+
+#[utoipa::path(post, path = PASSKEY_REGISTER_OPTIONS,
+    responses(
+        (status = 200, description = "Registration options created", body = ApiResponse<PasskeyRegistrationOptions>),
+        (status = 404, description = "User not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Passkey Handler",
+    summary = "Start passkey registration")]
+#[debug_handler]
+pub async fn passkey_registration_options(
+    State(handler): State<Arc<PasskeyHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<PasskeyRegistrationOptions>>), (StatusCode, Json<ApiError>)> {
+    match handler.passkey_service.registration_options(claims.subject).await {
+        Ok(options) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Registration options created.", options)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = PASSKEY_REGISTER, request_body = PasskeyRegistrationRequest,
+    responses(
+        (status = 201, description = "Passkey registered", body = ApiResponse<PasskeyResponse>),
+        (status = 400, description = "Invalid attestation or challenge", body = ApiError),
+        (status = 409, description = "Passkey already registered", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Passkey Handler",
+    summary = "Finish passkey registration")]
+#[debug_handler]
+pub async fn register_passkey(
+    State(handler): State<Arc<PasskeyHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Json(req): Json<PasskeyRegistrationRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<PasskeyResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.passkey_service.register(claims.subject, req, &client).await {
+        Ok(passkey) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::new("Passkey registered.", passkey)),
+        )),
+        Err(err) => {
+            error!("Failed to register passkey: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = PASSKEY_LOGIN_OPTIONS, request_body = PasskeyLoginOptionsRequest,
+    responses(
+        (status = 200, description = "Authentication options created", body = ApiResponse<PasskeyLoginOptions>),
+        (status = 400, description = "Invalid request data", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    tag = "Passkey Handler",
+    summary = "Start passkey sign-in")]
+#[debug_handler]
+pub async fn passkey_login_options(
+    State(handler): State<Arc<PasskeyHandler>>,
+    Json(req): Json<PasskeyLoginOptionsRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<PasskeyLoginOptions>>), (StatusCode, Json<ApiError>)> {
+    match handler.passkey_service.login_options(req).await {
+        Ok(options) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Authentication options created.", options)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = PASSKEY_LOGIN, request_body = PasskeyLoginRequest,
+    responses(
+        (status = 200, description = "Login successful", body = ApiResponse<JwtToken>),
+        (status = 400, description = "Invalid assertion or challenge", body = ApiError),
+        (status = 401, description = "Passkey authentication failed", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    tag = "Passkey Handler",
+    summary = "Finish passkey sign-in")]
+#[debug_handler]
+pub async fn login_with_passkey(
+    State(handler): State<Arc<PasskeyHandler>>,
+    jar: CookieJar,
+    client: ClientInfo,
+    Json(req): Json<PasskeyLoginRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ApiResponse<JwtToken>>), (StatusCode, Json<ApiError>)> {
+    match handler.passkey_service.login(req, &client).await {
+        Ok(token) => {
+            let (jar, token) = handler.session_config.issue(jar, token);
+            Ok((StatusCode::OK, jar, Json(ApiResponse::new("Login successful.", token))))
+        }
+        Err(err) => {
+            error!("Failed to login with passkey: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = USER_PASSKEYS,
+    responses(
+        (status = 200, description = "Passkeys retrieved", body = ApiResponse<Vec<PasskeyResponse>>),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Passkey Handler",
+    summary = "List the authenticated user's passkeys")]
+#[debug_handler]
+pub async fn list_passkeys(
+    State(handler): State<Arc<PasskeyHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<PasskeyResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.passkey_service.list_passkeys(claims.subject).await {
+        Ok(passkeys) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Passkeys retrieved.", passkeys)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(patch, path = USER_PASSKEY, request_body = RenamePasskeyRequest,
+    params(
+        ("id" = i64, Path, description = "Passkey id")
+    ),
+    responses(
+        (status = 200, description = "Passkey renamed", body = ApiResponse<PasskeyResponse>),
+        (status = 404, description = "Passkey not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Passkey Handler",
+    summary = "Rename a passkey")]
+#[debug_handler]
+pub async fn rename_passkey(
+    State(handler): State<Arc<PasskeyHandler>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+    Json(req): Json<RenamePasskeyRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<PasskeyResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.passkey_service.rename_passkey(claims.subject, id, req).await {
+        Ok(passkey) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Passkey renamed.", passkey)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(delete, path = USER_PASSKEY,
+    params(
+        ("id" = i64, Path, description = "Passkey id")
+    ),
+    responses(
+        (status = 200, description = "Passkey revoked", body = ApiResponse<EmptyResponse>),
+        (status = 404, description = "Passkey not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Passkey Handler",
+    summary = "Revoke a passkey")]
+#[debug_handler]
+pub async fn revoke_passkey(
+    State(handler): State<Arc<PasskeyHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.passkey_service.revoke_passkey(claims.subject, id, &client).await {
+        Ok(_) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Passkey revoked.", ())),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
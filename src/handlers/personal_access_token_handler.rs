@@ -0,0 +1,116 @@
+use crate::configs::routes::{USER_TOKEN, USER_TOKENS};
+use crate::errors::api_error::ApiError;
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::payloads::personal_access_token::{
+    CreatePersonalAccessTokenRequest, CreatedPersonalAccessTokenResponse, PersonalAccessTokenResponse,
+};
+use crate::services::personal_access_token_service::PersonalAccessTokenService;
+use crate::utils::api_response::{ApiResponse, EmptyResponse};
+use crate::utils::jwt::Claims;
+use axum::extract::{Path, State};
+use axum::Json;
+use axum_macros::debug_handler;
+use http::StatusCode;
+use std::sync::Arc;
+use tracing::error;
+
+pub struct PersonalAccessTokenHandler {
+    pub token_service: Arc<PersonalAccessTokenService>,
+}
+
+#[utoipa::path(post, path = USER_TOKENS, request_body = CreatePersonalAccessTokenRequest,
+    responses(
+        (status = 201, description = "Personal access token created", body = ApiResponse<CreatedPersonalAccessTokenResponse>),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Personal Access Token Handler",
+    summary = "Create a personal access token")]
+#[debug_handler]
+pub async fn create_personal_access_token(
+    State(handler): State<Arc<PersonalAccessTokenHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Json(req): Json<CreatePersonalAccessTokenRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<CreatedPersonalAccessTokenResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.token_service.create(claims.subject, req, &client).await {
+        Ok(token) => Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::new("Personal access token created. Copy it now, it will not be shown again.", token)),
+        )),
+        Err(err) => {
+            error!("Failed to create personal access token: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = USER_TOKENS,
+    responses(
+        (status = 200, description = "Personal access tokens retrieved", body = ApiResponse<Vec<PersonalAccessTokenResponse>>),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Personal Access Token Handler",
+    summary = "List personal access tokens")]
+#[debug_handler]
+pub async fn list_personal_access_tokens(
+    State(handler): State<Arc<PersonalAccessTokenHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<PersonalAccessTokenResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.token_service.list(claims.subject).await {
+        Ok(tokens) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Personal access tokens retrieved.", tokens)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(delete, path = USER_TOKEN,
+    params(
+        ("id" = i64, Path, description = "Personal access token id")
+    ),
+    responses(
+        (status = 200, description = "Personal access token revoked", body = ApiResponse<EmptyResponse>),
+        (status = 404, description = "Personal access token not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Personal Access Token Handler",
+    summary = "Revoke a personal access token")]
+#[debug_handler]
+pub async fn revoke_personal_access_token(
+    State(handler): State<Arc<PersonalAccessTokenHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.token_service.revoke(claims.subject, id, &client).await {
+        Ok(_) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Personal access token revoked.", ())),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
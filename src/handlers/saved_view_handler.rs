@@ -0,0 +1,166 @@
+use crate::configs::routes::{APPLICATION_VIEW, APPLICATION_VIEWS};
+use crate::errors::api_error::ApiError;
+use crate::payloads::saved_view::{CreateSavedViewRequest, SavedViewResponse, UpdateSavedViewRequest};
+use crate::services::saved_view_service::SavedViewService;
+use crate::utils::api_response::{ApiResponse, EmptyResponse};
+use crate::utils::jwt::Claims;
+use axum::extract::{Path, State};
+use axum::Json;
+use axum_macros::debug_handler;
+use http::StatusCode;
+use std::sync::Arc;
+
+pub struct SavedViewHandler {
+    pub saved_view_service: Arc<SavedViewService>,
+}
+
+#[utoipa::path(post, path = APPLICATION_VIEWS, request_body = CreateSavedViewRequest,
+    responses(
+        (status = 201, description = "Saved view created", body = ApiResponse<SavedViewResponse>),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 409, description = "A saved view with this name already exists", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Saved View Handler",
+    summary = "Save a combination of application filters, sort and columns as a view")]
+#[debug_handler]
+pub async fn create_saved_view(
+    State(handler): State<Arc<SavedViewHandler>>,
+    claims: Claims,
+    Json(req): Json<CreateSavedViewRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<SavedViewResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.saved_view_service.create(claims.subject, req).await {
+        Ok(view) => Ok((StatusCode::CREATED, Json(ApiResponse::new("Saved view created.", view)))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = APPLICATION_VIEWS,
+    responses(
+        (status = 200, description = "Saved views retrieved, with live counts for views that show one", body = ApiResponse<Vec<SavedViewResponse>>),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Saved View Handler",
+    summary = "List saved views")]
+#[debug_handler]
+pub async fn list_saved_views(
+    State(handler): State<Arc<SavedViewHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<SavedViewResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.saved_view_service.list(claims.subject).await {
+        Ok(views) => Ok((StatusCode::OK, Json(ApiResponse::new("Saved views retrieved.", views)))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = APPLICATION_VIEW,
+    params(
+        ("id" = i64, Path, description = "Saved view id")
+    ),
+    responses(
+        (status = 200, description = "Saved view retrieved", body = ApiResponse<SavedViewResponse>),
+        (status = 404, description = "Saved view not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Saved View Handler",
+    summary = "Get a saved view")]
+#[debug_handler]
+pub async fn get_saved_view(
+    State(handler): State<Arc<SavedViewHandler>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<SavedViewResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.saved_view_service.get(claims.subject, id).await {
+        Ok(view) => Ok((StatusCode::OK, Json(ApiResponse::new("Saved view retrieved.", view)))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(patch, path = APPLICATION_VIEW, request_body = UpdateSavedViewRequest,
+    params(
+        ("id" = i64, Path, description = "Saved view id")
+    ),
+    responses(
+        (status = 200, description = "Saved view updated", body = ApiResponse<SavedViewResponse>),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 404, description = "Saved view not found", body = ApiError),
+        (status = 409, description = "A saved view with this name already exists", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Saved View Handler",
+    summary = "Update a saved view")]
+#[debug_handler]
+pub async fn update_saved_view(
+    State(handler): State<Arc<SavedViewHandler>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+    Json(req): Json<UpdateSavedViewRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<SavedViewResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.saved_view_service.update(claims.subject, id, req).await {
+        Ok(view) => Ok((StatusCode::OK, Json(ApiResponse::new("Saved view updated.", view)))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(delete, path = APPLICATION_VIEW,
+    params(
+        ("id" = i64, Path, description = "Saved view id")
+    ),
+    responses(
+        (status = 200, description = "Saved view deleted", body = ApiResponse<EmptyResponse>),
+        (status = 404, description = "Saved view not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Saved View Handler",
+    summary = "Delete a saved view")]
+#[debug_handler]
+pub async fn delete_saved_view(
+    State(handler): State<Arc<SavedViewHandler>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.saved_view_service.delete(claims.subject, id).await {
+        Ok(_) => Ok((StatusCode::OK, Json(ApiResponse::new("Saved view deleted.", ())))),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::configs::routes::{USER_SESSION, USER_SESSIONS};
+use crate::errors::api_error::ApiError;
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::payloads::session::{RevokedSessionsResponse, SessionResponse};
+use crate::services::session_service::SessionService;
+use crate::utils::api_response::{ApiResponse, EmptyResponse};
+use crate::utils::jwt::Claims;
+use axum::extract::{Path, State};
+use axum::Json;
+use axum_macros::debug_handler;
+use http::StatusCode;
+use std::sync::Arc;
+
+pub struct SessionHandler {
+    pub session_service: Arc<SessionService>,
+}
+
+#[utoipa::path(get, path = USER_SESSIONS,
+    responses(
+        (status = 200, description = "Active sessions retrieved", body = ApiResponse<Vec<SessionResponse>>),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Session Handler",
+    summary = "List the devices the user is signed in on")]
+#[debug_handler]
+pub async fn list_sessions(
+    State(handler): State<Arc<SessionHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<Vec<SessionResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.session_service.list(claims.subject, claims.session_id).await {
+        Ok(sessions) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Active sessions retrieved.", sessions)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(delete, path = USER_SESSIONS,
+    responses(
+        (status = 200, description = "All other sessions revoked", body = ApiResponse<RevokedSessionsResponse>),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Session Handler",
+    summary = "Sign out of every other session")]
+#[debug_handler]
+pub async fn revoke_other_sessions(
+    State(handler): State<Arc<SessionHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+) -> Result<(StatusCode, Json<ApiResponse<RevokedSessionsResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.session_service.revoke_others(claims.subject, claims.session_id, &client).await {
+        Ok(revoked) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Other sessions have been signed out.", revoked)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(delete, path = USER_SESSION,
+    params(
+        ("id" = i64, Path, description = "Session id")
+    ),
+    responses(
+        (status = 200, description = "Session revoked", body = ApiResponse<EmptyResponse>),
+        (status = 404, description = "Session not found", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Session Handler",
+    summary = "Sign out of a single session")]
+#[debug_handler]
+pub async fn revoke_session(
+    State(handler): State<Arc<SessionHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Path(id): Path<i64>,
+) -> Result<(StatusCode, Json<ApiResponse<()>>), (StatusCode, Json<ApiError>)> {
+    match handler.session_service.revoke(claims.subject, id, &client).await {
+        Ok(_) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Session revoked.", ())),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
@@ -1,11 +1,17 @@
-use crate::configs::routes::{USER_DATA, USER_REGISTER};
+use crate::configs::routes::{USER_AUDIT_LOGS, USER_CANCEL_DELETION, USER_DATA, USER_PREFERENCES, USER_REGISTER};
+use crate::enums::audit::AuditAction;
 use crate::errors::api_error::ApiError;
-use crate::payloads::user::{UserInfo, UserRequest};
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::models::preferences::UserPreferences;
+use crate::payloads::admin::{AuditLogFilter, AuditLogResponse};
+use crate::payloads::pagination::Page;
+use crate::payloads::preferences::UpdatePreferencesRequest;
+use crate::payloads::user::{AccountDeletionResponse, CancelAccountDeletionRequest, DeleteAccountRequest, UpdateProfileRequest, UserInfo, UserRequest};
 use crate::services::user_service::UserService;
 use crate::utils::api_response::ApiResponse;
 use crate::utils::jwt::Claims;
 use axum::Json;
-use axum::extract::State;
+use axum::extract::{OriginalUri, Query, State};
 use axum::http::StatusCode;
 use std::sync::Arc;
 use tracing::error;
@@ -27,9 +33,10 @@ pub struct UserHandler {
     description = "Creates a new user account with the provided credentials and user information.")]
 pub async fn register_user(
     State(handler): State<Arc<UserHandler>>,
+    client: ClientInfo,
     Json(req): Json<UserRequest>,
 ) -> Result<(StatusCode, Json<ApiResponse<UserInfo>>), (StatusCode, Json<ApiError>)> {
-    match handler.user_service.register_user(req).await {
+    match handler.user_service.register_user(req, &client).await {
         Ok(user) => Ok((
             StatusCode::CREATED,
             Json(ApiResponse::new("User registered successfully", user)),
@@ -77,3 +84,212 @@ pub async fn get_user_data(
         }
     }
 }
+
+#[utoipa::path(delete, path = USER_DATA, request_body = DeleteAccountRequest,
+    responses(
+        (status = 200, description = "Account deleted and scheduled for purging", body = ApiResponse<AccountDeletionResponse>),
+        (status = 400, description = "Incorrect password", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or expired token", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User Handler",
+    operation_id = "deleteAccount",
+    summary = "Delete the authenticated user's account",
+    description = "Deletes the account immediately and purges its data once the grace period has passed. Until then the deletion can be cancelled.")]
+pub async fn delete_account(
+    State(handler): State<Arc<UserHandler>>,
+    claims: Claims,
+    client: ClientInfo,
+    Json(req): Json<DeleteAccountRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<AccountDeletionResponse>>), (StatusCode, Json<ApiError>)> {
+    match handler.user_service.delete_account(claims.subject, req, &client).await {
+        Ok(deletion) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Account deleted", deletion)),
+        )),
+        Err(err) => {
+            error!("Failed to delete account: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(post, path = USER_CANCEL_DELETION, request_body = CancelAccountDeletionRequest,
+    responses(
+        (status = 200, description = "Account restored", body = ApiResponse<UserInfo>),
+        (status = 400, description = "Invalid credentials or grace period expired", body = ApiError),
+        (status = 409, description = "Email already in use by another account", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    tag = "User Handler",
+    operation_id = "cancelAccountDeletion",
+    summary = "Cancel a pending account deletion",
+    description = "Restores an account deleted by its owner, as long as the grace period has not passed.")]
+pub async fn cancel_account_deletion(
+    State(handler): State<Arc<UserHandler>>,
+    client: ClientInfo,
+    Json(req): Json<CancelAccountDeletionRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<UserInfo>>), (StatusCode, Json<ApiError>)> {
+    match handler.user_service.cancel_account_deletion(req, &client).await {
+        Ok(user) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Account restored", user)),
+        )),
+        Err(err) => {
+            error!("Failed to cancel account deletion: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(patch, path = USER_DATA, request_body = UpdateProfileRequest,
+    responses(
+        (status = 200, description = "Profile updated successfully", body = ApiResponse<UserInfo>),
+        (status = 400, description = "Bad request", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or expired token", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User Handler",
+    operation_id = "updateProfile",
+    summary = "Update the authenticated user's profile",
+    description = "Updates the name and phone number of the currently authenticated user. Omitted fields are left unchanged.")]
+pub async fn update_profile(
+    State(handler): State<Arc<UserHandler>>,
+    claims: Claims,
+    Json(req): Json<UpdateProfileRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<UserInfo>>), (StatusCode, Json<ApiError>)> {
+    match handler.user_service.update_profile(claims.subject, req).await {
+        Ok(user) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Profile updated", user)),
+        )),
+        Err(err) => {
+            error!("Failed to update profile: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = USER_PREFERENCES, responses(
+        (status = 200, description = "Preferences retrieved successfully", body = ApiResponse<UserPreferences>),
+        (status = 401, description = "Unauthorized - invalid or expired token", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User Handler",
+    operation_id = "getPreferences",
+    summary = "Get the authenticated user's preferences")]
+pub async fn get_preferences(
+    State(handler): State<Arc<UserHandler>>,
+    claims: Claims,
+) -> Result<(StatusCode, Json<ApiResponse<UserPreferences>>), (StatusCode, Json<ApiError>)> {
+    match handler.user_service.get_preferences(claims.subject).await {
+        Ok(preferences) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Preferences retrieved", preferences)),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(patch, path = USER_PREFERENCES, request_body = UpdatePreferencesRequest,
+    responses(
+        (status = 200, description = "Preferences updated successfully", body = ApiResponse<UserPreferences>),
+        (status = 400, description = "Bad request", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or expired token", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User Handler",
+    operation_id = "updatePreferences",
+    summary = "Update the authenticated user's preferences",
+    description = "Merges the given settings into the stored preferences. Omitted fields are left unchanged.")]
+pub async fn update_preferences(
+    State(handler): State<Arc<UserHandler>>,
+    claims: Claims,
+    Json(req): Json<UpdatePreferencesRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<UserPreferences>>), (StatusCode, Json<ApiError>)> {
+    match handler.user_service.update_preferences(claims.subject, req).await {
+        Ok(preferences) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Preferences updated", preferences)),
+        )),
+        Err(err) => {
+            error!("Failed to update preferences: {err}");
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
+
+#[utoipa::path(get, path = USER_AUDIT_LOGS, params(
+        ("action" = Option<AuditAction>, Query, description = "Filter by action"),
+        ("ipAddress" = Option<String>, Query, description = "Filter by client IP address"),
+        ("from" = Option<String>, Query, description = "Only entries at or after this time (RFC 3339)"),
+        ("to" = Option<String>, Query, description = "Only entries at or before this time (RFC 3339)"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("size" = Option<i64>, Query, description = "Page size")
+    ),
+    responses(
+        (status = 200, description = "Security history retrieved", body = ApiResponse<Page<AuditLogResponse>>),
+        (status = 401, description = "Unauthorized - invalid or expired token", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError),
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User Handler",
+    operation_id = "listUserAuditLogs",
+    summary = "List the user's security history",
+    description = "Logins, password and email changes, revoked sessions and tokens, and admin actions on the account.")]
+pub async fn list_own_audit_logs(
+    State(handler): State<Arc<UserHandler>>,
+    claims: Claims,
+    OriginalUri(uri): OriginalUri,
+    Query(filter): Query<AuditLogFilter>,
+) -> Result<(StatusCode, Json<ApiResponse<Page<AuditLogResponse>>>), (StatusCode, Json<ApiError>)> {
+    match handler.user_service.list_audit_logs(claims.subject, filter).await {
+        Ok(logs) => Ok((
+            StatusCode::OK,
+            Json(ApiResponse::new("Security history retrieved", logs.with_links(&uri))),
+        )),
+        Err(err) => {
+            let api_error = err.to_api_error();
+            let status_code = StatusCode::from_u16(api_error.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+            Err((status_code, Json(api_error)))
+        }
+    }
+}
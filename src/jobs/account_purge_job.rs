@@ -0,0 +1,25 @@
+use crate::repositories::user_repository::UserRepository;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{error, info};
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically purges accounts whose deletion grace period has ended.
+pub fn start(user_repo: Arc<UserRepository>) {
+    tokio::spawn(async move {
+        let mut ticker = interval(PURGE_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            match user_repo.purge_expired_deletions().await {
+                Ok(0) => (),
+                Ok(purged) => info!("Purged {} deleted account(s)", purged),
+                Err(e) => error!("Failed to purge deleted accounts: {:?}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,36 @@
+use crate::repositories::data_export_repository::DataExportRepository;
+use chrono::Local;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Building an archive takes seconds, an export pending for longer lost its builder.
+const PENDING_TIMEOUT_IN_MINUTES: i64 = 30;
+
+/// Periodically fails data exports that were abandoned mid-build and drops archives whose
+/// download link has expired. The first run happens at startup.
+pub fn start(data_export_repo: Arc<DataExportRepository>) {
+    tokio::spawn(async move {
+        let mut ticker = interval(CLEANUP_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            let started_before = Local::now() - chrono::Duration::minutes(PENDING_TIMEOUT_IN_MINUTES);
+            match data_export_repo.fail_stale_pending(started_before).await {
+                Ok(0) => (),
+                Ok(failed) => warn!("Failed {} data export(s) left pending by an interrupted build", failed),
+                Err(e) => error!("Failed to recover pending data exports: {:?}", e),
+            }
+
+            match data_export_repo.expire_archives().await {
+                Ok(0) => (),
+                Ok(expired) => info!("Expired {} data export archive(s)", expired),
+                Err(e) => error!("Failed to expire data export archives: {:?}", e),
+            }
+        }
+    });
+}
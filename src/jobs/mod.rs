@@ -0,0 +1,3 @@
+pub(crate) mod account_purge_job;
+pub(crate) mod data_export_cleanup_job;
+pub(crate) mod webauthn_challenge_cleanup_job;
\ No newline at end of file
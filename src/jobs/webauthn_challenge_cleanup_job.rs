@@ -0,0 +1,27 @@
+use crate::repositories::passkey_repository::PasskeyRepository;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{error, info};
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically deletes passkey challenges that were used or expired. Every registration and
+/// login attempt stores one, so the table would otherwise grow without bound. The first run
+/// happens at startup.
+pub fn start(passkey_repo: Arc<PasskeyRepository>) {
+    tokio::spawn(async move {
+        let mut ticker = interval(CLEANUP_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            match passkey_repo.delete_stale_challenges().await {
+                Ok(0) => (),
+                Ok(deleted) => info!("Deleted {} stale passkey challenge(s)", deleted),
+                Err(e) => error!("Failed to delete stale passkey challenges: {:?}", e),
+            }
+        }
+    });
+}
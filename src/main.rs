@@ -12,6 +12,7 @@ mod services;
 mod handlers;
 mod errors;
 mod payloads;
+mod jobs;
 
 use crate::utils::custom_formatter::{init_tracing};
 
@@ -32,7 +33,18 @@ async fn main() {
         .expect("Could Not Run Migrations");
 
 
-    let app = configs::router::app_router(Arc::new(sqlx_pool));
+    let db_pool = Arc::new(sqlx_pool);
+    let audit_log_repo = repositories::audit_log_repository::AuditLogRepository::new(
+        db_pool.clone(),
+        configs::audit::load_audit_log_key(),
+    );
+
+    jobs::account_purge_job::start(repositories::user_repository::UserRepository::new(db_pool.clone(), audit_log_repo.clone()));
+    jobs::data_export_cleanup_job::start(repositories::data_export_repository::DataExportRepository::new(db_pool.clone()));
+    jobs::webauthn_challenge_cleanup_job::start(repositories::passkey_repository::PasskeyRepository::new(db_pool.clone()));
+    info!("Background jobs started.");
+
+    let app = configs::router::app_router(db_pool, audit_log_repo);
     info!("Application router initialized.");
 
     let port: u16 = std::env::var("PORT")
@@ -54,7 +66,7 @@ async fn main() {
     };
 
     info!("Server is now running on {}", addr);
-    if let Err(e) = axum::serve(listener, app).await {
+    if let Err(e) = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
         error!("Server encountered an error: {}", e);
     }
 }
@@ -0,0 +1,25 @@
+use crate::enums::roles::Role;
+use crate::errors::app_error::AppError;
+use crate::utils::jwt::Claims;
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+/// Claims of an authenticated user holding `Role::Admin`. Use it in place of `Claims`
+/// to restrict a handler to administrators.
+pub struct AdminClaims(pub Claims);
+
+impl<S> FromRequestParts<S> for AdminClaims
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = Claims::from_request_parts(parts, state).await?;
+
+        if claims.role != Role::Admin {
+            return Err(AppError::Forbidden("Administrator access is required.".into()));
+        }
+
+        Ok(AdminClaims(claims))
+    }
+}
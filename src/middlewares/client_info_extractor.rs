@@ -0,0 +1,52 @@
+use crate::errors::app_error::AppError;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::header::USER_AGENT;
+use axum::http::request::Parts;
+use lazy_static::lazy_static;
+use std::env::var;
+use std::net::{IpAddr, SocketAddr};
+
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+lazy_static! {
+    /// Set `TRUST_PROXY_HEADERS=true` only behind a reverse proxy that sets `X-Forwarded-For`;
+    /// otherwise clients could claim any address.
+    static ref TRUST_PROXY_HEADERS: bool = var("TRUST_PROXY_HEADERS")
+        .map(|value| value.parse().expect("TRUST_PROXY_HEADERS must be true or false"))
+        .unwrap_or(false);
+}
+
+/// Where a request comes from, recorded on login sessions.
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+        let forwarded_for = parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .and_then(|ip| ip.trim().parse::<IpAddr>().ok())
+            .filter(|_| *TRUST_PROXY_HEADERS);
+
+        let ip_address = forwarded_for
+            .or_else(|| parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip()))
+            .map(|ip| ip.to_canonical().to_string());
+
+        Ok(ClientInfo { user_agent, ip_address })
+    }
+}
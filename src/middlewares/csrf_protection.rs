@@ -0,0 +1,44 @@
+use crate::configs::session::{ACCESS_TOKEN_COOKIE, CSRF_TOKEN_COOKIE, CSRF_TOKEN_HEADER, REFRESH_TOKEN_COOKIE};
+use crate::errors::app_error::AppError;
+use crate::middlewares::jwt_claims_extractor::bearer_token;
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use axum_extra::extract::cookie::CookieJar;
+use http::StatusCode;
+
+/// Double-submit CSRF check for cookie sessions: a state-changing request carrying the access or
+/// refresh cookie must echo the `csrf_token` cookie in the `X-CSRF-Token` header. A cross-site
+/// page can make the browser send the cookies, but cannot read them to set the header.
+///
+/// Safe methods and requests carrying an `Authorization: Bearer` token are not affected, because
+/// browsers never attach that header on their own and such requests never fall back to the
+/// cookies (see [`bearer_token`]). Any other `Authorization` scheme is checked like a cookie request.
+pub async fn csrf_protection(req: Request, next: Next) -> Response {
+    if req.method().is_safe() || bearer_token(req.headers()).is_some_and(|token| token.is_ok()) {
+        return next.run(req).await;
+    }
+
+    let jar = CookieJar::from_headers(req.headers());
+    if jar.get(ACCESS_TOKEN_COOKIE).is_none() && jar.get(REFRESH_TOKEN_COOKIE).is_none() {
+        return next.run(req).await;
+    }
+
+    let expected = jar.get(CSRF_TOKEN_COOKIE).map(|cookie| cookie.value());
+    let presented = req.headers().get(CSRF_TOKEN_HEADER).and_then(|value| value.to_str().ok());
+
+    match (expected, presented) {
+        (Some(expected), Some(presented)) if !expected.is_empty() && constant_time_eq(expected, presented) => {
+            next.run(req).await
+        }
+        _ => {
+            let api_error = AppError::Forbidden("Missing or invalid CSRF token.".into()).to_api_error();
+            (StatusCode::FORBIDDEN, Json(api_error)).into_response()
+        }
+    }
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
@@ -1,11 +1,25 @@
+use crate::configs::session::ACCESS_TOKEN_COOKIE;
 use crate::errors::app_error::AppError;
+use crate::repositories::session_repository::SessionRepository;
+use crate::models::user::User;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::personal_access_token_service::{
+    PersonalAccessTokenService, PERSONAL_ACCESS_TOKEN_PREFIX,
+};
 use crate::utils::jwt::{validate_jwt, Claims};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::{Duration, Local};
+use std::sync::Arc;
+use tracing::error;
 use axum::{
-    extract::FromRequestParts,
-    http::{header::AUTHORIZATION, request::Parts, StatusCode},
+    extract::{FromRequestParts, MatchedPath},
+    http::{header::AUTHORIZATION, request::Parts, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
 };
 
+/// Activity is recorded at most this often per session, to avoid a write on every request.
+const LAST_SEEN_RESOLUTION_IN_SECONDS: i64 = 60;
+
 impl<S> FromRequestParts<S> for Claims
 where
     S: Send + Sync,
@@ -13,20 +27,109 @@ where
     type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        if let Some(auth_header) = parts.headers.get(AUTHORIZATION) {
-            if let Ok(auth_str) = auth_header.to_str() {
-                if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                    return match validate_jwt(token) {
-                        Ok(claims) => Ok(claims),
-                        Err(e) => Err(AppError::InvalidToken(e.to_string())),
-                    };
-                }
+        if let Some(token) = bearer_token(&parts.headers) {
+            let token = token?;
+            if token.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX) {
+                let token_service = parts
+                    .extensions
+                    .get::<Arc<PersonalAccessTokenService>>()
+                    .cloned()
+                    .ok_or_else(|| {
+                        AppError::InternalServerError("Personal access tokens are not configured.".into())
+                    })?;
+                let route = parts.extensions.get::<MatchedPath>().map(MatchedPath::as_str);
+                return token_service.authenticate(token, &parts.method, route).await;
             }
+            return authenticate_jwt(parts, token).await;
+        }
+
+        // Cookie sessions; the CSRF middleware has already checked unsafe requests.
+        let jar = CookieJar::from_headers(&parts.headers);
+        if let Some(cookie) = jar.get(ACCESS_TOKEN_COOKIE) {
+            return authenticate_jwt(parts, cookie.value()).await;
         }
+
         Err(AppError::MissingToken(String::from("Authorization token is missing.")))
     }
 }
 
+/// The token of an `Authorization: Bearer` header. `None` without the header, an error for any
+/// other scheme. A request with an `Authorization` header never falls back to the session
+/// cookies, which is what lets the CSRF check skip exactly the requests with a bearer token.
+pub fn bearer_token(headers: &HeaderMap) -> Option<Result<&str, AppError>> {
+    let header = headers.get(AUTHORIZATION)?;
+
+    Some(
+        header
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| AppError::InvalidToken("Only bearer tokens are supported in the Authorization header.".into())),
+    )
+}
+
+async fn authenticate_jwt(parts: &Parts, token: &str) -> Result<Claims, AppError> {
+    match validate_jwt(token) {
+        Ok(claims) => ensure_not_revoked(parts, claims).await,
+        Err(e) => Err(AppError::InvalidToken(e.to_string())),
+    }
+}
+
+/// Rejects JWTs of deactivated users and JWTs issued before the user's
+/// `token_version` was last bumped, e.g. by a password change.
+async fn ensure_not_revoked(parts: &Parts, claims: Claims) -> Result<Claims, AppError> {
+    let user_repo = parts
+        .extensions
+        .get::<Arc<UserRepository>>()
+        .cloned()
+        .ok_or_else(|| AppError::InternalServerError("User repository is not configured.".into()))?;
+
+    let user = user_repo
+        .get_user_by_id(claims.subject)
+        .await
+        .map_err(|_| AppError::InvalidToken("Session is no longer valid.".into()))?;
+
+    if is_revoked(&user, &claims) {
+        return Err(AppError::InvalidToken("Session has been revoked. Please log in again.".into()));
+    }
+
+    if let Some(session_id) = claims.session_id {
+        ensure_session_active(parts, session_id, claims.subject).await?;
+    }
+
+    Ok(claims)
+}
+
+/// Whether the user was deactivated, or their `token_version` was bumped (by a password change
+/// or reset, a role change, ...) after the token was issued.
+fn is_revoked(user: &User, claims: &Claims) -> bool {
+    user.deleted || user.token_version != claims.token_version
+}
+
+/// Rejects JWTs whose login session was revoked or has expired, and records the activity.
+async fn ensure_session_active(parts: &Parts, session_id: i64, user_id: i64) -> Result<(), AppError> {
+    let session_repo = parts
+        .extensions
+        .get::<Arc<SessionRepository>>()
+        .cloned()
+        .ok_or_else(|| AppError::InternalServerError("Session repository is not configured.".into()))?;
+
+    let session = session_repo
+        .find_active_by_id(session_id, user_id)
+        .await
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| AppError::InvalidToken("Session has been revoked. Please log in again.".into()))?;
+
+    let now = Local::now();
+    if now - session.last_seen_at > Duration::seconds(LAST_SEEN_RESOLUTION_IN_SECONDS)
+        && let Err(e) = session_repo.touch(session.id, now).await
+    {
+        error!("Failed to update last activity of session {}: {:?}", session.id, e);
+    }
+
+    Ok(())
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         match self {
@@ -37,6 +140,7 @@ impl IntoResponse for AppError {
                 StatusCode::UNAUTHORIZED,
                 format!("{msg}"),
             ),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Internal server error: {}", self),
@@ -45,3 +149,53 @@ impl IntoResponse for AppError {
             .into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::roles::Role;
+
+    fn user(token_version: i32) -> User {
+        User {
+            id: 1,
+            token_version,
+            ..User::new("Ada".into(), "Lovelace".into(), "ada@example.com".into(), None, String::new(), None)
+        }
+    }
+
+    fn claims(token_version: i32) -> Claims {
+        Claims {
+            subject: 1,
+            role: Role::User,
+            token_version,
+            session_id: Some(7),
+            exp: usize::MAX,
+        }
+    }
+
+    #[test]
+    fn access_token_from_before_a_password_reset_is_rejected() {
+        let issued = claims(3);
+        assert!(!is_revoked(&user(3), &issued));
+
+        // `update_password_and_revoke_sessions` bumps the version.
+        assert!(is_revoked(&user(4), &issued));
+        assert!(!is_revoked(&user(4), &claims(4)));
+    }
+
+    #[test]
+    fn only_bearer_authorization_headers_carry_a_token() {
+        let headers = |value: &str| HeaderMap::from_iter([(AUTHORIZATION, value.parse().unwrap())]);
+
+        assert!(bearer_token(&HeaderMap::new()).is_none());
+        assert_eq!(bearer_token(&headers("Bearer abc")).unwrap().ok(), Some("abc"));
+        assert!(bearer_token(&headers("Basic eDp5")).unwrap().is_err());
+        assert!(bearer_token(&headers("bearer abc")).unwrap().is_err());
+    }
+
+    #[test]
+    fn access_token_of_a_deactivated_user_is_rejected() {
+        let deactivated = User { deleted: true, ..user(3) };
+        assert!(is_revoked(&deactivated, &claims(3)));
+    }
+}
@@ -1 +1,4 @@
-pub(crate) mod jwt_claims_extractor;
\ No newline at end of file
+pub(crate) mod jwt_claims_extractor;
+pub(crate) mod admin_claims_extractor;
+pub(crate) mod csrf_protection;
+pub(crate) mod client_info_extractor;
\ No newline at end of file
@@ -11,6 +11,8 @@ pub struct Application {
     pub position: String,
     pub website: Option<String>,
     pub application_type: Option<ApplicationType>,
+    pub tags: Vec<String>,
+    pub archived_at: Option<DateTime<Local>>,
     pub created_at: DateTime<Local>,
     pub created_by: i64,
     pub updated_at: DateTime<Local>,
@@ -35,6 +37,8 @@ impl Application {
             position,
             website,
             application_type,
+            tags: Vec::new(),
+            archived_at: None,
             created_by: user_id,
             created_at: now,
             updated_at: now,
@@ -0,0 +1,133 @@
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::middlewares::client_info_extractor::ClientInfo;
+use chrono::{DateTime, Local, SubsecRound};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::FromRow;
+
+/// The `prev_hash` of the first entry in the chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct AuditLog {
+    pub id: i64,
+    pub actor_id: Option<i64>,
+    pub action: AuditAction,
+    pub target_type: AuditTargetType,
+    pub target_id: Option<i64>,
+    pub details: Value,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub prev_hash: Option<String>,
+    pub hash: Option<String>,
+    pub created_at: DateTime<Local>,
+}
+
+impl AuditLog {
+    pub fn new(
+        actor_id: Option<i64>,
+        action: AuditAction,
+        target_type: AuditTargetType,
+        target_id: Option<i64>,
+        details: Value,
+    ) -> Self {
+        Self {
+            id: 0,
+            actor_id,
+            action,
+            target_type,
+            target_id,
+            details,
+            ip_address: None,
+            user_agent: None,
+            prev_hash: None,
+            hash: None,
+            // Postgres keeps microseconds, the hash must be computed from what is stored.
+            created_at: Local::now().trunc_subsecs(6),
+        }
+    }
+
+    pub fn with_client(mut self, client: &ClientInfo) -> Self {
+        self.ip_address = client.ip_address.clone();
+        self.user_agent = client.user_agent.clone();
+        self
+    }
+
+    /// HMAC-SHA256 over the entry's content and the hash of the entry before it, so that editing,
+    /// removing or reordering entries breaks every hash that follows. Without `key` the hashes
+    /// cannot be recomputed, so the chain cannot be rewritten from the database alone.
+    pub fn compute_hash(&self, key: &[u8], prev_hash: &str) -> String {
+        let content = json!([
+            prev_hash,
+            self.actor_id,
+            format!("{:?}", self.action),
+            format!("{:?}", self.target_type),
+            self.target_id,
+            canonicalize(&self.details),
+            self.ip_address,
+            self.user_agent,
+            self.created_at.timestamp_micros(),
+        ]);
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(content.to_string().as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// JSONB does not keep key order, so objects are rewritten with sorted keys before hashing.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            Value::Array(entries.into_iter().map(|(key, value)| json!([key, canonicalize(value)])).collect())
+        }
+        Value::Array(values) => Value::Array(values.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn log() -> AuditLog {
+        AuditLog::new(Some(1), AuditAction::RoleChanged, AuditTargetType::User, Some(2), json!({ "from": "User", "to": "Admin" }))
+    }
+
+    #[test]
+    fn hash_depends_on_the_key() {
+        let log = log();
+        let hash = log.compute_hash(KEY, GENESIS_HASH);
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, log.compute_hash(KEY, GENESIS_HASH));
+        assert_ne!(hash, log.compute_hash(b"another key, just as long as it", GENESIS_HASH));
+    }
+
+    #[test]
+    fn hash_covers_content_and_previous_hash() {
+        let log = log();
+        let hash = log.compute_hash(KEY, GENESIS_HASH);
+
+        let mut edited = log.clone();
+        edited.details = json!({ "from": "User", "to": "User" });
+
+        assert_ne!(hash, edited.compute_hash(KEY, GENESIS_HASH));
+        assert_ne!(hash, log.compute_hash(KEY, &hash));
+    }
+
+    #[test]
+    fn hash_ignores_key_order_in_details() {
+        let log = log();
+        let mut reordered = log.clone();
+        reordered.details = json!({ "to": "Admin", "from": "User" });
+
+        assert_eq!(log.compute_hash(KEY, GENESIS_HASH), reordered.compute_hash(KEY, GENESIS_HASH));
+    }
+}
@@ -0,0 +1,31 @@
+use crate::enums::data_export::DataExportStatus;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Export metadata. The archive itself is only loaded when it is downloaded.
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct DataExport {
+    pub id: i64,
+    pub user_id: i64,
+    pub status: DataExportStatus,
+    #[serde(skip_serializing)]
+    pub download_token_hash: String,
+    pub created_at: DateTime<Local>,
+    pub completed_at: Option<DateTime<Local>>,
+    pub expires_at: Option<DateTime<Local>>,
+}
+
+impl DataExport {
+    pub fn new(user_id: i64, download_token_hash: String) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            status: DataExportStatus::Pending,
+            download_token_hash,
+            created_at: Local::now(),
+            completed_at: None,
+            expires_at: None,
+        }
+    }
+}
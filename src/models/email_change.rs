@@ -0,0 +1,34 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::time::Duration;
+use uuid::Uuid;
+
+const EMAIL_CHANGE_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct EmailChangeRequest {
+    pub id: i64,
+    pub user_id: i64,
+    pub new_email: String,
+    pub token: String,
+    pub expires_at: DateTime<Local>,
+    pub used: bool,
+    pub created_at: DateTime<Local>,
+}
+
+impl EmailChangeRequest {
+    pub fn new(user_id: i64, new_email: String) -> Self {
+        let now = Local::now();
+
+        Self {
+            id: 0,
+            user_id,
+            new_email,
+            token: Uuid::new_v4().to_string(),
+            expires_at: now + EMAIL_CHANGE_TTL,
+            used: false,
+            created_at: now,
+        }
+    }
+}
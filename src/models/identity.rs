@@ -0,0 +1,68 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct UserIdentity {
+    pub id: i64,
+    pub user_id: i64,
+    pub provider: String,
+    pub subject: String,
+    pub email: String,
+    pub created_at: DateTime<Local>,
+    pub last_login_at: Option<DateTime<Local>>,
+}
+
+impl UserIdentity {
+    pub fn new(user_id: i64, provider: String, subject: String, email: String) -> Self {
+        let now = Local::now();
+        Self {
+            id: 0,
+            user_id,
+            provider,
+            subject,
+            email,
+            created_at: now,
+            last_login_at: Some(now),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct OidcLoginState {
+    pub id: i64,
+    pub provider: String,
+    pub state: String,
+    pub nonce: String,
+    #[serde(skip_serializing)]
+    pub code_verifier: String,
+    pub remember_me: bool,
+    pub expires_at: DateTime<Local>,
+    pub used: bool,
+    pub created_at: DateTime<Local>,
+}
+
+impl OidcLoginState {
+    pub fn new(
+        provider: String,
+        state: String,
+        nonce: String,
+        code_verifier: String,
+        remember_me: bool,
+        ttl: Duration,
+    ) -> Self {
+        let now = Local::now();
+        Self {
+            id: 0,
+            provider,
+            state,
+            nonce,
+            code_verifier,
+            remember_me,
+            expires_at: now + ttl,
+            used: false,
+            created_at: now,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::enums::roles::Role;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct Invite {
+    pub id: i64,
+    pub email: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub role: Option<Role>,
+    pub invited_by: Option<i64>,
+    pub accepted_by: Option<i64>,
+    pub expires_at: DateTime<Local>,
+    pub accepted_at: Option<DateTime<Local>>,
+    pub revoked_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+}
+
+impl Invite {
+    pub fn new(
+        email: String,
+        token_hash: String,
+        role: Option<Role>,
+        invited_by: i64,
+        expires_in: Duration,
+    ) -> Self {
+        let now = Local::now();
+        Self {
+            id: 0,
+            email,
+            token_hash,
+            role,
+            invited_by: Some(invited_by),
+            accepted_by: None,
+            expires_at: now + expires_in,
+            accepted_at: None,
+            revoked_at: None,
+            created_at: now,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.accepted_at.is_none() && self.revoked_at.is_none() && self.expires_at > Local::now()
+    }
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct UserMfa {
+    pub user_id: i64,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub enabled: bool,
+    #[serde(skip_serializing)]
+    pub last_used_step: Option<i64>,
+    pub failed_attempts: i32,
+    pub locked_until: Option<DateTime<Local>>,
+    pub confirmed_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct RecoveryCode {
+    pub id: i64,
+    pub user_id: i64,
+    #[serde(skip_serializing)]
+    pub code_hash: String,
+    pub used_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+}
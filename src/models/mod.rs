@@ -1,3 +1,14 @@
 pub mod user;
 pub(crate) mod application;
-pub(crate) mod token;
\ No newline at end of file
+pub(crate) mod token;
+pub(crate) mod mfa;
+pub(crate) mod passkey;
+pub(crate) mod identity;
+pub(crate) mod personal_access_token;
+pub(crate) mod email_change;
+pub(crate) mod audit_log;
+pub(crate) mod preferences;
+pub(crate) mod data_export;
+pub(crate) mod invite;
+pub(crate) mod session;
+pub(crate) mod saved_view;
\ No newline at end of file
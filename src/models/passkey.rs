@@ -0,0 +1,68 @@
+use crate::enums::passkey::Ceremony;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct PasskeyCredential {
+    pub id: i64,
+    pub user_id: i64,
+    pub credential_id: String,
+    #[serde(skip_serializing)]
+    pub public_key: Vec<u8>,
+    pub sign_count: i64,
+    pub name: String,
+    pub aaguid: Option<String>,
+    pub created_at: DateTime<Local>,
+    pub last_used_at: Option<DateTime<Local>>,
+}
+
+impl PasskeyCredential {
+    pub fn new(
+        user_id: i64,
+        credential_id: String,
+        public_key: Vec<u8>,
+        sign_count: i64,
+        name: String,
+        aaguid: Option<String>,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            credential_id,
+            public_key,
+            sign_count,
+            name,
+            aaguid,
+            created_at: Local::now(),
+            last_used_at: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct WebauthnChallenge {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub challenge: String,
+    pub ceremony: Ceremony,
+    pub expires_at: DateTime<Local>,
+    pub used: bool,
+    pub created_at: DateTime<Local>,
+}
+
+impl WebauthnChallenge {
+    pub fn new(user_id: Option<i64>, challenge: String, ceremony: Ceremony, ttl: Duration) -> Self {
+        let now = Local::now();
+        Self {
+            id: 0,
+            user_id,
+            challenge,
+            ceremony,
+            expires_at: now + ttl,
+            used: false,
+            created_at: now,
+        }
+    }
+}
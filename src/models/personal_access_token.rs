@@ -0,0 +1,42 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct PersonalAccessToken {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub token_prefix: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Local>>,
+    pub last_used_at: Option<DateTime<Local>>,
+    pub revoked_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+}
+
+impl PersonalAccessToken {
+    pub fn new(
+        user_id: i64,
+        name: String,
+        token_prefix: String,
+        token_hash: String,
+        scopes: Vec<String>,
+        expires_at: Option<DateTime<Local>>,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            name,
+            token_prefix,
+            token_hash,
+            scopes,
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: Local::now(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::enums::application::ApplicationType;
+use crate::enums::preferences::DateFormat;
+use chrono::{DateTime, Local};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+
+/// Per-user settings, stored as a single JSONB document. Missing keys fall back to
+/// their defaults, so new settings can be added without a migration.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct UserPreferences {
+    pub timezone: String,
+    /// BCP 47 tag such as `en` or `en-US`, used as the language of emails.
+    pub locale: String,
+    #[serde(rename = "dateFormat")]
+    pub date_format: DateFormat,
+    #[serde(rename = "defaultPageSize")]
+    pub default_page_size: i64,
+    #[serde(rename = "defaultApplicationType")]
+    pub default_application_type: Option<ApplicationType>,
+    pub notifications: NotificationPreferences,
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        Self {
+            timezone: "UTC".into(),
+            locale: "en-US".into(),
+            date_format: DateFormat::Iso,
+            default_page_size: DEFAULT_PAGE_SIZE,
+            default_application_type: None,
+            notifications: NotificationPreferences::default(),
+        }
+    }
+}
+
+impl UserPreferences {
+    /// Renders a point in time for emails, in the user's timezone and date format.
+    pub fn format_datetime(&self, at: &DateTime<Local>) -> String {
+        let timezone: Tz = self.timezone.parse().unwrap_or(Tz::UTC);
+        let pattern = format!("{} %H:%M %Z", self.date_format.pattern());
+        at.with_timezone(&timezone).format(&pattern).to_string()
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    /// New device sign-ins and email address changes.
+    #[serde(rename = "securityAlerts")]
+    pub security_alerts: bool,
+    #[serde(rename = "productUpdates")]
+    pub product_updates: bool,
+    #[serde(rename = "applicationReminders")]
+    pub application_reminders: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            security_alerts: true,
+            product_updates: false,
+            application_reminders: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn formats_dates_in_the_users_timezone_and_format() {
+        let at = Utc.with_ymd_and_hms(2026, 1, 31, 23, 30, 0).unwrap().with_timezone(&Local);
+        let mut preferences = UserPreferences::default();
+
+        assert_eq!(preferences.format_datetime(&at), "2026-01-31 23:30 UTC");
+
+        preferences.timezone = "Europe/Berlin".into();
+        preferences.date_format = DateFormat::DayMonthYear;
+        assert_eq!(preferences.format_datetime(&at), "01/02/2026 00:30 CET");
+
+        preferences.timezone = "America/New_York".into();
+        preferences.date_format = DateFormat::MonthDayYear;
+        assert_eq!(preferences.format_datetime(&at), "01/31/2026 18:30 EST");
+    }
+
+    #[test]
+    fn missing_keys_fall_back_to_their_defaults() {
+        let stored = serde_json::json!({ "timezone": "Europe/Berlin", "notifications": { "securityAlerts": false } });
+        let preferences: UserPreferences = serde_json::from_value(stored).unwrap();
+
+        assert_eq!(preferences.timezone, "Europe/Berlin");
+        assert_eq!(preferences.locale, "en-US");
+        assert!(!preferences.notifications.security_alerts);
+        assert!(!preferences.notifications.product_updates);
+        assert!(preferences.notifications.application_reminders);
+    }
+}
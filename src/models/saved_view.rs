@@ -0,0 +1,39 @@
+use crate::enums::application::ApplicationColumn;
+use crate::payloads::application::ApplicationFilter;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+
+#[derive(Serialize, Deserialize, FromRow, Clone)]
+pub struct SavedView {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub filter: Json<ApplicationFilter>,
+    pub columns: Json<Vec<ApplicationColumn>>,
+    pub show_count: bool,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl SavedView {
+    pub fn new(
+        user_id: i64,
+        name: String,
+        filter: ApplicationFilter,
+        columns: Vec<ApplicationColumn>,
+        show_count: bool,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            name,
+            filter: Json(filter),
+            columns: Json(columns),
+            show_count,
+            created_at: Local::now(),
+            updated_at: Local::now(),
+        }
+    }
+}
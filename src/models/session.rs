@@ -0,0 +1,42 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One login of a user on one device. Every JWT carries the id of the session it belongs to,
+/// so revoking the session revokes its tokens.
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, PartialEq)]
+pub struct UserSession {
+    pub id: i64,
+    pub user_id: i64,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing)]
+    pub device_hash: String,
+    pub created_at: DateTime<Local>,
+    pub last_seen_at: DateTime<Local>,
+    pub expires_at: DateTime<Local>,
+    pub revoked_at: Option<DateTime<Local>>,
+}
+
+impl UserSession {
+    pub fn new(
+        user_id: i64,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        device_hash: String,
+        expires_at: DateTime<Local>,
+    ) -> Self {
+        let now = Local::now();
+        Self {
+            id: 0,
+            user_id,
+            user_agent,
+            ip_address,
+            device_hash,
+            created_at: now,
+            last_seen_at: now,
+            expires_at,
+            revoked_at: None,
+        }
+    }
+}
@@ -29,6 +29,12 @@ pub struct User {
 
     #[serde(skip_serializing)]
     pub failed_login_attempts: i32,
+
+    #[serde(skip_serializing)]
+    pub token_version: i32,
+
+    #[serde(skip_serializing)]
+    pub purge_after: Option<DateTime<Local>>,
 }
 
 impl User {
@@ -56,6 +62,8 @@ impl User {
             is_verified: false,
             last_login_at: None,
             failed_login_attempts: 0,
+            token_version: 0,
+            purge_after: None,
         }
     }
 }
@@ -0,0 +1,160 @@
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::enums::roles::Role;
+use crate::models::audit_log::AuditLog;
+use crate::models::user::User;
+use crate::payloads::pagination::PageItem;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema, Clone)]
+pub struct AdminUserFilter {
+    pub search: Option<String>,
+    pub role: Option<Role>,
+    pub deactivated: Option<bool>,
+    pub page: Option<i64>,
+    pub size: Option<i64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ChangeRoleRequest {
+    pub role: Role,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AdminUserResponse {
+    pub id: i64,
+
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+
+    #[serde(rename = "lastName")]
+    pub last_name: String,
+
+    pub email: String,
+
+    #[serde(rename = "phoneNumber")]
+    pub phone_number: Option<String>,
+
+    pub role: Role,
+
+    #[serde(rename = "isVerified")]
+    pub is_verified: bool,
+
+    pub deactivated: bool,
+
+    #[serde(rename = "deactivatedAt")]
+    pub deactivated_at: Option<DateTime<Local>>,
+
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+
+    #[serde(rename = "lastLoginAt")]
+    pub last_login_at: Option<DateTime<Local>>,
+}
+
+impl PageItem for AdminUserResponse {
+    const KEY: &'static str = "users";
+}
+
+impl AdminUserResponse {
+    pub fn from_user(user: &User) -> Self {
+        Self {
+            id: user.id,
+            first_name: user.first_name.clone(),
+            last_name: user.last_name.clone(),
+            email: user.email.clone(),
+            phone_number: user.phone_number.clone(),
+            role: user.role.clone(),
+            is_verified: user.is_verified,
+            deactivated: user.deleted,
+            deactivated_at: user.deleted_at,
+            created_at: user.created_at,
+            last_login_at: user.last_login_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, Clone)]
+pub struct AuditLogFilter {
+    #[serde(rename = "actorId")]
+    pub actor_id: Option<i64>,
+    pub action: Option<AuditAction>,
+    #[serde(rename = "targetType")]
+    pub target_type: Option<AuditTargetType>,
+    #[serde(rename = "targetId")]
+    pub target_id: Option<i64>,
+    /// Entries where the user is either the actor or the target.
+    #[serde(rename = "userId")]
+    pub user_id: Option<i64>,
+    #[serde(rename = "ipAddress")]
+    pub ip_address: Option<String>,
+    pub from: Option<DateTime<Local>>,
+    pub to: Option<DateTime<Local>>,
+    pub page: Option<i64>,
+    pub size: Option<i64>,
+    /// Set when users read their own history, see `AuditLogResponse::for_user`.
+    #[serde(skip)]
+    pub own_history: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub id: i64,
+    #[serde(rename = "actorId")]
+    pub actor_id: Option<i64>,
+    pub action: AuditAction,
+    #[serde(rename = "targetType")]
+    pub target_type: AuditTargetType,
+    #[serde(rename = "targetId")]
+    pub target_id: Option<i64>,
+    pub details: Value,
+    #[serde(rename = "ipAddress")]
+    pub ip_address: Option<String>,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+}
+
+impl PageItem for AuditLogResponse {
+    const KEY: &'static str = "auditLogs";
+}
+
+impl AuditLogResponse {
+    pub fn from_audit_log(log: &AuditLog) -> Self {
+        Self {
+            id: log.id,
+            actor_id: log.actor_id,
+            action: log.action.clone(),
+            target_type: log.target_type.clone(),
+            target_id: log.target_id,
+            details: log.details.clone(),
+            ip_address: log.ip_address.clone(),
+            user_agent: log.user_agent.clone(),
+            created_at: log.created_at,
+        }
+    }
+
+    /// Entries recorded by someone else, e.g. an admin acting on the account, do not reveal
+    /// where that person was connecting from.
+    pub fn for_user(log: &AuditLog, user_id: i64) -> Self {
+        let mut response = Self::from_audit_log(log);
+        if log.actor_id.is_some_and(|actor_id| actor_id != user_id) {
+            response.ip_address = None;
+            response.user_agent = None;
+        }
+        response
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AuditChainVerificationResponse {
+    pub valid: bool,
+    #[serde(rename = "checkedEntries")]
+    pub checked_entries: i64,
+    /// The first entry whose hash or link to the previous entry does not match.
+    #[serde(rename = "brokenAtId")]
+    pub broken_at_id: Option<i64>,
+}
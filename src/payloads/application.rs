@@ -1,18 +1,197 @@
-use crate::enums::application::{ApplicationType, InterviewType, Status, TestType};
+use crate::enums::application::{ApplicationFacet, ApplicationSortField, BulkOutcome, ApplicationType, DuplicateMatch, InterviewType, SortDirection, Status, TestType};
 use crate::models::application::{Application, ApplicationStatus};
+use crate::utils::query_util::{deserialize_comma_separated, deserialize_keep_empty, deserialize_repeated};
+use crate::utils::search_util::to_prefix_tsquery;
+use crate::utils::token_util::hash_token;
+use crate::payloads::pagination::PageItem;
 use chrono::{DateTime, Local, Utc};
+use serde::de::value::StrDeserializer;
+use serde::de::IntoDeserializer;
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 use utoipa::ToSchema;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-#[derive(Serialize, Deserialize, ToSchema, Clone)]
+/// List filters match any of their values; the `exclude*` variants drop applications matching
+/// any of their values. Fixed values are comma separated, while free text such as company names
+/// and tags is given as repeated parameters. All lists may also be repeated parameters.
+#[derive(Serialize, Deserialize, ToSchema, Validate, Clone, Default)]
 pub struct ApplicationFilter {
     pub search: Option<String>,
-    pub status: Option<Status>,
+    /// Matched against the latest status of each application.
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    pub status: Option<Vec<Status>>,
+    #[serde(rename = "excludeStatus", default, deserialize_with = "deserialize_comma_separated")]
+    pub exclude_status: Option<Vec<Status>>,
+    #[serde(rename = "applicationType", default, deserialize_with = "deserialize_comma_separated")]
+    pub application_type: Option<Vec<ApplicationType>>,
+    #[serde(rename = "excludeApplicationType", default, deserialize_with = "deserialize_comma_separated")]
+    pub exclude_application_type: Option<Vec<ApplicationType>>,
+    /// Exact company names, case insensitive.
+    #[serde(default, deserialize_with = "deserialize_repeated")]
+    pub company: Option<Vec<String>>,
+    #[serde(rename = "excludeCompany", default, deserialize_with = "deserialize_repeated")]
+    pub exclude_company: Option<Vec<String>>,
+    /// Applications having any of these tags.
+    #[serde(default, deserialize_with = "deserialize_repeated")]
+    pub tags: Option<Vec<String>>,
+    /// Lists only archived applications when `true`; they are left out otherwise.
+    pub archived: Option<bool>,
+    /// Applications that had an interview of one of these types at any point.
+    #[serde(rename = "interviewType", default, deserialize_with = "deserialize_comma_separated")]
+    pub interview_type: Option<Vec<InterviewType>>,
+    #[serde(rename = "excludeInterviewType", default, deserialize_with = "deserialize_comma_separated")]
+    pub exclude_interview_type: Option<Vec<InterviewType>>,
     pub from: Option<DateTime<Utc>>,
     pub to: Option<DateTime<Utc>>,
+    /// The last activity is the most recent status change, or the creation of the application.
+    #[serde(rename = "lastActivityAfter")]
+    pub last_activity_after: Option<DateTime<Utc>>,
+    #[serde(rename = "lastActivityBefore")]
+    pub last_activity_before: Option<DateTime<Utc>>,
+    #[serde(rename = "appliedWithinDays")]
+    #[validate(range(min = 1, max = 3650, message = "appliedWithinDays must be between 1 and 3650"))]
+    pub applied_within_days: Option<i64>,
+    /// Sort keys in order of precedence, e.g. `status,lastActivity:desc`. Keys are `createdAt`,
+    /// `company`, `position`, `status`, `lastActivity` and `timeInStage`, ascending unless
+    /// followed by `:desc`. Defaults to search relevance when searching, newest first otherwise.
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    #[validate(
+        length(max = 6, message = "At most 6 sort keys are allowed"),
+        custom(function = "validate_application_sort")
+    )]
+    pub sort: Option<Vec<String>>,
+    /// Switches to cursor pagination: pass an empty cursor for the first page, then the
+    /// `nextCursor` or `prevCursor` of the previous response. `page` is ignored meanwhile.
+    #[serde(default, deserialize_with = "deserialize_keep_empty")]
+    pub cursor: Option<String>,
     pub page: Option<i64>,
     pub size: Option<i64>,
+    /// A saved view to start from. Filters set on the request take precedence over the view's.
+    pub view: Option<i64>,
+    /// Dimensions to return result counts for along with the page, e.g. `status,company`.
+    /// Each is counted under every other filter, ignoring the facet's own filters.
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    pub facets: Option<Vec<ApplicationFacet>>,
+}
+
+impl ApplicationFilter {
+    /// Fills every filter left unset on this one from a saved view's filter.
+    pub fn or_saved(self, saved: ApplicationFilter) -> Self {
+        Self {
+            search: self.search.or(saved.search),
+            status: self.status.or(saved.status),
+            exclude_status: self.exclude_status.or(saved.exclude_status),
+            application_type: self.application_type.or(saved.application_type),
+            exclude_application_type: self.exclude_application_type.or(saved.exclude_application_type),
+            company: self.company.or(saved.company),
+            exclude_company: self.exclude_company.or(saved.exclude_company),
+            tags: self.tags.or(saved.tags),
+            archived: self.archived.or(saved.archived),
+            interview_type: self.interview_type.or(saved.interview_type),
+            exclude_interview_type: self.exclude_interview_type.or(saved.exclude_interview_type),
+            from: self.from.or(saved.from),
+            to: self.to.or(saved.to),
+            last_activity_after: self.last_activity_after.or(saved.last_activity_after),
+            last_activity_before: self.last_activity_before.or(saved.last_activity_before),
+            applied_within_days: self.applied_within_days.or(saved.applied_within_days),
+            sort: self.sort.or(saved.sort),
+            cursor: self.cursor,
+            page: self.page,
+            size: self.size.or(saved.size),
+            view: self.view,
+            facets: self.facets.or(saved.facets),
+        }
+    }
+
+    /// What a saved view keeps of a filter: everything but the position in the list.
+    pub fn into_saved(mut self) -> Self {
+        self.cursor = None;
+        self.page = None;
+        self.view = None;
+        self
+    }
+
+    /// The filter a facet is counted under: every other filter applies, but none of the facet's
+    /// own, so that each value shows how many results picking it would give.
+    pub fn without_facet(mut self, facet: ApplicationFacet) -> Self {
+        match facet {
+            ApplicationFacet::Status => {
+                self.status = None;
+                self.exclude_status = None;
+            }
+            ApplicationFacet::ApplicationType => {
+                self.application_type = None;
+                self.exclude_application_type = None;
+            }
+            ApplicationFacet::Company => {
+                self.company = None;
+                self.exclude_company = None;
+            }
+        }
+        self
+    }
+
+    /// The requested sort keys, keeping only the first occurrence of each field. Keys that do
+    /// not parse are skipped; they are rejected when the filter is validated.
+    pub fn sort_keys(&self) -> Vec<ApplicationSort> {
+        let mut keys: Vec<ApplicationSort> = Vec::new();
+        for key in self.sort.iter().flatten().filter_map(|key| ApplicationSort::parse(key)) {
+            if !keys.iter().any(|existing| existing.field == key.field) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    /// Identifies the effective ordering, so that a cursor issued under one sort is not used
+    /// with another.
+    pub fn ordering(&self) -> String {
+        let keys = self.sort_keys();
+        if !keys.is_empty() {
+            keys.iter()
+                .map(|key| format!("{:?}:{:?}", key.field, key.direction))
+                .collect::<Vec<_>>()
+                .join(",")
+        } else if let Some(search_query) = self.search.as_deref().and_then(to_prefix_tsquery) {
+            // Ranks depend on the search, so the cursor of one search means nothing in another.
+            format!("relevance:{}", &hash_token(&search_query)[..16])
+        } else {
+            "default".to_string()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ApplicationSort {
+    pub field: ApplicationSortField,
+    pub direction: SortDirection,
+}
+
+impl ApplicationSort {
+    /// Parses `field` or `field:direction`, e.g. `company` or `lastActivity:desc`.
+    pub fn parse(key: &str) -> Option<Self> {
+        let (field, direction) = key.split_once(':').unwrap_or((key, "asc"));
+        let field: StrDeserializer<'_, serde::de::value::Error> = field.trim().into_deserializer();
+        let direction: StrDeserializer<'_, serde::de::value::Error> = direction.trim().into_deserializer();
+        Some(Self {
+            field: ApplicationSortField::deserialize(field).ok()?,
+            direction: SortDirection::deserialize(direction).ok()?,
+        })
+    }
+}
+
+fn validate_application_sort(keys: &[String]) -> Result<(), ValidationError> {
+    match keys.iter().find(|key| ApplicationSort::parse(key).is_none()) {
+        Some(key) => Err(ValidationError::new("sort").with_message(
+            format!(
+                "Invalid sort key '{}'. Use createdAt, company, position, status, lastActivity or timeInStage, optionally followed by :asc or :desc",
+                key
+            )
+            .into(),
+        )),
+        None => Ok(()),
+    }
 }
 
 #[derive(Validate, Deserialize, ToSchema)]
@@ -27,6 +206,10 @@ pub struct ApplicationRequest {
 
     #[serde(rename = "applicationType")]
     pub application_type: Option<ApplicationType>,
+
+    /// Register the application even if it looks like one made recently.
+    #[serde(rename = "allowDuplicate", default)]
+    pub allow_duplicate: bool,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -37,6 +220,9 @@ pub struct ApplicationsResponse {
     pub website: Option<String>,
     #[serde(rename = "applicationType")]
     pub application_type: Option<ApplicationType>,
+    pub tags: Vec<String>,
+    #[serde(rename = "archivedAt", skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<DateTime<Local>>,
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Local>,
     #[serde(rename = "createdBy")]
@@ -44,6 +230,80 @@ pub struct ApplicationsResponse {
     pub status: Status,
     #[serde(rename = "statusHistory")]
     pub status_history: Vec<ApplicationStatusResponse>,
+    /// Only set when searching: where the search terms were found, HTML-escaped with matches
+    /// wrapped in `<mark>`.
+    #[serde(rename = "searchSnippet", skip_serializing_if = "Option::is_none")]
+    pub search_snippet: Option<String>,
+}
+
+impl PageItem for ApplicationsResponse {
+    const KEY: &'static str = "applications";
+}
+
+/// Returned when an application is registered, warning about existing companies whose name is
+/// close to but not the same as the new one, which usually means a typo.
+#[derive(Serialize, ToSchema)]
+pub struct CreatedApplicationResponse {
+    #[serde(flatten)]
+    pub application: ApplicationsResponse,
+    #[serde(rename = "similarCompanies", skip_serializing_if = "Vec::is_empty")]
+    pub similar_companies: Vec<String>,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CompanySuggestionQuery {
+    #[validate(length(min = 1, max = 70, message = "Search must be between 1 and 70 characters"))]
+    pub search: String,
+    #[validate(range(min = 1, max = 25, message = "limit must be between 1 and 25"))]
+    pub limit: Option<i64>,
+}
+
+/// An existing application that looks like the same role as another one.
+#[derive(Serialize, ToSchema)]
+pub struct DuplicateApplication {
+    pub id: i64,
+    pub company: String,
+    pub position: String,
+    pub website: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+    /// Only set when checking a new application: what it has in common with this one.
+    #[serde(rename = "matchedOn", skip_serializing_if = "Vec::is_empty")]
+    pub matched_on: Vec<DuplicateMatch>,
+}
+
+/// An application along with the normalized keys duplicates are detected on.
+pub struct DuplicateCandidate {
+    pub application: DuplicateApplication,
+    pub company_key: String,
+    pub position_key: String,
+    pub domain: Option<String>,
+}
+
+/// Applications to the same position at the same company, each made within the window of
+/// another one in the cluster.
+#[derive(Serialize, ToSchema)]
+pub struct DuplicateCluster {
+    #[serde(rename = "matchedOn")]
+    pub matched_on: Vec<DuplicateMatch>,
+    pub applications: Vec<DuplicateApplication>,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct DuplicateScanQuery {
+    /// Maximum days between two applications for them to count as duplicates, 90 by default.
+    #[serde(rename = "windowDays")]
+    #[validate(range(min = 1, max = 3650, message = "windowDays must be between 1 and 3650"))]
+    pub window_days: Option<i32>,
+}
+
+/// A company from the user's past applications; spellings differing only in case count as one.
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct CompanySuggestion {
+    pub company: String,
+    pub applications: i64,
+    /// Trigram similarity to the search, from 0 to 1.
+    pub score: f32,
 }
 
 impl ApplicationsResponse {
@@ -57,6 +317,8 @@ impl ApplicationsResponse {
             position: application.position.clone(),
             website: application.website.clone(),
             application_type: application.application_type.clone(),
+            tags: application.tags.clone(),
+            archived_at: application.archived_at,
             created_at: application.created_at.clone(),
             created_by: application.created_by.clone(),
             status: statuses.last().unwrap().status_type.clone(),
@@ -64,6 +326,7 @@ impl ApplicationsResponse {
                 .iter()
                 .map(|status| ApplicationStatusResponse::from_application_status(status))
                 .collect(),
+            search_snippet: None,
         }
     }
 }
@@ -113,3 +376,113 @@ pub struct ApplicationStatusRequest {
     pub interview_type: Option<InterviewType>,
     pub notes: Option<String>,
 }
+
+/// Tags are compared and stored trimmed and lowercased.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Selects applications either by id or with the same filters as the list, then applies one
+/// action to all of them at once.
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct BulkApplicationRequest {
+    /// Exactly one of `ids` and `filter` must be given.
+    #[validate(length(min = 1, max = 500, message = "Between 1 and 500 ids can be given"))]
+    pub ids: Option<Vec<i64>>,
+    /// Selects what the list would return for this filter, across all pages. Deleted
+    /// applications are never listed, so they can only be restored by id.
+    #[validate(nested)]
+    pub filter: Option<ApplicationFilter>,
+    #[validate(custom(function = "validate_bulk_action"))]
+    pub action: BulkApplicationAction,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BulkApplicationAction {
+    AddStatus { status: Status, notes: Option<String> },
+    Delete,
+    Restore,
+    Tag { tags: Vec<String> },
+    Untag { tags: Vec<String> },
+    Archive,
+    Unarchive,
+}
+
+impl BulkApplicationAction {
+    pub fn normalized(self) -> Self {
+        let normalize = |tags: Vec<String>| {
+            let mut tags: Vec<String> = tags.iter().map(|tag| normalize_tag(tag)).collect();
+            tags.sort();
+            tags.dedup();
+            tags
+        };
+        match self {
+            BulkApplicationAction::Tag { tags } => BulkApplicationAction::Tag { tags: normalize(tags) },
+            BulkApplicationAction::Untag { tags } => BulkApplicationAction::Untag { tags: normalize(tags) },
+            action => action,
+        }
+    }
+
+    /// Why the action leaves an application as it is, if it does.
+    pub fn skip_reason(&self, state: &BulkApplicationState) -> Option<&'static str> {
+        match self {
+            BulkApplicationAction::Restore if !state.deleted => Some("Application is not deleted."),
+            BulkApplicationAction::Restore => None,
+            _ if state.deleted => Some("Application is deleted."),
+            BulkApplicationAction::AddStatus { status, .. } if state.latest_status.as_ref() == Some(status) => {
+                Some("Application already has this status.")
+            }
+            BulkApplicationAction::Tag { tags } if tags.iter().all(|tag| state.tags.contains(tag)) => {
+                Some("Application already has these tags.")
+            }
+            BulkApplicationAction::Untag { tags } if !tags.iter().any(|tag| state.tags.contains(tag)) => {
+                Some("Application has none of these tags.")
+            }
+            BulkApplicationAction::Archive if state.archived => Some("Application is already archived."),
+            BulkApplicationAction::Unarchive if !state.archived => Some("Application is not archived."),
+            _ => None,
+        }
+    }
+}
+
+fn validate_bulk_action(action: &BulkApplicationAction) -> Result<(), ValidationError> {
+    let tags = match action {
+        BulkApplicationAction::Tag { tags } | BulkApplicationAction::Untag { tags } => tags,
+        _ => return Ok(()),
+    };
+    if tags.is_empty() || tags.len() > 10 {
+        return Err(ValidationError::new("tags").with_message("Between 1 and 10 tags can be given".into()));
+    }
+    if tags.iter().any(|tag| tag.trim().is_empty() || tag.trim().chars().count() > 30) {
+        return Err(ValidationError::new("tags").with_message("Tags must be between 1 and 30 characters".into()));
+    }
+    Ok(())
+}
+
+/// What a bulk action needs to know about an application, read while holding its row lock.
+#[derive(FromRow)]
+pub struct BulkApplicationState {
+    pub id: i64,
+    pub deleted: bool,
+    pub archived: bool,
+    pub tags: Vec<String>,
+    pub latest_status: Option<Status>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BulkApplicationResult {
+    pub id: i64,
+    pub outcome: BulkOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BulkApplicationResponse {
+    pub updated: usize,
+    pub skipped: usize,
+    #[serde(rename = "notFound")]
+    pub not_found: usize,
+    pub results: Vec<BulkApplicationResult>,
+}
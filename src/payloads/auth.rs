@@ -1,3 +1,5 @@
+use crate::payloads::mfa::MfaChallengeResponse;
+use crate::utils::jwt::JwtToken;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
@@ -23,7 +25,6 @@ pub struct ForgotPasswordRequest {
 
 #[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct ResetPasswordRequest {
-    #[validate(length(min = 8, message = "Password must be at least 8 characters long"))]
     pub password: String,
 
     #[serde(rename = "confirmPassword")]
@@ -32,3 +33,43 @@ pub struct ResetPasswordRequest {
 
     pub token: String,
 }
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[serde(rename = "currentPassword")]
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+
+    #[serde(rename = "newPassword")]
+    pub new_password: String,
+
+    #[serde(rename = "confirmPassword")]
+    #[validate(must_match(other = "new_password", message = "Passwords do not match"))]
+    pub confirm_password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangeEmailRequest {
+    #[serde(rename = "newEmail")]
+    #[validate(email(message = "Invalid email format"))]
+    pub new_email: String,
+
+    #[serde(rename = "currentPassword")]
+    #[validate(length(min = 1, message = "Current password is required"))]
+    pub current_password: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ConfirmEmailChangeRequest {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+}
+
+/// Result of a password login: either the issued tokens, or a challenge to
+/// complete with a second factor when MFA is enabled for the account.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(JwtToken),
+    MfaRequired(MfaChallengeResponse),
+}
@@ -0,0 +1,263 @@
+use crate::enums::application::ApplicationType;
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::enums::data_export::DataExportStatus;
+use crate::enums::roles::Role;
+use crate::models::application::{Application, ApplicationStatus};
+use crate::models::audit_log::AuditLog;
+use crate::models::data_export::DataExport;
+use crate::models::email_change::EmailChangeRequest;
+use crate::models::personal_access_token::PersonalAccessToken;
+use crate::models::saved_view::SavedView;
+use crate::models::token::Token;
+use crate::models::user::User;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DataExportResponse {
+    pub id: i64,
+    pub status: DataExportStatus,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+    #[serde(rename = "completedAt")]
+    pub completed_at: Option<DateTime<Local>>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Local>>,
+}
+
+impl DataExportResponse {
+    pub fn from_export(export: &DataExport) -> Self {
+        Self {
+            id: export.id,
+            status: export.status.clone(),
+            created_at: export.created_at,
+            completed_at: export.completed_at,
+            expires_at: export.expires_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct DownloadDataExportRequest {
+    pub token: String,
+}
+
+// The structs below describe the files inside the archive. They mirror the table columns,
+// so they keep the database naming rather than the camelCase used by the API.
+
+/// The `users` row without the password hash.
+#[derive(Serialize)]
+pub struct UserExport {
+    pub id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub phone_number: Option<String>,
+    pub role: Role,
+    pub is_verified: bool,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    pub last_login_at: Option<DateTime<Local>>,
+    pub failed_login_attempts: i32,
+    pub deleted: bool,
+    pub deleted_at: Option<DateTime<Local>>,
+    pub purge_after: Option<DateTime<Local>>,
+}
+
+impl UserExport {
+    pub fn from_user(user: &User) -> Self {
+        Self {
+            id: user.id,
+            first_name: user.first_name.clone(),
+            last_name: user.last_name.clone(),
+            email: user.email.clone(),
+            phone_number: user.phone_number.clone(),
+            role: user.role.clone(),
+            is_verified: user.is_verified,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            last_login_at: user.last_login_at,
+            failed_login_attempts: user.failed_login_attempts,
+            deleted: user.deleted,
+            deleted_at: user.deleted_at,
+            purge_after: user.purge_after,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ApplicationExport {
+    pub id: i64,
+    pub company: String,
+    pub position: String,
+    pub website: Option<String>,
+    pub application_type: Option<ApplicationType>,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    pub archived_at: Option<DateTime<Local>>,
+    pub deleted: bool,
+    pub deleted_at: Option<DateTime<Local>>,
+}
+
+impl ApplicationExport {
+    pub fn from_application(application: &Application) -> Self {
+        Self {
+            id: application.id,
+            company: application.company.clone(),
+            position: application.position.clone(),
+            website: application.website.clone(),
+            application_type: application.application_type.clone(),
+            created_at: application.created_at,
+            updated_at: application.updated_at,
+            archived_at: application.archived_at,
+            deleted: application.deleted,
+            deleted_at: application.deleted_at,
+        }
+    }
+}
+
+/// JSON only: CSV cannot nest, so the CSV files keep applications and statuses apart.
+#[derive(Serialize)]
+pub struct ApplicationWithHistoryExport {
+    #[serde(flatten)]
+    pub application: ApplicationExport,
+    pub tags: Vec<String>,
+    pub status_history: Vec<ApplicationStatus>,
+}
+
+/// One row per tag of an application, the CSV counterpart of `tags` in the JSON file.
+#[derive(Serialize)]
+pub struct ApplicationTagExport {
+    pub application_id: i64,
+    pub tag: String,
+}
+
+/// Metadata only, the token hash is left out.
+#[derive(Serialize)]
+pub struct PersonalAccessTokenExport {
+    pub id: i64,
+    pub name: String,
+    pub token_prefix: String,
+    /// Space separated, so the row stays flat in CSV.
+    pub scopes: String,
+    pub expires_at: Option<DateTime<Local>>,
+    pub last_used_at: Option<DateTime<Local>>,
+    pub revoked_at: Option<DateTime<Local>>,
+    pub created_at: DateTime<Local>,
+}
+
+impl PersonalAccessTokenExport {
+    pub fn from_token(token: &PersonalAccessToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name.clone(),
+            token_prefix: token.token_prefix.clone(),
+            scopes: token.scopes.join(" "),
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            revoked_at: token.revoked_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+/// Metadata only, the token value itself is left out.
+#[derive(Serialize)]
+pub struct PasswordResetTokenExport {
+    pub id: i64,
+    pub used: bool,
+    pub expires_at: DateTime<Local>,
+    pub created_at: DateTime<Local>,
+}
+
+impl PasswordResetTokenExport {
+    pub fn from_token(token: &Token) -> Self {
+        Self {
+            id: token.id,
+            used: token.used,
+            expires_at: token.expires_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+/// The confirmation token is left out.
+#[derive(Serialize)]
+pub struct EmailChangeRequestExport {
+    pub id: i64,
+    pub new_email: String,
+    pub used: bool,
+    pub expires_at: DateTime<Local>,
+    pub created_at: DateTime<Local>,
+}
+
+impl EmailChangeRequestExport {
+    pub fn from_request(request: &EmailChangeRequest) -> Self {
+        Self {
+            id: request.id,
+            new_email: request.new_email.clone(),
+            used: request.used,
+            expires_at: request.expires_at,
+            created_at: request.created_at,
+        }
+    }
+}
+
+/// Filter and columns are JSON encoded, so the row stays flat in CSV.
+#[derive(Serialize)]
+pub struct SavedViewExport {
+    pub id: i64,
+    pub name: String,
+    pub filter: String,
+    pub columns: String,
+    pub show_count: bool,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+impl SavedViewExport {
+    pub fn from_view(view: &SavedView) -> Self {
+        Self {
+            id: view.id,
+            name: view.name.clone(),
+            filter: serde_json::to_string(&view.filter.0).unwrap_or_default(),
+            columns: serde_json::to_string(&view.columns.0).unwrap_or_default(),
+            show_count: view.show_count,
+            created_at: view.created_at,
+            updated_at: view.updated_at,
+        }
+    }
+}
+
+/// Details are JSON encoded, so the row stays flat in CSV. Like the audit history the user
+/// can browse, entries recorded by someone else leave out where they connected from.
+#[derive(Serialize)]
+pub struct AuditLogExport {
+    pub id: i64,
+    pub actor_id: Option<i64>,
+    pub action: AuditAction,
+    pub target_type: AuditTargetType,
+    pub target_id: Option<i64>,
+    pub details: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Local>,
+}
+
+impl AuditLogExport {
+    pub fn for_user(log: &AuditLog, user_id: i64) -> Self {
+        let own_entry = log.actor_id.is_none_or(|actor_id| actor_id == user_id);
+        Self {
+            id: log.id,
+            actor_id: log.actor_id,
+            action: log.action.clone(),
+            target_type: log.target_type.clone(),
+            target_id: log.target_id,
+            details: log.details.to_string(),
+            ip_address: log.ip_address.clone().filter(|_| own_entry),
+            user_agent: log.user_agent.clone().filter(|_| own_entry),
+            created_at: log.created_at,
+        }
+    }
+}
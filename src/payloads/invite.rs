@@ -0,0 +1,80 @@
+use crate::enums::invite::InviteStatus;
+use crate::enums::roles::Role;
+use crate::models::invite::Invite;
+use crate::payloads::pagination::PageItem;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct CreateInviteRequest {
+    #[validate(email(message = "Email must be valid"), length(min = 6))]
+    pub email: String,
+
+    /// Role given to the account created from this invite. Defaults to `User`.
+    pub role: Option<Role>,
+
+    #[serde(rename = "expiresInDays")]
+    #[validate(range(min = 1, max = 30, message = "Invite expiry must be between 1 and 30 days"))]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Deserialize, ToSchema, Clone)]
+pub struct InviteFilter {
+    pub email: Option<String>,
+    pub pending: Option<bool>,
+    pub page: Option<i64>,
+    pub size: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct InviteResponse {
+    pub id: i64,
+    pub email: String,
+    pub role: Option<Role>,
+    pub status: InviteStatus,
+    #[serde(rename = "invitedBy")]
+    pub invited_by: Option<i64>,
+    #[serde(rename = "acceptedBy")]
+    pub accepted_by: Option<i64>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Local>,
+    #[serde(rename = "acceptedAt")]
+    pub accepted_at: Option<DateTime<Local>>,
+    #[serde(rename = "revokedAt")]
+    pub revoked_at: Option<DateTime<Local>>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+}
+
+impl PageItem for InviteResponse {
+    const KEY: &'static str = "invites";
+}
+
+impl InviteResponse {
+    pub fn from_invite(invite: &Invite) -> Self {
+        let status = if invite.accepted_at.is_some() {
+            InviteStatus::Accepted
+        } else if invite.revoked_at.is_some() {
+            InviteStatus::Revoked
+        } else if invite.is_pending() {
+            InviteStatus::Pending
+        } else {
+            InviteStatus::Expired
+        };
+
+        Self {
+            id: invite.id,
+            email: invite.email.clone(),
+            role: invite.role.clone(),
+            status,
+            invited_by: invite.invited_by,
+            accepted_by: invite.accepted_by,
+            expires_at: invite.expires_at,
+            accepted_at: invite.accepted_at,
+            revoked_at: invite.revoked_at,
+            created_at: invite.created_at,
+        }
+    }
+}
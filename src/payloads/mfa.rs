@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MfaEnrollmentResponse {
+    pub secret: String,
+
+    #[serde(rename = "otpauthUri")]
+    pub otpauth_uri: String,
+
+    #[serde(rename = "qrCodeSvg")]
+    pub qr_code_svg: String,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct MfaConfirmRequest {
+    #[validate(length(equal = 6, message = "Code must be 6 digits"))]
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    #[serde(rename = "recoveryCodes")]
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct MfaVerifyRequest {
+    #[serde(rename = "challengeToken")]
+    #[validate(length(min = 1, message = "Challenge token cannot be empty"))]
+    pub challenge_token: String,
+
+    pub code: Option<String>,
+
+    #[serde(rename = "recoveryCode")]
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct MfaDisableRequest {
+    #[validate(length(min = 1, message = "Password cannot be empty"))]
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MfaChallengeResponse {
+    #[serde(rename = "mfaRequired")]
+    pub mfa_required: bool,
+
+    #[serde(rename = "challengeToken")]
+    pub challenge_token: String,
+
+    #[serde(rename = "expiresIn")]
+    pub expires_in: i64,
+}
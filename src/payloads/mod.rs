@@ -3,3 +3,15 @@ pub(crate) mod pagination;
 pub(crate) mod user;
 pub(crate) mod auth;
 pub(crate) mod dashboard;
+pub(crate) mod mfa;
+pub(crate) mod passkey;
+
+pub(crate) mod oidc;
+pub(crate) mod personal_access_token;
+pub(crate) mod admin;
+pub(crate) mod preferences;
+pub(crate) mod data_export;
+pub(crate) mod invite;
+pub(crate) mod password;
+pub(crate) mod session;
+pub(crate) mod saved_view;
\ No newline at end of file
@@ -0,0 +1,57 @@
+use crate::models::identity::UserIdentity;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OidcProviderResponse {
+    pub name: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct OidcAuthorizeRequest {
+    #[serde(default, rename = "rememberMe")]
+    pub remember_me: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OidcAuthorizationResponse {
+    #[serde(rename = "authorizationUrl")]
+    pub authorization_url: String,
+    pub state: String,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct OidcCallbackRequest {
+    #[validate(length(min = 1, message = "Authorization code cannot be empty"))]
+    pub code: String,
+
+    #[validate(length(min = 1, message = "State cannot be empty"))]
+    pub state: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct IdentityResponse {
+    pub id: i64,
+    pub provider: String,
+    pub email: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+    #[serde(rename = "lastLoginAt")]
+    pub last_login_at: Option<DateTime<Local>>,
+}
+
+impl IdentityResponse {
+    pub fn from_identity(identity: &UserIdentity) -> Self {
+        Self {
+            id: identity.id,
+            provider: identity.provider.clone(),
+            email: identity.email.clone(),
+            created_at: identity.created_at,
+            last_login_at: identity.last_login_at,
+        }
+    }
+}
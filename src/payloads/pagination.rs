@@ -1,32 +1,196 @@
-use serde::Serialize;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use http::Uri;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
 use sqlx::postgres::PgRow;
-use sqlx::{PgPool, Postgres, QueryBuilder};
-use std::collections::HashMap;
-
-pub fn build_paginated_response<T: Serialize>(
-    items: Vec<T>,
-    page: i64,
-    total: i64,
-    total_pages: i64,
-    key: &str
-) -> HashMap<String, Value> {
-    let size = items.len() as i64;
-
-    // Create pagination map
-    let pagination = serde_json::json!({
-        "total": total,
-        "size": size,
-        "page": page,
-        "totalPages": total_pages
-    });
-
-    // Create the final response map
-    let mut data = HashMap::new();
-    data.insert(key.to_string(), serde_json::to_value(items).unwrap_or_default());
-    data.insert("pagination".to_string(), pagination);
-
-    data
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder, Row};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use utoipa::openapi::schema::{ArrayBuilder, ObjectBuilder, Schema};
+use utoipa::openapi::{Ref, RefOr};
+use utoipa::__dev::ComposeSchema;
+use utoipa::{PartialSchema, ToSchema};
+
+/// Implemented by the items of paginated lists, naming the key the items are listed under.
+pub trait PageItem {
+    const KEY: &'static str;
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(untagged)]
+pub enum Pagination {
+    Offset {
+        total: i64,
+        size: i64,
+        page: i64,
+        #[serde(rename = "totalPages")]
+        total_pages: i64,
+    },
+    Cursor {
+        total: i64,
+        size: i64,
+        #[serde(rename = "nextCursor")]
+        next_cursor: Option<String>,
+        #[serde(rename = "prevCursor")]
+        prev_cursor: Option<String>,
+    },
+}
+
+/// Links to neighbouring pages, built from the request with only `page` or `cursor` changed.
+#[derive(Serialize, Debug, Default, ToSchema)]
+pub struct PageLinks {
+    #[serde(rename = "self")]
+    pub current: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last: Option<String>,
+}
+
+/// How many results one value of a facet has, e.g. the number of applications at a company.
+#[derive(Serialize, Debug, FromRow, ToSchema)]
+pub struct FacetCount {
+    pub value: Option<String>,
+    pub count: i64,
+}
+
+/// A page of a list endpoint, serialized as `{"<key>": [...], "pagination": {...}, "links": {...}}`
+/// where the key comes from `PageItem`, plus `facets` when they were asked for.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub pagination: Pagination,
+    pub links: Option<PageLinks>,
+    pub facets: Option<BTreeMap<String, Vec<FacetCount>>>,
+}
+
+impl<T> Page<T> {
+    pub fn offset(items: Vec<T>, page: i64, total: i64, total_pages: i64) -> Self {
+        Self {
+            pagination: Pagination::Offset {
+                total,
+                size: items.len() as i64,
+                page,
+                total_pages,
+            },
+            items,
+            links: None,
+            facets: None,
+        }
+    }
+
+    pub fn cursor(items: Vec<T>, total: i64, next_cursor: Option<String>, prev_cursor: Option<String>) -> Self {
+        Self {
+            pagination: Pagination::Cursor {
+                total,
+                size: items.len() as i64,
+                next_cursor,
+                prev_cursor,
+            },
+            items,
+            links: None,
+            facets: None,
+        }
+    }
+
+    pub fn with_facets(mut self, facets: BTreeMap<String, Vec<FacetCount>>) -> Self {
+        self.facets = Some(facets);
+        self
+    }
+
+    /// Adds links to the surrounding pages, relative to the URI the page was requested with.
+    pub fn with_links(mut self, uri: &Uri) -> Self {
+        let link = |key: &str, value: String| Some(replace_query_param(uri, key, &value));
+        let current = uri.path_and_query().map_or_else(|| uri.path().to_string(), |pq| pq.to_string());
+
+        self.links = Some(match &self.pagination {
+            Pagination::Offset { page, total_pages, .. } => PageLinks {
+                current,
+                first: link("page", "1".to_string()),
+                prev: if *page > 1 { link("page", (page - 1).to_string()) } else { None },
+                next: if page < total_pages { link("page", (page + 1).to_string()) } else { None },
+                last: if *total_pages > 0 { link("page", total_pages.to_string()) } else { None },
+            },
+            Pagination::Cursor { next_cursor, prev_cursor, .. } => PageLinks {
+                current,
+                first: link("cursor", String::new()),
+                prev: prev_cursor.clone().and_then(|cursor| link("cursor", cursor)),
+                next: next_cursor.clone().and_then(|cursor| link("cursor", cursor)),
+                last: None,
+            },
+        });
+        self
+    }
+}
+
+impl<T: Serialize + PageItem> Serialize for Page<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(T::KEY, &self.items)?;
+        map.serialize_entry("pagination", &self.pagination)?;
+        if let Some(links) = &self.links {
+            map.serialize_entry("links", links)?;
+        }
+        if let Some(facets) = &self.facets {
+            map.serialize_entry("facets", facets)?;
+        }
+        map.end()
+    }
+}
+
+impl<T: ToSchema + PageItem> ToSchema for Page<T> {
+    /// utoipa appends the item type to generic names itself, e.g. `ApiResponse_Page_InviteResponse`.
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("Page")
+    }
+
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        schemas.push((T::name().into_owned(), T::schema()));
+        T::schemas(schemas);
+        schemas.push((Pagination::name().into_owned(), Pagination::schema()));
+        schemas.push((PageLinks::name().into_owned(), PageLinks::schema()));
+        schemas.push((FacetCount::name().into_owned(), FacetCount::schema()));
+    }
+}
+
+/// Implemented instead of `PartialSchema`, which utoipa derives from it, so that `Page<T>` can
+/// also be the generic argument of derived schemas such as `ApiResponse<T>`.
+impl<T: ToSchema + PageItem> ComposeSchema for Page<T> {
+    fn compose(_: Vec<RefOr<Schema>>) -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .property(T::KEY, ArrayBuilder::new().items(Ref::from_schema_name(T::name())))
+            .required(T::KEY)
+            .property("pagination", Ref::from_schema_name(Pagination::name()))
+            .required("pagination")
+            .property("links", Ref::from_schema_name(PageLinks::name()))
+            .property(
+                "facets",
+                ObjectBuilder::new().additional_properties(Some(
+                    ArrayBuilder::new().items(Ref::from_schema_name(FacetCount::name())),
+                )),
+            )
+            .into()
+    }
+}
+
+/// The request URI with `key` set to `value`, keeping every other query parameter as sent.
+fn replace_query_param(uri: &Uri, key: &str, value: &str) -> String {
+    let mut params: Vec<String> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty() && param.split('=').next() != Some(key))
+        .map(str::to_string)
+        .collect();
+    params.push(format!("{}={}", key, value));
+    format!("{}?{}", uri.path(), params.join("&"))
 }
 
 pub fn compute_pagination(page: Option<i64>, size: Option<i64>, total: i64) -> (i64, i64, i64, i64) {
@@ -56,13 +220,39 @@ pub async fn fetch_with_filters<T>(
     offset: i64,
     pool: &PgPool,
 ) -> Result<Vec<T>, sqlx::Error>
+where
+    T: for<'r> sqlx::FromRow<'r, PgRow> + Unpin + Send,
+{
+    fetch_with_filters_ordered(
+        base_query,
+        apply_filters,
+        |mut builder| {
+            builder.push(" ORDER BY created_at DESC ");
+            builder
+        },
+        page_size,
+        offset,
+        pool,
+    )
+    .await
+}
+
+/// Same as `fetch_with_filters`, for listings not ordered by recency, such as search results
+/// ordered by rank. `apply_order` must push the `ORDER BY` clause.
+pub async fn fetch_with_filters_ordered<'q, T>(
+    base_query: &'q str,
+    apply_filters: impl FnOnce(QueryBuilder<'q, Postgres>) -> QueryBuilder<'q, Postgres>,
+    apply_order: impl FnOnce(QueryBuilder<'q, Postgres>) -> QueryBuilder<'q, Postgres>,
+    page_size: i64,
+    offset: i64,
+    pool: &PgPool,
+) -> Result<Vec<T>, sqlx::Error>
 where
     T: for<'r> sqlx::FromRow<'r, PgRow> + Unpin + Send,
 {
     let builder = QueryBuilder::new(base_query);
-    let mut builder = apply_filters(builder);
+    let mut builder = apply_order(apply_filters(builder));
     builder
-        .push(" ORDER BY created_at DESC ")
         .push(" LIMIT ")
         .push_bind(page_size)
         .push(" OFFSET ")
@@ -71,3 +261,212 @@ where
     builder.build_query_as::<T>().fetch_all(pool).await
 }
 
+/// The SQL type of a keyset column, needed to bind cursor values back into a query.
+#[derive(Clone, Copy, Debug)]
+pub enum KeysetKind {
+    BigInt,
+    Int,
+    Real,
+    Text,
+    Timestamp,
+}
+
+/// One column of an ordering that can be paginated with cursors. Expressions must never be
+/// NULL, and the last column of an ordering must be unique, usually `id`.
+#[derive(Clone, Debug)]
+pub struct KeysetColumn {
+    expression: String,
+    /// A parameter the expression takes, bound after `expression` and followed by the suffix.
+    bind: Option<(String, &'static str)>,
+    kind: KeysetKind,
+    descending: bool,
+}
+
+impl KeysetColumn {
+    pub fn new(expression: impl Into<String>, kind: KeysetKind, descending: bool) -> Self {
+        Self {
+            expression: expression.into(),
+            bind: None,
+            kind,
+            descending,
+        }
+    }
+
+    /// A column whose expression takes a parameter, such as a search rank.
+    pub fn with_bind(
+        prefix: impl Into<String>,
+        value: String,
+        suffix: &'static str,
+        kind: KeysetKind,
+        descending: bool,
+    ) -> Self {
+        Self {
+            expression: prefix.into(),
+            bind: Some((value, suffix)),
+            kind,
+            descending,
+        }
+    }
+
+    fn push_expression(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push(&self.expression);
+        if let Some((value, suffix)) = &self.bind {
+            builder.push_bind(value.clone()).push(*suffix);
+        }
+    }
+
+    /// Values that do not match the column's type are bound as NULL, which matches nothing.
+    fn push_value(&self, builder: &mut QueryBuilder<'_, Postgres>, value: &Value) {
+        match self.kind {
+            KeysetKind::BigInt => builder.push_bind(value.as_i64()),
+            KeysetKind::Int => builder.push_bind(value.as_i64().and_then(|v| i32::try_from(v).ok())),
+            KeysetKind::Real => builder.push_bind(value.as_f64().map(|v| v as f32)),
+            KeysetKind::Text => builder.push_bind(value.as_str().map(str::to_string)),
+            KeysetKind::Timestamp => builder.push_bind(
+                value
+                    .as_str()
+                    .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                    .map(|v| v.with_timezone(&Utc)),
+            ),
+        };
+    }
+}
+
+/// Pushes `ORDER BY` for the given columns, reversed when reading backwards from a cursor.
+pub fn push_keyset_order(builder: &mut QueryBuilder<'_, Postgres>, columns: &[KeysetColumn], backward: bool) {
+    builder.push(" ORDER BY ");
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            builder.push(", ");
+        }
+        column.push_expression(builder);
+        builder.push(if column.descending != backward { " DESC" } else { " ASC" });
+    }
+}
+
+/// Position of a row within an ordering. Encoded as an opaque token, it replaces the page number
+/// so that rows inserted or removed before the cursor do not shift the following pages.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Cursor {
+    /// Identifies the ordering the cursor was issued for, as its values mean nothing in another.
+    #[serde(rename = "o")]
+    pub ordering: String,
+    /// Whether the page is read backwards from the cursor, i.e. the one before it.
+    #[serde(rename = "b", default)]
+    pub backward: bool,
+    #[serde(rename = "v")]
+    pub values: Vec<Value>,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(token).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+/// Keyset counterpart of `fetch_with_filters`: fetches the page after (or before) `cursor`, or
+/// the first page without one. `apply_filters` must leave a `WHERE` clause open for the
+/// cursor condition to be appended with `AND`.
+pub async fn fetch_with_cursor<'q, T>(
+    table: &str,
+    apply_filters: impl FnOnce(QueryBuilder<'q, Postgres>) -> QueryBuilder<'q, Postgres>,
+    columns: &[KeysetColumn],
+    ordering: &str,
+    cursor: Option<Cursor>,
+    page_size: i64,
+    pool: &PgPool,
+) -> Result<CursorPage<T>, sqlx::Error>
+where
+    T: for<'r> FromRow<'r, PgRow>,
+{
+    let backward = cursor.as_ref().is_some_and(|cursor| cursor.backward);
+
+    // The ordering values of every row are selected along with it, to build the next cursors from.
+    let mut builder = QueryBuilder::new("SELECT *, jsonb_build_array(");
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            builder.push(", ");
+        }
+        column.push_expression(&mut builder);
+    }
+    builder.push(format!(") AS keyset_values FROM {}", table));
+
+    let mut builder = apply_filters(builder);
+    if let Some(cursor) = &cursor {
+        push_keyset_condition(&mut builder, columns, cursor);
+    }
+    push_keyset_order(&mut builder, columns, backward);
+    // One extra row tells whether there is anything past this page.
+    builder.push(" LIMIT ").push_bind(page_size + 1);
+
+    let mut rows = builder.build().fetch_all(pool).await?;
+    let has_more = rows.len() as i64 > page_size;
+    rows.truncate(page_size as usize);
+    if backward {
+        rows.reverse();
+    }
+
+    let (more_after, more_before) = match &cursor {
+        None => (has_more, false),
+        Some(_) if backward => (true, has_more),
+        Some(_) => (has_more, true),
+    };
+
+    let cursor_at = |row: Option<&PgRow>, backward: bool| -> Result<Option<String>, sqlx::Error> {
+        let Some(row) = row else { return Ok(None) };
+        let values: Value = row.try_get("keyset_values")?;
+        Ok(Some(
+            Cursor {
+                ordering: ordering.to_string(),
+                backward,
+                values: values.as_array().cloned().unwrap_or_default(),
+            }
+            .encode(),
+        ))
+    };
+
+    let next_cursor = if more_after { cursor_at(rows.last(), false)? } else { None };
+    let prev_cursor = if more_before { cursor_at(rows.first(), true)? } else { None };
+    let items = rows.iter().map(T::from_row).collect::<Result<Vec<T>, _>>()?;
+
+    Ok(CursorPage {
+        items,
+        next_cursor,
+        prev_cursor,
+    })
+}
+
+/// Rows past the cursor in the current direction: greater on the first column, or equal on it
+/// and past the cursor on the next one, and so on. Spelled out per column rather than as a
+/// row comparison because columns may sort in different directions.
+fn push_keyset_condition(builder: &mut QueryBuilder<'_, Postgres>, columns: &[KeysetColumn], cursor: &Cursor) {
+    builder.push(" AND (");
+    for (i, column) in columns.iter().enumerate() {
+        if i > 0 {
+            builder.push(" OR ");
+        }
+        builder.push("(");
+        for (equal, value) in columns.iter().zip(&cursor.values).take(i) {
+            equal.push_expression(builder);
+            builder.push(" = ");
+            equal.push_value(builder, value);
+            builder.push(" AND ");
+        }
+        column.push_expression(builder);
+        builder.push(if column.descending != cursor.backward { " < " } else { " > " });
+        column.push_value(builder, cursor.values.get(i).unwrap_or(&Value::Null));
+        builder.push(")");
+    }
+    builder.push(")");
+}
\ No newline at end of file
@@ -0,0 +1,159 @@
+use crate::models::passkey::PasskeyCredential;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RelyingParty {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PasskeyUserEntity {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PublicKeyCredentialParameters {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub alg: i64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PublicKeyCredentialDescriptor {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AuthenticatorSelection {
+    #[serde(rename = "residentKey")]
+    pub resident_key: String,
+    #[serde(rename = "userVerification")]
+    pub user_verification: String,
+}
+
+/// `PublicKeyCredentialCreationOptions` in the WebAuthn JSON encoding.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PasskeyRegistrationOptions {
+    pub challenge: String,
+    pub rp: RelyingParty,
+    pub user: PasskeyUserEntity,
+    #[serde(rename = "pubKeyCredParams")]
+    pub pub_key_cred_params: Vec<PublicKeyCredentialParameters>,
+    pub timeout: u64,
+    pub attestation: String,
+    #[serde(rename = "excludeCredentials")]
+    pub exclude_credentials: Vec<PublicKeyCredentialDescriptor>,
+    #[serde(rename = "authenticatorSelection")]
+    pub authenticator_selection: AuthenticatorSelection,
+}
+
+/// `PublicKeyCredentialRequestOptions` in the WebAuthn JSON encoding.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PasskeyLoginOptions {
+    pub challenge: String,
+    #[serde(rename = "rpId")]
+    pub rp_id: String,
+    pub timeout: u64,
+    #[serde(rename = "userVerification")]
+    pub user_verification: String,
+    #[serde(rename = "allowCredentials")]
+    pub allow_credentials: Vec<PublicKeyCredentialDescriptor>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    #[serde(rename = "attestationObject")]
+    pub attestation_object: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RegistrationCredential {
+    pub id: String,
+    #[serde(rename = "rawId")]
+    pub raw_id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub response: AttestationResponse,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct PasskeyRegistrationRequest {
+    #[validate(length(min = 1, max = 100, message = "Passkey name must be between 1 and 100 characters"))]
+    pub name: String,
+    pub credential: RegistrationCredential,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct PasskeyLoginOptionsRequest {
+    #[validate(email(message = "Email must be valid"))]
+    pub email: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    #[serde(rename = "authenticatorData")]
+    pub authenticator_data: String,
+    pub signature: String,
+    #[serde(rename = "userHandle")]
+    pub user_handle: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AuthenticationCredential {
+    pub id: String,
+    #[serde(rename = "rawId")]
+    pub raw_id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub response: AssertionResponse,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PasskeyLoginRequest {
+    pub credential: AuthenticationCredential,
+    #[serde(default, rename = "rememberMe")]
+    pub remember_me: bool,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct RenamePasskeyRequest {
+    #[validate(length(min = 1, max = 100, message = "Passkey name must be between 1 and 100 characters"))]
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PasskeyResponse {
+    pub id: i64,
+    pub name: String,
+    #[serde(rename = "credentialId")]
+    pub credential_id: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+    #[serde(rename = "lastUsedAt")]
+    pub last_used_at: Option<DateTime<Local>>,
+}
+
+impl PasskeyResponse {
+    pub fn from_credential(credential: &PasskeyCredential) -> Self {
+        Self {
+            id: credential.id,
+            name: credential.name.clone(),
+            credential_id: credential.credential_id.clone(),
+            created_at: credential.created_at,
+            last_used_at: credential.last_used_at,
+        }
+    }
+}
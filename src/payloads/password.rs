@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Personal details are optional; when given, passwords built from them score lower.
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct PasswordStrengthRequest {
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+
+    pub email: Option<String>,
+
+    #[serde(rename = "firstName")]
+    pub first_name: Option<String>,
+
+    #[serde(rename = "lastName")]
+    pub last_name: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PasswordStrengthResponse {
+    /// 0 (very weak) to 4 (very strong).
+    pub score: u8,
+
+    /// Whether the password satisfies the configured policy.
+    pub acceptable: bool,
+
+    pub feedback: Vec<String>,
+}
@@ -0,0 +1,56 @@
+use crate::enums::token_scope::TokenScope;
+use crate::models::personal_access_token::PersonalAccessToken;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct CreatePersonalAccessTokenRequest {
+    #[validate(length(min = 1, max = 100, message = "Token name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<TokenScope>,
+
+    #[serde(rename = "expiresInDays")]
+    #[validate(range(min = 1, max = 365, message = "Token expiry must be between 1 and 365 days"))]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PersonalAccessTokenResponse {
+    pub id: i64,
+    pub name: String,
+    #[serde(rename = "tokenPrefix")]
+    pub token_prefix: String,
+    pub scopes: Vec<TokenScope>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Local>>,
+    #[serde(rename = "lastUsedAt")]
+    pub last_used_at: Option<DateTime<Local>>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+}
+
+impl PersonalAccessTokenResponse {
+    pub fn from_token(token: &PersonalAccessToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name.clone(),
+            token_prefix: token.token_prefix.clone(),
+            scopes: token.scopes.iter().filter_map(|s| TokenScope::parse(s)).collect(),
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+/// Returned only once, when the token is created. The plain token is never stored.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreatedPersonalAccessTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub details: PersonalAccessTokenResponse,
+}
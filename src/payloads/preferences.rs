@@ -0,0 +1,49 @@
+use crate::enums::application::ApplicationType;
+use crate::enums::preferences::DateFormat;
+use crate::utils::validator_util::{validate_timezone, LOCALE_REGEX};
+use serde::{Deserialize, Deserializer};
+use utoipa::ToSchema;
+use validator::Validate;
+
+/// Partial update of the preferences document; omitted fields keep their current value.
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct UpdatePreferencesRequest {
+    #[validate(custom(function = "validate_timezone"))]
+    pub timezone: Option<String>,
+
+    #[validate(regex(path = "*LOCALE_REGEX", message = "Locale must look like en or en-US"))]
+    pub locale: Option<String>,
+
+    #[serde(rename = "dateFormat")]
+    pub date_format: Option<DateFormat>,
+
+    #[serde(rename = "defaultPageSize")]
+    #[validate(range(min = 1, max = 100, message = "Default page size must be between 1 and 100"))]
+    pub default_page_size: Option<i64>,
+
+    /// Send `null` to clear the default application type.
+    #[serde(default, rename = "defaultApplicationType", deserialize_with = "deserialize_present")]
+    #[schema(value_type = Option<ApplicationType>)]
+    pub default_application_type: Option<Option<ApplicationType>>,
+
+    pub notifications: Option<UpdateNotificationPreferencesRequest>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateNotificationPreferencesRequest {
+    #[serde(rename = "securityAlerts")]
+    pub security_alerts: Option<bool>,
+    #[serde(rename = "productUpdates")]
+    pub product_updates: Option<bool>,
+    #[serde(rename = "applicationReminders")]
+    pub application_reminders: Option<bool>,
+}
+
+/// Distinguishes an explicit `null` (`Some(None)`) from an omitted field (`None`).
+fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,75 @@
+use crate::enums::application::ApplicationColumn;
+use crate::models::saved_view::SavedView;
+use crate::payloads::application::ApplicationFilter;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct CreateSavedViewRequest {
+    #[validate(length(min = 1, max = 100, message = "View name must be between 1 and 100 characters"))]
+    pub name: String,
+
+    /// The same filters, sort and page size the application list accepts, as JSON. List
+    /// filters may be given as arrays or comma separated strings.
+    #[validate(nested)]
+    #[serde(default)]
+    pub filter: ApplicationFilter,
+
+    #[serde(default)]
+    pub columns: Vec<ApplicationColumn>,
+
+    /// Whether listing views also counts the applications each one currently matches.
+    #[serde(rename = "showCount", default)]
+    pub show_count: bool,
+}
+
+/// Partial update of a saved view; omitted fields keep their current value.
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct UpdateSavedViewRequest {
+    #[validate(length(min = 1, max = 100, message = "View name must be between 1 and 100 characters"))]
+    pub name: Option<String>,
+
+    /// Replaces the whole filter of the view.
+    #[validate(nested)]
+    pub filter: Option<ApplicationFilter>,
+
+    pub columns: Option<Vec<ApplicationColumn>>,
+
+    #[serde(rename = "showCount")]
+    pub show_count: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SavedViewResponse {
+    pub id: i64,
+    pub name: String,
+    pub filter: ApplicationFilter,
+    pub columns: Vec<ApplicationColumn>,
+    #[serde(rename = "showCount")]
+    pub show_count: bool,
+    /// Applications currently matching the view, only when `showCount` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Local>,
+}
+
+impl SavedViewResponse {
+    pub fn from_saved_view(view: &SavedView, count: Option<i64>) -> Self {
+        Self {
+            id: view.id,
+            name: view.name.clone(),
+            filter: view.filter.0.clone(),
+            columns: view.columns.0.clone(),
+            show_count: view.show_count,
+            count,
+            created_at: view.created_at,
+            updated_at: view.updated_at,
+        }
+    }
+}
+
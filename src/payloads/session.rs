@@ -0,0 +1,40 @@
+use crate::models::session::UserSession;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct SessionResponse {
+    pub id: i64,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(rename = "ipAddress")]
+    pub ip_address: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Local>,
+    #[serde(rename = "lastSeenAt")]
+    pub last_seen_at: DateTime<Local>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Local>,
+    /// Whether this is the session making the request.
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub fn from_session(session: &UserSession, current_session_id: Option<i64>) -> Self {
+        Self {
+            id: session.id,
+            user_agent: session.user_agent.clone(),
+            ip_address: session.ip_address.clone(),
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
+            expires_at: session.expires_at,
+            current: current_session_id == Some(session.id),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RevokedSessionsResponse {
+    pub revoked: u64,
+}
@@ -27,11 +27,12 @@ pub struct UserRequest {
     )]
     pub phone_number: String,
 
-    #[validate(length(min = 6, message = "Password must be more than 5 characters long"))]
+    /// Checked against the password policy rather than a fixed length.
     pub password: String,
 
-    #[serde(skip)]
-    pub role: Option<Role>,
+    /// Required when registration is invite-only. Always validated when present.
+    #[serde(rename = "inviteToken")]
+    pub invite_token: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -76,3 +77,42 @@ impl UserInfo {
         }
     }
 }
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct DeleteAccountRequest {
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AccountDeletionResponse {
+    #[serde(rename = "purgeAfter")]
+    pub purge_after: DateTime<Local>,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct CancelAccountDeletionRequest {
+    #[validate(email(message = "Email must be valid"))]
+    pub email: String,
+
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+#[derive(Validate, Deserialize, ToSchema)]
+pub struct UpdateProfileRequest {
+    #[serde(rename = "firstName")]
+    #[validate(length(min = 1, max = 100, message = "First name must be between 1 and 100 characters"))]
+    pub first_name: Option<String>,
+
+    #[serde(rename = "lastName")]
+    #[validate(length(min = 1, max = 100, message = "Last name must be between 1 and 100 characters"))]
+    pub last_name: Option<String>,
+
+    #[serde(rename = "phoneNumber")]
+    #[validate(
+        regex(path = "*PHONE_REGEX", message = "Invalid phone number format (e.g., +1234567890123)"),
+        length(min = 14, message = "Phone number must be at least 14 characters")
+    )]
+    pub phone_number: Option<String>,
+}
@@ -1,14 +1,40 @@
+use crate::enums::application::{ApplicationFacet, ApplicationSortField, BulkOutcome, DuplicateMatch, SortDirection};
 use crate::models::application::{Application, ApplicationStatus};
 use crate::payloads::application::{
-    ApplicationFilter, ApplicationStatusResponse, ApplicationsResponse,
+    ApplicationFilter, ApplicationStatusResponse, ApplicationsResponse, CompanySuggestion,
+    BulkApplicationAction, BulkApplicationResult, BulkApplicationState, DuplicateApplication,
+    DuplicateCandidate, normalize_tag,
 };
-use crate::payloads::pagination::{build_paginated_response, compute_pagination, count_with_filters, fetch_with_filters};
-use serde_json::Value;
+use crate::payloads::pagination::{
+    compute_pagination, count_with_filters, fetch_with_cursor, fetch_with_filters_ordered, push_keyset_order, Cursor,
+    CursorPage, FacetCount, KeysetColumn, KeysetKind, Page,
+};
+use crate::utils::search_util::to_prefix_tsquery;
 use sqlx::{PgPool, Postgres, QueryBuilder, Row};
-use std::collections::HashMap;
+use chrono::Local;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use crate::payloads::dashboard::{ApplicationTrendsRequest, ApplicationTrendsResponse, DashboardCount, DatesCount, StatusCount, SuccessRate};
 
+/// The status an application is currently in, i.e. its most recent status change.
+const LATEST_STATUS: &str = "(SELECT s.status_type FROM application_statuses s \
+     WHERE s.application_id = applications.id ORDER BY s.created_at DESC, s.id DESC LIMIT 1)";
+
+/// The most recent status change, or the creation of the application when it has none.
+const LAST_ACTIVITY: &str = "GREATEST(created_at, (SELECT MAX(s.created_at) FROM application_statuses s \
+     WHERE s.application_id = applications.id))";
+
+/// When the application entered its current status, or its creation when it has none.
+const STAGE_ENTERED_AT: &str = "COALESCE((SELECT MAX(s.created_at) FROM application_statuses s \
+     WHERE s.application_id = applications.id), created_at)";
+
+/// Facets with many values, such as companies, only return the most frequent ones.
+const MAX_FACET_VALUES: i64 = 50;
+
+/// Completed by binding the interview types and closing both parentheses.
+const HAS_INTERVIEW: &str = "SELECT 1 FROM application_statuses s WHERE s.application_id = applications.id \
+     AND s.status_type = 'Interview' AND s.interview_type = ANY(";
+
 pub struct ApplicationRepository {
     pub pool: Arc<PgPool>,
 }
@@ -42,11 +68,12 @@ impl ApplicationRepository {
         .await
     }
 
-    pub async fn exists_by_application_id(&self, application_id: i64) -> Result<bool, sqlx::Error> {
+    pub async fn exists_by_application_id(&self, application_id: i64, created_by: i64) -> Result<bool, sqlx::Error> {
         let exists = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM applications WHERE id = $1)",
+            "SELECT EXISTS(SELECT 1 FROM applications WHERE id = $1 AND created_by = $2 AND deleted = false)",
         )
         .bind(application_id)
+        .bind(created_by)
         .fetch_one(self.pool.as_ref())
         .await?;
 
@@ -75,29 +102,338 @@ impl ApplicationRepository {
             .await
     }
 
-    pub async fn find_applications_by_user_with_filters(
+    /// Companies from the user's applications that look like `search`: trigram similar to it,
+    /// containing it as a word, or starting with it so that suggestions show up while typing.
+    /// Spellings differing only in case are grouped together.
+    pub async fn find_similar_companies(
+        &self,
+        created_by: i64,
+        search: &str,
+        limit: i64,
+    ) -> Result<Vec<CompanySuggestion>, sqlx::Error> {
+        sqlx::query_as::<_, CompanySuggestion>(
+            r#"
+            SELECT MIN(company) AS company,
+                   COUNT(*) AS applications,
+                   MAX(GREATEST(similarity(LOWER(company), $2), word_similarity($2, LOWER(company)))) AS score
+            FROM applications
+            WHERE created_by = $1 AND deleted = false
+              AND (LOWER(company) % $2 OR $2 <% LOWER(company) OR starts_with(LOWER(company), $2))
+            GROUP BY LOWER(company)
+            ORDER BY bool_or(starts_with(LOWER(company), $2)) DESC, score DESC, applications DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(created_by)
+        .bind(search.trim().to_lowercase())
+        .bind(limit)
+        .fetch_all(self.pool.as_ref())
+        .await
+    }
+
+    /// Applications made in the last `window_days` days to the same position at the same company
+    /// as the one given, once both are normalized. The website only adds to what matched: job
+    /// boards host the postings of many companies, so a shared domain proves nothing on its own.
+    pub async fn find_possible_duplicates(
+        &self,
+        created_by: i64,
+        company: &str,
+        position: &str,
+        website: Option<&str>,
+        window_days: i32,
+    ) -> Result<Vec<DuplicateApplication>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            SELECT id, company, position, website, created_at,
+                   COALESCE(website_domain(website) = website_domain($4), false) AS same_website
+            FROM applications
+            WHERE created_by = $1 AND deleted = false
+              AND created_at >= NOW() - make_interval(days => $5)
+              AND normalize_position(position) = normalize_position($3)
+              AND normalize_company(company) = normalize_company($2)
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(created_by)
+        .bind(company)
+        .bind(position)
+        .bind(website)
+        .bind(window_days)
+        .fetch_all(self.pool.as_ref())
+        .await?
+        .into_iter()
+        .map(|row| {
+            let mut matched_on = vec![DuplicateMatch::Company];
+            if row.try_get("same_website")? {
+                matched_on.push(DuplicateMatch::Website);
+            }
+            Ok(DuplicateApplication {
+                id: row.try_get("id")?,
+                company: row.try_get("company")?,
+                position: row.try_get("position")?,
+                website: row.try_get("website")?,
+                created_at: row.try_get("created_at")?,
+                matched_on,
+            })
+        })
+        .collect()
+    }
+
+    /// Every application of the user with its duplicate detection keys, oldest first.
+    pub async fn find_duplicate_candidates(
+        &self,
+        created_by: i64,
+    ) -> Result<Vec<DuplicateCandidate>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            SELECT id, company, position, website, created_at,
+                   normalize_company(company) AS company_key,
+                   normalize_position(position) AS position_key,
+                   website_domain(website) AS domain
+            FROM applications
+            WHERE created_by = $1 AND deleted = false
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(created_by)
+        .fetch_all(self.pool.as_ref())
+        .await?
+        .into_iter()
+        .map(|row| {
+            Ok(DuplicateCandidate {
+                application: DuplicateApplication {
+                    id: row.try_get("id")?,
+                    company: row.try_get("company")?,
+                    position: row.try_get("position")?,
+                    website: row.try_get("website")?,
+                    created_at: row.try_get("created_at")?,
+                    matched_on: Vec::new(),
+                },
+                company_key: row.try_get("company_key")?,
+                position_key: row.try_get("position_key")?,
+                domain: row.try_get("domain")?,
+            })
+        })
+        .collect()
+    }
+
+    pub async fn count_applications_by_user_with_filters(
         &self,
         created_by: i64,
         filter: ApplicationFilter,
-    ) -> Result<HashMap<String, Value>, sqlx::Error> {
-        let total = count_with_filters(
+    ) -> Result<i64, sqlx::Error> {
+        count_with_filters(
             "SELECT COUNT(*) FROM applications",
-            |b| self.apply_application_filters(b, filter.clone(), created_by.clone()),
+            |b| self.apply_application_filters(b, filter, created_by),
             self.pool.as_ref(),
         )
-        .await?;
+        .await
+    }
+
+    /// Ids of the applications the list would return for the filter, at most `limit` of them.
+    pub async fn find_ids_by_user_with_filters(
+        &self,
+        created_by: i64,
+        filter: ApplicationFilter,
+        limit: i64,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let builder = QueryBuilder::new("SELECT id FROM applications");
+        let mut builder = self.apply_application_filters(builder, filter, created_by);
+        builder.push(" ORDER BY id LIMIT ").push_bind(limit);
+        builder.build_query_scalar().fetch_all(self.pool.as_ref()).await
+    }
+
+    /// Applies the action to the user's applications among `ids` in one transaction, reporting
+    /// what happened to each id in the order given. Ids of other users' applications are
+    /// reported as not found.
+    pub async fn apply_bulk_action(
+        &self,
+        created_by: i64,
+        ids: &[i64],
+        action: &BulkApplicationAction,
+    ) -> Result<Vec<BulkApplicationResult>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let states: HashMap<i64, BulkApplicationState> = sqlx::query_as::<_, BulkApplicationState>(
+            r#"
+            SELECT id, deleted, archived_at IS NOT NULL AS archived, tags,
+                   (SELECT s.status_type FROM application_statuses s
+                    WHERE s.application_id = applications.id
+                    ORDER BY s.created_at DESC, s.id DESC LIMIT 1) AS latest_status
+            FROM applications
+            WHERE created_by = $1 AND id = ANY($2)
+            ORDER BY id
+            FOR UPDATE
+            "#,
+        )
+        .bind(created_by)
+        .bind(ids)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|state| (state.id, state))
+        .collect();
+
+        let mut results = Vec::with_capacity(ids.len());
+        let mut updated_ids = Vec::new();
+        for &id in ids {
+            let (outcome, reason) = match states.get(&id) {
+                None => (BulkOutcome::NotFound, Some("Application not found.")),
+                Some(state) => match action.skip_reason(state) {
+                    Some(reason) => (BulkOutcome::Skipped, Some(reason)),
+                    None => {
+                        updated_ids.push(id);
+                        (BulkOutcome::Updated, None)
+                    }
+                },
+            };
+            results.push(BulkApplicationResult { id, outcome, reason: reason.map(String::from) });
+        }
+
+        if updated_ids.is_empty() {
+            return Ok(results);
+        }
+
+        let now = Local::now();
+        let (set, tags) = match action {
+            BulkApplicationAction::AddStatus { status, notes } => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO application_statuses (application_id, status_type, created_by, created_at, notes)
+                    SELECT id, $2, $3, $4, $5 FROM UNNEST($1::BIGINT[]) AS id
+                    "#,
+                )
+                .bind(&updated_ids)
+                .bind(status)
+                .bind(created_by)
+                .bind(now)
+                .bind(notes)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+                return Ok(results);
+            }
+            BulkApplicationAction::Delete => ("deleted = true, deleted_at = $2", None),
+            BulkApplicationAction::Restore => ("deleted = false, deleted_at = NULL", None),
+            BulkApplicationAction::Tag { tags } => (
+                "tags = ARRAY(SELECT DISTINCT t FROM UNNEST(tags || $3::TEXT[]) AS t ORDER BY t)",
+                Some(tags),
+            ),
+            BulkApplicationAction::Untag { tags } => (
+                "tags = ARRAY(SELECT t FROM UNNEST(tags) AS t WHERE t <> ALL($3::TEXT[]))",
+                Some(tags),
+            ),
+            BulkApplicationAction::Archive => ("archived_at = $2", None),
+            BulkApplicationAction::Unarchive => ("archived_at = NULL", None),
+        };
+
+        let sql = format!("UPDATE applications SET {}, updated_at = $2 WHERE id = ANY($1)", set);
+        let mut query = sqlx::query(&sql).bind(&updated_ids).bind(now);
+        if let Some(tags) = tags {
+            query = query.bind(tags);
+        }
+        query.execute(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(results)
+    }
+
+    /// Counts the applications per value of each facet, under the filter without the facet's
+    /// own filters. Companies are grouped case insensitively, as the company filter matches.
+    pub async fn find_facet_counts(
+        &self,
+        created_by: i64,
+        filter: &ApplicationFilter,
+        facets: &[ApplicationFacet],
+    ) -> Result<BTreeMap<String, Vec<FacetCount>>, sqlx::Error> {
+        let mut counts = BTreeMap::new();
+        for facet in facets {
+            let (value, group) = match facet {
+                ApplicationFacet::Status => (LATEST_STATUS.to_string(), "1"),
+                ApplicationFacet::ApplicationType => ("application_type".to_string(), "1"),
+                ApplicationFacet::Company => ("MIN(company)".to_string(), "LOWER(company)"),
+            };
+
+            let builder = QueryBuilder::new(format!("SELECT {} AS value, COUNT(*) AS count FROM applications", value));
+            let mut builder = self.apply_application_filters(builder, filter.clone().without_facet(*facet), created_by);
+            builder
+                .push(format!(" GROUP BY {} ORDER BY count DESC, value LIMIT ", group))
+                .push_bind(MAX_FACET_VALUES);
+
+            let values = builder.build_query_as::<FacetCount>().fetch_all(self.pool.as_ref()).await?;
+            counts.insert(facet.as_str().to_string(), values);
+        }
+        Ok(counts)
+    }
+
+    pub async fn find_applications_by_user_with_filters(
+        &self,
+        created_by: i64,
+        filter: ApplicationFilter,
+    ) -> Result<Page<ApplicationsResponse>, sqlx::Error> {
+        let total = self.count_applications_by_user_with_filters(created_by, filter.clone()).await?;
 
         let (page, size, offset, total_pages) = compute_pagination(filter.page, filter.size, total);
+        let search_query = filter.search.as_deref().and_then(to_prefix_tsquery);
+        let ordering = ordering_columns(&filter, search_query.clone());
 
-        let applications: Vec<Application> = fetch_with_filters(
+        let applications: Vec<Application> = fetch_with_filters_ordered(
             "SELECT * FROM applications",
             |b| self.apply_application_filters(b, filter, created_by),
+            |mut b| {
+                push_keyset_order(&mut b, &ordering, false);
+                b
+            },
             size,
             offset,
             self.pool.as_ref(),
         )
         .await?;
 
+        let data = self.to_applications_response(applications, search_query).await?;
+
+        // -------- RETURN PAGINATED RESULT --------
+        Ok(Page::offset(data, page, total, total_pages))
+    }
+
+    /// Cursor paginated variant of `find_applications_by_user_with_filters`, starting from the
+    /// first page when no cursor is given.
+    pub async fn find_applications_by_user_with_cursor(
+        &self,
+        created_by: i64,
+        filter: ApplicationFilter,
+        cursor: Option<Cursor>,
+    ) -> Result<Page<ApplicationsResponse>, sqlx::Error> {
+        let total = self.count_applications_by_user_with_filters(created_by, filter.clone()).await?;
+
+        let (_, size, _, _) = compute_pagination(None, filter.size, total);
+        let search_query = filter.search.as_deref().and_then(to_prefix_tsquery);
+        let ordering = ordering_columns(&filter, search_query.clone());
+        let ordering_key = filter.ordering();
+
+        let page: CursorPage<Application> = fetch_with_cursor(
+            "applications",
+            |b| self.apply_application_filters(b, filter, created_by),
+            &ordering,
+            &ordering_key,
+            cursor,
+            size,
+            self.pool.as_ref(),
+        )
+        .await?;
+
+        let data = self.to_applications_response(page.items, search_query).await?;
+
+        Ok(Page::cursor(data, total, page.next_cursor, page.prev_cursor))
+    }
+
+    /// Attaches the status history, and the search snippet when searching, to each application.
+    async fn to_applications_response(
+        &self,
+        applications: Vec<Application>,
+        search_query: Option<String>,
+    ) -> Result<Vec<ApplicationsResponse>, sqlx::Error> {
         // -------- FETCH STATUSES --------
         let application_ids: Vec<i64> = applications.iter().map(|app| app.id).collect();
         let statuses: Vec<ApplicationStatus> = sqlx::query_as::<_, ApplicationStatus>(
@@ -112,6 +448,11 @@ impl ApplicationRepository {
         .fetch_all(self.pool.as_ref())
         .await?;
 
+        let mut snippets = match &search_query {
+            Some(search_query) => self.find_search_snippets(&application_ids, search_query).await?,
+            None => HashMap::new(),
+        };
+
         // -------- GROUP STATUSES --------
         let mut status_map: HashMap<i64, Vec<ApplicationStatusResponse>> = HashMap::new();
         for status in statuses {
@@ -122,7 +463,7 @@ impl ApplicationRepository {
         }
 
         // -------- COMBINE INTO ApplicationsResponse --------
-        let data: Vec<ApplicationsResponse> = applications
+        let data = applications
             .into_iter()
             .map(|app| ApplicationsResponse {
                 id: app.id,
@@ -130,6 +471,8 @@ impl ApplicationRepository {
                 position: app.position,
                 website: app.website,
                 application_type: app.application_type,
+                tags: app.tags,
+                archived_at: app.archived_at,
                 created_at: app.created_at,
                 created_by: app.created_by,
                 status: status_map
@@ -138,11 +481,68 @@ impl ApplicationRepository {
                     .map(|s| s.status.clone())
                     .unwrap(),
                 status_history: status_map.remove(&app.id).unwrap_or_else(Vec::new),
+                search_snippet: snippets.remove(&app.id),
             })
             .collect();
 
-        // -------- RETURN PAGINATED RESULT --------
-        Ok(build_paginated_response(data, page, total, total_pages, "applications"))
+        Ok(data)
+    }
+
+    pub async fn find_all_by_user_id(&self, created_by: i64) -> Result<Vec<Application>, sqlx::Error> {
+        sqlx::query_as::<_, Application>(
+            "SELECT * FROM applications WHERE created_by = $1 ORDER BY created_at ASC",
+        )
+        .bind(created_by)
+        .fetch_all(self.pool.as_ref())
+        .await
+    }
+
+    pub async fn find_statuses_by_user_id(&self, created_by: i64) -> Result<Vec<ApplicationStatus>, sqlx::Error> {
+        sqlx::query_as::<_, ApplicationStatus>(
+            r#"
+            SELECT s.*
+            FROM application_statuses s
+            JOIN applications a ON a.id = s.application_id
+            WHERE a.created_by = $1
+            ORDER BY s.application_id ASC, s.created_at ASC
+            "#,
+        )
+        .bind(created_by)
+        .fetch_all(self.pool.as_ref())
+        .await
+    }
+
+    /// Excerpts of each application around the search terms, with matches wrapped in
+    /// `<mark>` tags. The text is HTML-escaped before highlighting, so the `<mark>` tags are
+    /// the only markup in a snippet.
+    async fn find_search_snippets(
+        &self,
+        application_ids: &[i64],
+        search_query: &str,
+    ) -> Result<HashMap<i64, String>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id,
+                   ts_headline(
+                       'simple',
+                       replace(replace(replace(replace(replace(
+                           concat_ws(' · ', company, position, website, application_notes(id)),
+                           '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;'),
+                       to_tsquery('simple', $2),
+                       'StartSel=<mark>, StopSel=</mark>, MaxWords=25, MinWords=8, MaxFragments=2, FragmentDelimiter=" … "'
+                   ) AS snippet
+            FROM applications
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(application_ids)
+        .bind(search_query)
+        .fetch_all(self.pool.as_ref())
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get("id")?, row.try_get("snippet")?)))
+            .collect()
     }
 
     pub fn apply_application_filters<'a>(
@@ -151,33 +551,84 @@ impl ApplicationRepository {
         filter: ApplicationFilter,
         created_by: i64,
     ) -> QueryBuilder<'a, Postgres> {
-        builder.push(" WHERE created_by = ").push_bind(created_by);
+        builder.push(" WHERE created_by = ").push_bind(created_by).push(" AND deleted = false");
 
+        if filter.archived == Some(true) {
+            builder.push(" AND archived_at IS NOT NULL");
+        } else {
+            builder.push(" AND archived_at IS NULL");
+        }
+
+        // A search with nothing searchable in it, e.g. only punctuation, matches nothing.
         if let Some(search) = filter.search {
-            let pattern = format!("%{}%", search);
+            match to_prefix_tsquery(&search) {
+                Some(search_query) => builder
+                    .push(" AND search_vector @@ to_tsquery('simple', ")
+                    .push_bind(search_query)
+                    .push(")"),
+                None => builder.push(" AND FALSE"),
+            };
+        }
+
+        if let Some(statuses) = filter.status {
+            builder
+                .push(format!(" AND {} = ANY(", LATEST_STATUS))
+                .push_bind(statuses)
+                .push(")");
+        }
+
+        if let Some(statuses) = filter.exclude_status {
             builder
-                .push(" AND (company ILIKE ")
-                .push_bind(pattern.clone())
-                .push(" OR position ILIKE ")
-                .push_bind(pattern.clone())
-                .push(" OR website ILIKE ")
-                .push_bind(pattern)
+                .push(format!(" AND COALESCE({}, '') <> ALL(", LATEST_STATUS))
+                .push_bind(statuses)
                 .push(")");
         }
 
-        if let Some(status) = filter.status {
+        if let Some(application_types) = filter.application_type {
             builder
-                .push(" AND id IN (")
-                .push("SELECT application_id FROM application_statuses AS s1 ")
-                .push("WHERE status_type = ")
-                .push_bind(status)
-                .push(" AND created_at = (")
-                .push("SELECT MAX(created_at) FROM application_statuses AS s2 ")
-                .push("WHERE s2.application_id = s1.application_id")
-                .push(")")
+                .push(" AND application_type = ANY(")
+                .push_bind(application_types)
                 .push(")");
         }
 
+        if let Some(application_types) = filter.exclude_application_type {
+            builder
+                .push(" AND (application_type IS NULL OR application_type <> ALL(")
+                .push_bind(application_types)
+                .push("))");
+        }
+
+        if let Some(companies) = filter.company {
+            let companies: Vec<String> = companies.iter().map(|company| company.to_lowercase()).collect();
+            builder.push(" AND LOWER(company) = ANY(").push_bind(companies).push(")");
+        }
+
+        if let Some(companies) = filter.exclude_company {
+            let companies: Vec<String> = companies.iter().map(|company| company.to_lowercase()).collect();
+            builder.push(" AND LOWER(company) <> ALL(").push_bind(companies).push(")");
+        }
+
+        if let Some(tags) = filter.tags {
+            let tags: Vec<String> = tags.iter().map(|tag| normalize_tag(tag)).collect();
+            builder.push(" AND tags && ").push_bind(tags);
+        }
+
+        if let Some(interview_types) = filter.interview_type {
+            builder
+                .push(" AND EXISTS (")
+                .push(HAS_INTERVIEW)
+                .push_bind(interview_types)
+                .push("))");
+        }
+
+        if let Some(interview_types) = filter.exclude_interview_type {
+            builder
+                .push(" AND NOT EXISTS (")
+                .push(HAS_INTERVIEW)
+                .push_bind(interview_types)
+                .push("))");
+        }
+
         if let Some(start) = filter.from {
             builder.push(" AND created_at >= ").push_bind(start);
         }
@@ -186,6 +637,21 @@ impl ApplicationRepository {
             builder.push(" AND created_at <= ").push_bind(end);
         }
 
+        if let Some(after) = filter.last_activity_after {
+            builder.push(format!(" AND {} >= ", LAST_ACTIVITY)).push_bind(after);
+        }
+
+        if let Some(before) = filter.last_activity_before {
+            builder.push(format!(" AND {} <= ", LAST_ACTIVITY)).push_bind(before);
+        }
+
+        if let Some(days) = filter.applied_within_days {
+            builder
+                .push(" AND created_at >= NOW() - make_interval(days => ")
+                .push_bind(days)
+                .push("::int)");
+        }
+
         builder
     }
 
@@ -284,7 +750,7 @@ impl ApplicationRepository {
         })
     }
 
-    pub async fn get_chart_data(&self, user_id: i64, req: ApplicationTrendsRequest) -> Result<ApplicationTrendsResponse, sqlx::Error> {
+    pub async fn get_chart_data(&self, user_id: i64, req: ApplicationTrendsRequest, timezone: &str) -> Result<ApplicationTrendsResponse, sqlx::Error> {
         let mut bar_query = QueryBuilder::new(
             r#"
         WITH latest_statuses AS (
@@ -302,6 +768,8 @@ impl ApplicationRepository {
         "#
         );
 
+        // Dates are bucketed by day in the user's timezone. All values are bound through the
+        // builder so the placeholders stay in order.
         let mut line_query = QueryBuilder::new(
             r#"
         WITH latest_statuses AS (
@@ -311,17 +779,28 @@ impl ApplicationRepository {
                 ast.status_type
             FROM applications a
             LEFT JOIN application_statuses ast ON a.id = ast.application_id
-            WHERE a.created_by = $1 AND a.deleted = false
+            WHERE a.created_by = "#
+        );
+        line_query
+            .push_bind(user_id)
+            .push(
+                r#" AND a.deleted = false
             ORDER BY a.id, ast.created_at DESC NULLS LAST
         )
         SELECT 
-            (DATE(created_at) || ' 00:00:00')::TIMESTAMPTZ as date, 
+            (DATE(created_at AT TIME ZONE "#,
+            )
+            .push_bind(timezone.to_string())
+            .push(") || ' 00:00:00')::TIMESTAMP AT TIME ZONE ")
+            .push_bind(timezone.to_string())
+            .push(
+                r#" as date, 
             status_type as status,
             COUNT(*) as count
         FROM latest_statuses
         WHERE 1=1
-        "#
-        );
+        "#,
+            );
         
         if let Some(from) = req.from {
             line_query.push(" AND created_at >= ").push_bind(from);
@@ -331,7 +810,7 @@ impl ApplicationRepository {
             line_query.push(" AND created_at <= ").push_bind(to);
         }
 
-        line_query.push(" GROUP BY (DATE(created_at) || ' 00:00:00')::TIMESTAMPTZ, status_type ORDER BY (DATE(created_at) || ' 00:00:00')::TIMESTAMPTZ, status_type");
+        line_query.push(" GROUP BY 1, 2 ORDER BY 1, 2");
 
         let bar_data: Vec<StatusCount> = bar_query
             .build_query_as()
@@ -341,7 +820,6 @@ impl ApplicationRepository {
 
         let line_data: Vec<DatesCount> = line_query
             .build_query_as()
-            .bind(user_id)
             .fetch_all(self.pool.as_ref())
             .await?;
 
@@ -351,3 +829,56 @@ impl ApplicationRepository {
         })
     }
 }
+
+/// The columns the application list is ordered by. Sortable fields are mapped onto fixed
+/// expressions here, so nothing from the request ever reaches the `ORDER BY` clause. `id`
+/// comes last as rows tying on every other column would otherwise come back in any order,
+/// which makes them skip or repeat across pages.
+fn ordering_columns(filter: &ApplicationFilter, search_query: Option<String>) -> Vec<KeysetColumn> {
+    let sort_keys = filter.sort_keys();
+    let mut columns: Vec<KeysetColumn> = if !sort_keys.is_empty() {
+        sort_keys
+            .iter()
+            .map(|key| sort_column(key.field, key.direction == SortDirection::Desc))
+            .collect()
+    } else if let Some(search_query) = search_query {
+        // Best matches first when searching, most recent first otherwise.
+        vec![
+            KeysetColumn::with_bind(
+                "ts_rank_cd(search_vector, to_tsquery('simple', ",
+                search_query,
+                "))",
+                KeysetKind::Real,
+                true,
+            ),
+            KeysetColumn::new("created_at", KeysetKind::Timestamp, true),
+        ]
+    } else {
+        vec![KeysetColumn::new("created_at", KeysetKind::Timestamp, true)]
+    };
+
+    columns.push(KeysetColumn::new("id", KeysetKind::BigInt, true));
+    columns
+}
+
+fn sort_column(field: ApplicationSortField, descending: bool) -> KeysetColumn {
+    match field {
+        ApplicationSortField::CreatedAt => KeysetColumn::new("created_at", KeysetKind::Timestamp, descending),
+        ApplicationSortField::Company => KeysetColumn::new("LOWER(company)", KeysetKind::Text, descending),
+        ApplicationSortField::Position => KeysetColumn::new("LOWER(position)", KeysetKind::Text, descending),
+        // Statuses sort in pipeline order rather than alphabetically.
+        ApplicationSortField::Status => KeysetColumn::new(
+            format!(
+                "CASE {} WHEN 'Applied' THEN 1 WHEN 'Test' THEN 2 WHEN 'Interview' THEN 3 \
+                 WHEN 'OfferAwarded' THEN 4 WHEN 'Rejected' THEN 5 WHEN 'Withdrawn' THEN 6 ELSE 0 END",
+                LATEST_STATUS
+            ),
+            KeysetKind::Int,
+            descending,
+        ),
+        ApplicationSortField::LastActivity => KeysetColumn::new(LAST_ACTIVITY, KeysetKind::Timestamp, descending),
+        // Longest in the current stage means the earliest stage change. Sorting on the change
+        // itself rather than on its age keeps values stable between requests, as cursors need.
+        ApplicationSortField::TimeInStage => KeysetColumn::new(STAGE_ENTERED_AT, KeysetKind::Timestamp, !descending),
+    }
+}
@@ -0,0 +1,162 @@
+use crate::models::audit_log::{AuditLog, GENESIS_HASH};
+use crate::payloads::admin::{AuditLogFilter, AuditLogResponse};
+use crate::payloads::pagination::{compute_pagination, count_with_filters, fetch_with_filters, Page};
+use sqlx::{PgConnection, PgPool, Postgres, QueryBuilder};
+use std::sync::Arc;
+
+/// Advisory lock key held while appending, so concurrent writers cannot fork the hash chain.
+const AUDIT_CHAIN_LOCK: i64 = 0x6175_6469_745f_6c6f;
+
+pub struct AuditLogRepository {
+    pool: Arc<PgPool>,
+    hmac_key: Vec<u8>,
+}
+
+impl AuditLogRepository {
+    pub fn new(pool: Arc<PgPool>, hmac_key: Vec<u8>) -> Arc<Self> {
+        Arc::new(Self { pool, hmac_key })
+    }
+
+    /// The hash `log` should have when it follows an entry hashed as `prev_hash`.
+    pub fn compute_hash(&self, log: &AuditLog, prev_hash: &str) -> String {
+        log.compute_hash(&self.hmac_key, prev_hash)
+    }
+
+    pub async fn save(&self, log: AuditLog) -> Result<AuditLog, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let saved = self.append(&mut tx, log).await?;
+        tx.commit().await?;
+        Ok(saved)
+    }
+
+    /// Appends the entry to the hash chain inside the caller's transaction, so an action and
+    /// its audit entry are committed together. The entry stores the hash of the latest entry
+    /// as its `prev_hash` and its own hash over both.
+    pub async fn append(&self, conn: &mut PgConnection, mut log: AuditLog) -> Result<AuditLog, sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(AUDIT_CHAIN_LOCK)
+            .execute(&mut *conn)
+            .await?;
+
+        let prev_hash = sqlx::query_scalar::<_, String>(
+            "SELECT hash FROM audit_logs WHERE hash IS NOT NULL ORDER BY id DESC LIMIT 1",
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .unwrap_or_else(|| GENESIS_HASH.to_string());
+
+        log.hash = Some(self.compute_hash(&log, &prev_hash));
+        log.prev_hash = Some(prev_hash);
+
+        sqlx::query_as::<_, AuditLog>(
+            r#"
+            INSERT INTO audit_logs (actor_id, action, target_type, target_id, details, ip_address, user_agent, prev_hash, hash, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING *
+            "#,
+        )
+        .bind(log.actor_id)
+        .bind(&log.action)
+        .bind(&log.target_type)
+        .bind(log.target_id)
+        .bind(&log.details)
+        .bind(&log.ip_address)
+        .bind(&log.user_agent)
+        .bind(&log.prev_hash)
+        .bind(&log.hash)
+        .bind(log.created_at)
+        .fetch_one(&mut *conn)
+        .await
+    }
+
+    /// Entries in insertion order, for walking the whole chain in batches.
+    pub async fn find_after(&self, after_id: i64, limit: i64) -> Result<Vec<AuditLog>, sqlx::Error> {
+        sqlx::query_as::<_, AuditLog>("SELECT * FROM audit_logs WHERE id > $1 ORDER BY id LIMIT $2")
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Every entry where the user is either the actor or the target, oldest first.
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<AuditLog>, sqlx::Error> {
+        sqlx::query_as::<_, AuditLog>(
+            "SELECT * FROM audit_logs WHERE actor_id = $1 OR (target_type = 'User' AND target_id = $1) ORDER BY id",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn find_with_filters(&self, filter: AuditLogFilter) -> Result<Page<AuditLogResponse>, sqlx::Error> {
+        let total = count_with_filters(
+            "SELECT COUNT(*) FROM audit_logs",
+            |b| apply_audit_log_filters(b, filter.clone()),
+            self.pool.as_ref(),
+        )
+        .await?;
+
+        let (page, size, offset, total_pages) = compute_pagination(filter.page, filter.size, total);
+
+        let logs: Vec<AuditLog> = fetch_with_filters(
+            "SELECT * FROM audit_logs",
+            |b| apply_audit_log_filters(b, filter.clone()),
+            size,
+            offset,
+            self.pool.as_ref(),
+        )
+        .await?;
+
+        let data: Vec<AuditLogResponse> = logs
+            .iter()
+            .map(|log| match filter.user_id {
+                Some(user_id) if filter.own_history => AuditLogResponse::for_user(log, user_id),
+                _ => AuditLogResponse::from_audit_log(log),
+            })
+            .collect();
+        Ok(Page::offset(data, page, total, total_pages))
+    }
+}
+
+fn apply_audit_log_filters(mut builder: QueryBuilder<'_, Postgres>, filter: AuditLogFilter) -> QueryBuilder<'_, Postgres> {
+    builder.push(" WHERE 1 = 1");
+
+    if let Some(user_id) = filter.user_id {
+        builder
+            .push(" AND (actor_id = ")
+            .push_bind(user_id)
+            .push(" OR (target_type = 'User' AND target_id = ")
+            .push_bind(user_id)
+            .push("))");
+    }
+
+    if let Some(actor_id) = filter.actor_id {
+        builder.push(" AND actor_id = ").push_bind(actor_id);
+    }
+
+    if let Some(action) = filter.action {
+        builder.push(" AND action = ").push_bind(action);
+    }
+
+    if let Some(target_type) = filter.target_type {
+        builder.push(" AND target_type = ").push_bind(target_type);
+    }
+
+    if let Some(target_id) = filter.target_id {
+        builder.push(" AND target_id = ").push_bind(target_id);
+    }
+
+    if let Some(ip_address) = filter.ip_address {
+        builder.push(" AND ip_address = ").push_bind(ip_address);
+    }
+
+    if let Some(from) = filter.from {
+        builder.push(" AND created_at >= ").push_bind(from);
+    }
+
+    if let Some(to) = filter.to {
+        builder.push(" AND created_at <= ").push_bind(to);
+    }
+
+    builder
+}
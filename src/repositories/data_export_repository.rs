@@ -0,0 +1,132 @@
+use crate::enums::data_export::DataExportStatus;
+use crate::models::data_export::DataExport;
+use chrono::{DateTime, Local};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+const DATA_EXPORT_COLUMNS: &str = "id, user_id, status, download_token_hash, created_at, completed_at, expires_at";
+
+pub struct DataExportRepository {
+    pool: Arc<PgPool>,
+}
+
+impl DataExportRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    pub async fn save(&self, export: DataExport) -> Result<DataExport, sqlx::Error> {
+        sqlx::query_as::<_, DataExport>(&format!(
+            r#"
+            INSERT INTO data_exports (user_id, status, download_token_hash, created_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {DATA_EXPORT_COLUMNS}
+            "#
+        ))
+        .bind(export.user_id)
+        .bind(&export.status)
+        .bind(&export.download_token_hash)
+        .bind(export.created_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    pub async fn exists_pending_for_user(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM data_exports WHERE user_id = $1 AND status = $2)",
+        )
+        .bind(user_id)
+        .bind(DataExportStatus::Pending)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<DataExport>, sqlx::Error> {
+        sqlx::query_as::<_, DataExport>(&format!(
+            "SELECT {DATA_EXPORT_COLUMNS} FROM data_exports WHERE user_id = $1 ORDER BY created_at DESC"
+        ))
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn mark_ready(
+        &self,
+        id: i64,
+        archive: &[u8],
+        expires_at: DateTime<Local>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = $1, archive = $2, completed_at = $3, expires_at = $4
+            WHERE id = $5
+            "#,
+        )
+        .bind(DataExportStatus::Ready)
+        .bind(archive)
+        .bind(Local::now())
+        .bind(expires_at)
+        .bind(id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE data_exports SET status = $1, completed_at = $2 WHERE id = $3")
+            .bind(DataExportStatus::Failed)
+            .bind(Local::now())
+            .bind(id)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the export id and archive for a download link that is ready and has not expired.
+    pub async fn find_downloadable_archive(&self, download_token_hash: &str) -> Result<Option<(i64, Vec<u8>)>, sqlx::Error> {
+        sqlx::query_as::<_, (i64, Vec<u8>)>(
+            r#"
+            SELECT id, archive FROM data_exports
+            WHERE download_token_hash = $1
+              AND status = $2
+              AND archive IS NOT NULL
+              AND expires_at > $3
+            "#,
+        )
+        .bind(download_token_hash)
+        .bind(DataExportStatus::Ready)
+        .bind(Local::now())
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    /// Fails exports still pending since before `started_before`. The archive is built by a task
+    /// of the process that took the request, so a crash or restart would otherwise leave the row
+    /// pending forever and block every later export of the user.
+    pub async fn fail_stale_pending(&self, started_before: DateTime<Local>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("UPDATE data_exports SET status = $1, completed_at = $2 WHERE status = $3 AND created_at < $4")
+            .bind(DataExportStatus::Failed)
+            .bind(Local::now())
+            .bind(DataExportStatus::Pending)
+            .bind(started_before)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Drops the archives of expired exports but keeps the rows as history.
+    pub async fn expire_archives(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("UPDATE data_exports SET status = $1, archive = NULL WHERE status = $2 AND expires_at <= $3")
+            .bind(DataExportStatus::Expired)
+            .bind(DataExportStatus::Ready)
+            .bind(Local::now())
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,69 @@
+use crate::models::email_change::EmailChangeRequest;
+use chrono::Local;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub struct EmailChangeRepository {
+    pool: Arc<PgPool>,
+}
+
+impl EmailChangeRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<EmailChangeRequest>, sqlx::Error> {
+        sqlx::query_as::<_, EmailChangeRequest>(
+            "SELECT * FROM email_change_requests WHERE user_id = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Stores a new request and invalidates any earlier pending request of the user,
+    /// so only the most recent confirmation link works.
+    pub async fn save(&self, request: EmailChangeRequest) -> Result<EmailChangeRequest, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE email_change_requests SET used = TRUE WHERE user_id = $1 AND used = FALSE")
+            .bind(request.user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let saved = sqlx::query_as::<_, EmailChangeRequest>(
+            r#"
+            INSERT INTO email_change_requests (user_id, new_email, token, expires_at, used, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(request.user_id)
+        .bind(&request.new_email)
+        .bind(&request.token)
+        .bind(request.expires_at)
+        .bind(request.used)
+        .bind(request.created_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(saved)
+    }
+
+    /// Atomically marks an unexpired request as used and returns it.
+    pub async fn consume(&self, token: &str) -> Result<Option<EmailChangeRequest>, sqlx::Error> {
+        sqlx::query_as::<_, EmailChangeRequest>(
+            r#"
+            UPDATE email_change_requests
+            SET used = TRUE
+            WHERE token = $1 AND used = FALSE AND expires_at > $2
+            RETURNING *
+            "#,
+        )
+        .bind(token)
+        .bind(Local::now())
+        .fetch_optional(&*self.pool)
+        .await
+    }
+}
@@ -0,0 +1,113 @@
+use crate::models::identity::{OidcLoginState, UserIdentity};
+use chrono::Local;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub struct IdentityRepository {
+    pool: Arc<PgPool>,
+}
+
+impl IdentityRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    pub async fn find_by_provider_subject(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<UserIdentity>, sqlx::Error> {
+        sqlx::query_as::<_, UserIdentity>(
+            "SELECT * FROM user_identities WHERE provider = $1 AND subject = $2",
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<UserIdentity>, sqlx::Error> {
+        sqlx::query_as::<_, UserIdentity>("SELECT * FROM user_identities WHERE user_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn save(&self, identity: UserIdentity) -> Result<UserIdentity, sqlx::Error> {
+        sqlx::query_as::<_, UserIdentity>(
+            r#"
+            INSERT INTO user_identities (user_id, provider, subject, email, created_at, last_login_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(identity.user_id)
+        .bind(&identity.provider)
+        .bind(&identity.subject)
+        .bind(&identity.email)
+        .bind(identity.created_at)
+        .bind(identity.last_login_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Returns the unlinked identity, or `None` if the user has no identity with this id.
+    pub async fn delete(&self, id: i64, user_id: i64) -> Result<Option<UserIdentity>, sqlx::Error> {
+        sqlx::query_as::<_, UserIdentity>("DELETE FROM user_identities WHERE id = $1 AND user_id = $2 RETURNING *")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn touch_last_login(&self, id: i64, email: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE user_identities SET last_login_at = $1, email = $2 WHERE id = $3")
+            .bind(Local::now())
+            .bind(email)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn save_login_state(&self, state: OidcLoginState) -> Result<OidcLoginState, sqlx::Error> {
+        sqlx::query_as::<_, OidcLoginState>(
+            r#"
+            INSERT INTO oidc_login_states (provider, state, nonce, code_verifier, remember_me, expires_at, used, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(&state.provider)
+        .bind(&state.state)
+        .bind(&state.nonce)
+        .bind(&state.code_verifier)
+        .bind(state.remember_me)
+        .bind(state.expires_at)
+        .bind(state.used)
+        .bind(state.created_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Atomically marks a login state as used and returns it, so an authorization
+    /// response can only be redeemed once.
+    pub async fn consume_login_state(
+        &self,
+        provider: &str,
+        state: &str,
+    ) -> Result<Option<OidcLoginState>, sqlx::Error> {
+        sqlx::query_as::<_, OidcLoginState>(
+            r#"
+            UPDATE oidc_login_states
+            SET used = TRUE
+            WHERE provider = $1 AND state = $2 AND used = FALSE
+            RETURNING *
+            "#,
+        )
+        .bind(provider)
+        .bind(state)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+}
@@ -0,0 +1,179 @@
+use crate::models::audit_log::AuditLog;
+use crate::models::invite::Invite;
+use crate::payloads::invite::{InviteFilter, InviteResponse};
+use crate::payloads::pagination::{compute_pagination, count_with_filters, fetch_with_filters, Page};
+use crate::repositories::audit_log_repository::AuditLogRepository;
+use chrono::Local;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::sync::Arc;
+
+pub struct InviteRepository {
+    pool: Arc<PgPool>,
+    audit_log_repo: Arc<AuditLogRepository>,
+}
+
+impl InviteRepository {
+    pub fn new(pool: Arc<PgPool>, audit_log_repo: Arc<AuditLogRepository>) -> Arc<Self> {
+        Arc::new(Self { pool, audit_log_repo })
+    }
+
+    /// Saves the invite and revokes any earlier invite still pending for the same email.
+    /// The audit entry built from the saved invite is written in the same transaction.
+    pub async fn save(&self, invite: Invite, audit: impl FnOnce(&Invite) -> AuditLog) -> Result<Invite, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE invites SET revoked_at = $1
+            WHERE LOWER(email) = LOWER($2) AND accepted_at IS NULL AND revoked_at IS NULL
+            "#,
+        )
+        .bind(invite.created_at)
+        .bind(&invite.email)
+        .execute(&mut *tx)
+        .await?;
+
+        let saved = sqlx::query_as::<_, Invite>(
+            r#"
+            INSERT INTO invites (email, token_hash, role, invited_by, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(&invite.email)
+        .bind(&invite.token_hash)
+        .bind(&invite.role)
+        .bind(invite.invited_by)
+        .bind(invite.expires_at)
+        .bind(invite.created_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        self.audit_log_repo.append(&mut tx, audit(&saved)).await?;
+
+        tx.commit().await?;
+        Ok(saved)
+    }
+
+    pub async fn find_pending_by_hash(&self, token_hash: &str) -> Result<Option<Invite>, sqlx::Error> {
+        sqlx::query_as::<_, Invite>(
+            r#"
+            SELECT * FROM invites
+            WHERE token_hash = $1
+              AND accepted_at IS NULL
+              AND revoked_at IS NULL
+              AND expires_at > $2
+            "#,
+        )
+        .bind(token_hash)
+        .bind(Local::now())
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    pub async fn find_pending_by_email(&self, email: &str) -> Result<Option<Invite>, sqlx::Error> {
+        sqlx::query_as::<_, Invite>(
+            r#"
+            SELECT * FROM invites
+            WHERE LOWER(email) = LOWER($1)
+              AND accepted_at IS NULL
+              AND revoked_at IS NULL
+              AND expires_at > $2
+            "#,
+        )
+        .bind(email)
+        .bind(Local::now())
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    /// Returns false if the invite was accepted or revoked in the meantime.
+    pub async fn mark_accepted(&self, id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE invites SET accepted_at = $1, accepted_by = $2
+            WHERE id = $3 AND accepted_at IS NULL AND revoked_at IS NULL
+            "#,
+        )
+        .bind(Local::now())
+        .bind(user_id)
+        .bind(id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Revokes a pending invite. The audit entry is written in the same transaction, and only
+    /// when an invite was actually revoked.
+    pub async fn revoke(&self, id: i64, audit: impl FnOnce(&Invite) -> AuditLog) -> Result<Option<Invite>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let revoked = sqlx::query_as::<_, Invite>(
+            r#"
+            UPDATE invites SET revoked_at = $1
+            WHERE id = $2 AND accepted_at IS NULL AND revoked_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(Local::now())
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(invite) = &revoked {
+            self.audit_log_repo.append(&mut tx, audit(invite)).await?;
+        }
+
+        tx.commit().await?;
+        Ok(revoked)
+    }
+
+    pub async fn find_with_filters(&self, filter: InviteFilter) -> Result<Page<InviteResponse>, sqlx::Error> {
+        let total = count_with_filters(
+            "SELECT COUNT(*) FROM invites",
+            |b| apply_invite_filters(b, filter.clone()),
+            self.pool.as_ref(),
+        )
+        .await?;
+
+        let (page, size, offset, total_pages) = compute_pagination(filter.page, filter.size, total);
+
+        let invites: Vec<Invite> = fetch_with_filters(
+            "SELECT * FROM invites",
+            |b| apply_invite_filters(b, filter),
+            size,
+            offset,
+            self.pool.as_ref(),
+        )
+        .await?;
+
+        let data: Vec<InviteResponse> = invites.iter().map(InviteResponse::from_invite).collect();
+        Ok(Page::offset(data, page, total, total_pages))
+    }
+}
+
+fn apply_invite_filters(mut builder: QueryBuilder<'_, Postgres>, filter: InviteFilter) -> QueryBuilder<'_, Postgres> {
+    builder.push(" WHERE 1 = 1");
+
+    if let Some(email) = filter.email {
+        builder.push(" AND email ILIKE ").push_bind(format!("%{}%", email));
+    }
+
+    match filter.pending {
+        Some(true) => {
+            builder
+                .push(" AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > ")
+                .push_bind(Local::now());
+        }
+        Some(false) => {
+            builder
+                .push(" AND (accepted_at IS NOT NULL OR revoked_at IS NOT NULL OR expires_at <= ")
+                .push_bind(Local::now())
+                .push(")");
+        }
+        None => (),
+    }
+
+    builder
+}
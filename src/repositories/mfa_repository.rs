@@ -0,0 +1,191 @@
+use crate::models::mfa::{RecoveryCode, UserMfa};
+use chrono::{DateTime, Local};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub struct MfaRepository {
+    pool: Arc<PgPool>,
+}
+
+impl MfaRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    pub async fn find_by_user_id(&self, user_id: i64) -> Result<Option<UserMfa>, sqlx::Error> {
+        sqlx::query_as::<_, UserMfa>("SELECT * FROM user_mfa WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn save_pending_secret(&self, user_id: i64, secret: &str) -> Result<UserMfa, sqlx::Error> {
+        sqlx::query_as::<_, UserMfa>(
+            r#"
+            INSERT INTO user_mfa (user_id, secret, enabled, created_at, updated_at)
+            VALUES ($1, $2, FALSE, $3, $3)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, enabled = FALSE, last_used_step = NULL,
+                failed_attempts = 0, locked_until = NULL, confirmed_at = NULL, updated_at = EXCLUDED.updated_at
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(secret)
+        .bind(Local::now())
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Enables MFA and replaces any existing recovery codes in a single transaction.
+    pub async fn enable(
+        &self,
+        user_id: i64,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let now = Local::now();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE user_mfa
+            SET enabled = TRUE, last_used_step = $1, confirmed_at = $2, updated_at = $2
+            WHERE user_id = $3
+            "#,
+        )
+        .bind(step)
+        .bind(now)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO mfa_recovery_codes (user_id, code_hash, created_at)
+            SELECT $1, code_hash, $2 FROM UNNEST($3::VARCHAR[]) AS code_hash
+            "#,
+        )
+        .bind(user_id)
+        .bind(now)
+        .bind(recovery_code_hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Records the time step of an accepted code. Returns false if an equal or later step
+    /// was already used, which means the code is being replayed.
+    pub async fn update_last_used_step(&self, user_id: i64, step: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_mfa
+            SET last_used_step = $1, updated_at = $2
+            WHERE user_id = $3 AND (last_used_step IS NULL OR last_used_step < $1)
+            "#,
+        )
+        .bind(step)
+        .bind(Local::now())
+        .bind(user_id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Counts a rejected code. The attempt that reaches `max_attempts` locks verification
+    /// until `locked_until` and resets the count.
+    pub async fn record_failed_attempt(
+        &self,
+        user_id: i64,
+        max_attempts: i32,
+        locked_until: DateTime<Local>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE user_mfa
+            SET failed_attempts = CASE WHEN failed_attempts + 1 >= $1 THEN 0 ELSE failed_attempts + 1 END,
+                locked_until = CASE WHEN failed_attempts + 1 >= $1 THEN $2 ELSE locked_until END,
+                updated_at = $3
+            WHERE user_id = $4
+            "#,
+        )
+        .bind(max_attempts)
+        .bind(locked_until)
+        .bind(Local::now())
+        .bind(user_id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn reset_failed_attempts(&self, user_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE user_mfa
+            SET failed_attempts = 0, locked_until = NULL, updated_at = $1
+            WHERE user_id = $2 AND (failed_attempts > 0 OR locked_until IS NOT NULL)
+            "#,
+        )
+        .bind(Local::now())
+        .bind(user_id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_recovery_codes_by_user_id(&self, user_id: i64) -> Result<Vec<RecoveryCode>, sqlx::Error> {
+        sqlx::query_as::<_, RecoveryCode>(
+            "SELECT * FROM mfa_recovery_codes WHERE user_id = $1 ORDER BY id",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn find_unused_recovery_codes(&self, user_id: i64) -> Result<Vec<RecoveryCode>, sqlx::Error> {
+        sqlx::query_as::<_, RecoveryCode>(
+            "SELECT * FROM mfa_recovery_codes WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Marks a recovery code as used. Returns false if it was consumed concurrently.
+    pub async fn mark_recovery_code_used(&self, code_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE mfa_recovery_codes SET used_at = $1 WHERE id = $2 AND used_at IS NULL",
+        )
+        .bind(Local::now())
+        .bind(code_id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn delete_for_user(&self, user_id: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM user_mfa WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await
+    }
+}
@@ -1,3 +1,14 @@
 pub(crate) mod user_repository;
 pub(crate) mod application_repository;
-pub(crate) mod token_repository;
\ No newline at end of file
+pub(crate) mod token_repository;
+pub(crate) mod mfa_repository;
+pub(crate) mod passkey_repository;
+pub(crate) mod identity_repository;
+pub(crate) mod personal_access_token_repository;
+pub(crate) mod email_change_repository;
+pub(crate) mod audit_log_repository;
+pub(crate) mod preferences_repository;
+pub(crate) mod data_export_repository;
+pub(crate) mod invite_repository;
+pub(crate) mod session_repository;
+pub(crate) mod saved_view_repository;
\ No newline at end of file
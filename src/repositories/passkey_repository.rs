@@ -0,0 +1,154 @@
+use crate::models::passkey::{PasskeyCredential, WebauthnChallenge};
+use chrono::Local;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub struct PasskeyRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PasskeyRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    pub async fn save(&self, credential: PasskeyCredential) -> Result<PasskeyCredential, sqlx::Error> {
+        sqlx::query_as::<_, PasskeyCredential>(
+            r#"
+            INSERT INTO webauthn_credentials (user_id, credential_id, public_key, sign_count, name, aaguid, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(credential.user_id)
+        .bind(&credential.credential_id)
+        .bind(&credential.public_key)
+        .bind(credential.sign_count)
+        .bind(&credential.name)
+        .bind(&credential.aaguid)
+        .bind(credential.created_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    pub async fn find_by_credential_id(
+        &self,
+        credential_id: &str,
+    ) -> Result<Option<PasskeyCredential>, sqlx::Error> {
+        sqlx::query_as::<_, PasskeyCredential>(
+            "SELECT * FROM webauthn_credentials WHERE credential_id = $1",
+        )
+        .bind(credential_id)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<PasskeyCredential>, sqlx::Error> {
+        sqlx::query_as::<_, PasskeyCredential>(
+            "SELECT * FROM webauthn_credentials WHERE user_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Stores the new signature counter. Returns false if another login already advanced it,
+    /// which indicates a replayed or cloned authenticator response.
+    pub async fn update_sign_count(
+        &self,
+        id: i64,
+        previous_count: i64,
+        sign_count: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webauthn_credentials
+            SET sign_count = $1, last_used_at = $2
+            WHERE id = $3 AND sign_count = $4
+            "#,
+        )
+        .bind(sign_count)
+        .bind(Local::now())
+        .bind(id)
+        .bind(previous_count)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn rename(
+        &self,
+        id: i64,
+        user_id: i64,
+        name: &str,
+    ) -> Result<Option<PasskeyCredential>, sqlx::Error> {
+        sqlx::query_as::<_, PasskeyCredential>(
+            r#"
+            UPDATE webauthn_credentials
+            SET name = $1
+            WHERE id = $2 AND user_id = $3
+            RETURNING *
+            "#,
+        )
+        .bind(name)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    pub async fn delete(&self, id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM webauthn_credentials WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn save_challenge(&self, challenge: WebauthnChallenge) -> Result<WebauthnChallenge, sqlx::Error> {
+        sqlx::query_as::<_, WebauthnChallenge>(
+            r#"
+            INSERT INTO webauthn_challenges (user_id, challenge, ceremony, expires_at, used, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(challenge.user_id)
+        .bind(&challenge.challenge)
+        .bind(&challenge.ceremony)
+        .bind(challenge.expires_at)
+        .bind(challenge.used)
+        .bind(challenge.created_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Atomically marks a challenge as used and returns it, so each challenge
+    /// can complete at most one ceremony.
+    pub async fn consume_challenge(&self, challenge: &str) -> Result<Option<WebauthnChallenge>, sqlx::Error> {
+        sqlx::query_as::<_, WebauthnChallenge>(
+            r#"
+            UPDATE webauthn_challenges
+            SET used = TRUE
+            WHERE challenge = $1 AND used = FALSE
+            RETURNING *
+            "#,
+        )
+        .bind(challenge)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    /// Deletes challenges that were used or have expired, since neither can complete a ceremony.
+    pub async fn delete_stale_challenges(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM webauthn_challenges WHERE used = TRUE OR expires_at <= $1")
+            .bind(Local::now())
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,94 @@
+use crate::models::personal_access_token::PersonalAccessToken;
+use chrono::Local;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub struct PersonalAccessTokenRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PersonalAccessTokenRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    pub async fn save(&self, token: PersonalAccessToken) -> Result<PersonalAccessToken, sqlx::Error> {
+        sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            INSERT INTO personal_access_tokens (user_id, name, token_prefix, token_hash, scopes, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(token.user_id)
+        .bind(&token.name)
+        .bind(&token.token_prefix)
+        .bind(&token.token_hash)
+        .bind(&token.scopes)
+        .bind(token.expires_at)
+        .bind(token.created_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Finds a token that has not been revoked and has not expired.
+    pub async fn find_active_by_hash(&self, token_hash: &str) -> Result<Option<PersonalAccessToken>, sqlx::Error> {
+        sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            SELECT * FROM personal_access_tokens
+            WHERE token_hash = $1
+              AND revoked_at IS NULL
+              AND (expires_at IS NULL OR expires_at > $2)
+            "#,
+        )
+        .bind(token_hash)
+        .bind(Local::now())
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<PersonalAccessToken>, sqlx::Error> {
+        sqlx::query_as::<_, PersonalAccessToken>(
+            "SELECT * FROM personal_access_tokens WHERE user_id = $1 AND revoked_at IS NULL ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Unlike `find_all_by_user_id`, this also returns revoked tokens.
+    pub async fn find_history_by_user_id(&self, user_id: i64) -> Result<Vec<PersonalAccessToken>, sqlx::Error> {
+        sqlx::query_as::<_, PersonalAccessToken>(
+            "SELECT * FROM personal_access_tokens WHERE user_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn touch_last_used(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE personal_access_tokens SET last_used_at = $1 WHERE id = $2")
+            .bind(Local::now())
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn revoke(&self, id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE personal_access_tokens
+            SET revoked_at = $1
+            WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(Local::now())
+        .bind(id)
+        .bind(user_id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::models::preferences::UserPreferences;
+use sqlx::types::Json;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub struct PreferencesRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PreferencesRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    /// Returns the stored preferences, or the defaults if the user never saved any.
+    pub async fn find_by_user_id(&self, user_id: i64) -> Result<UserPreferences, sqlx::Error> {
+        let preferences = sqlx::query_scalar::<_, Json<UserPreferences>>(
+            "SELECT preferences FROM user_preferences WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        Ok(preferences.map(|Json(preferences)| preferences).unwrap_or_default())
+    }
+
+    pub async fn save(&self, user_id: i64, preferences: &UserPreferences) -> Result<UserPreferences, sqlx::Error> {
+        sqlx::query_scalar::<_, Json<UserPreferences>>(
+            r#"
+            INSERT INTO user_preferences (user_id, preferences, updated_at)
+            VALUES ($1, $2, NOW() AT TIME ZONE 'utc')
+            ON CONFLICT (user_id) DO UPDATE
+            SET preferences = EXCLUDED.preferences, updated_at = EXCLUDED.updated_at
+            RETURNING preferences
+            "#,
+        )
+        .bind(user_id)
+        .bind(Json(preferences))
+        .fetch_one(&*self.pool)
+        .await
+        .map(|Json(preferences)| preferences)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::models::saved_view::SavedView;
+use chrono::Local;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub struct SavedViewRepository {
+    pool: Arc<PgPool>,
+}
+
+impl SavedViewRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    pub async fn save(&self, view: SavedView) -> Result<SavedView, sqlx::Error> {
+        sqlx::query_as::<_, SavedView>(
+            r#"
+            INSERT INTO saved_views (user_id, name, filter, columns, show_count, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(view.user_id)
+        .bind(&view.name)
+        .bind(&view.filter)
+        .bind(&view.columns)
+        .bind(view.show_count)
+        .bind(view.created_at)
+        .bind(view.updated_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<SavedView>, sqlx::Error> {
+        sqlx::query_as::<_, SavedView>("SELECT * FROM saved_views WHERE user_id = $1 ORDER BY LOWER(name)")
+            .bind(user_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn find_by_id(&self, id: i64, user_id: i64) -> Result<Option<SavedView>, sqlx::Error> {
+        sqlx::query_as::<_, SavedView>("SELECT * FROM saved_views WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    pub async fn count_by_user_id(&self, user_id: i64) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM saved_views WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn update(&self, view: &SavedView) -> Result<SavedView, sqlx::Error> {
+        sqlx::query_as::<_, SavedView>(
+            r#"
+            UPDATE saved_views
+            SET name = $1, filter = $2, columns = $3, show_count = $4, updated_at = $5
+            WHERE id = $6 AND user_id = $7
+            RETURNING *
+            "#,
+        )
+        .bind(&view.name)
+        .bind(&view.filter)
+        .bind(&view.columns)
+        .bind(view.show_count)
+        .bind(Local::now())
+        .bind(view.id)
+        .bind(view.user_id)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    pub async fn delete(&self, id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM saved_views WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
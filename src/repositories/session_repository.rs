@@ -0,0 +1,121 @@
+use crate::models::session::UserSession;
+use chrono::{DateTime, Local};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+pub struct SessionRepository {
+    pool: Arc<PgPool>,
+}
+
+impl SessionRepository {
+    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
+        Arc::new(Self { pool })
+    }
+
+    pub async fn save(&self, session: UserSession) -> Result<UserSession, sqlx::Error> {
+        sqlx::query_as::<_, UserSession>(
+            r#"
+            INSERT INTO user_sessions (user_id, user_agent, ip_address, device_hash, created_at, last_seen_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(session.user_id)
+        .bind(&session.user_agent)
+        .bind(&session.ip_address)
+        .bind(&session.device_hash)
+        .bind(session.created_at)
+        .bind(session.last_seen_at)
+        .bind(session.expires_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Whether the user has logged in from this device before, or `None` if they have never
+    /// logged in at all.
+    pub async fn is_known_device(&self, user_id: i64, device_hash: &str) -> Result<Option<bool>, sqlx::Error> {
+        sqlx::query_scalar::<_, Option<bool>>(
+            "SELECT BOOL_OR(device_hash = $2) FROM user_sessions WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .bind(device_hash)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    pub async fn find_active_by_id(&self, id: i64, user_id: i64) -> Result<Option<UserSession>, sqlx::Error> {
+        sqlx::query_as::<_, UserSession>(
+            r#"
+            SELECT * FROM user_sessions
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL AND expires_at > $3
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(Local::now())
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    /// Every session of the user, including revoked and expired ones.
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<UserSession>, sqlx::Error> {
+        sqlx::query_as::<_, UserSession>("SELECT * FROM user_sessions WHERE user_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    pub async fn find_active_by_user_id(&self, user_id: i64) -> Result<Vec<UserSession>, sqlx::Error> {
+        sqlx::query_as::<_, UserSession>(
+            r#"
+            SELECT * FROM user_sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > $2
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(Local::now())
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn touch(&self, id: i64, seen_at: DateTime<Local>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE user_sessions SET last_seen_at = $1 WHERE id = $2")
+            .bind(seen_at)
+            .bind(id)
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+    }
+
+    /// Returns false if the session does not exist, belongs to someone else or is already revoked.
+    pub async fn revoke(&self, id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE user_sessions SET revoked_at = $1 WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL",
+        )
+        .bind(Local::now())
+        .bind(id)
+        .bind(user_id)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Revokes every session of the user except `keep`, if given. Returns how many were revoked.
+    pub async fn revoke_all_except(&self, user_id: i64, keep: Option<i64>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions SET revoked_at = $1
+            WHERE user_id = $2 AND revoked_at IS NULL AND ($3::BIGINT IS NULL OR id <> $3)
+            "#,
+        )
+        .bind(Local::now())
+        .bind(user_id)
+        .bind(keep)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
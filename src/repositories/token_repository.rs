@@ -1,6 +1,6 @@
 use crate::models::token::Token;
 use chrono::Local;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use std::sync::Arc;
 
 pub struct TokenRepository {
@@ -13,6 +13,11 @@ impl TokenRepository {
     }
 
     pub async fn save(&self, token: Token) -> Result<Token, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::insert(&mut conn, token).await
+    }
+
+    pub async fn insert(conn: &mut PgConnection, token: Token) -> Result<Token, sqlx::Error> {
         sqlx::query_as::<_, Token>(
             r#"
         INSERT INTO tokens (user_id, token, expires_at, created_at, used)
@@ -25,7 +30,7 @@ impl TokenRepository {
         .bind(&token.expires_at)
         .bind(&token.created_at)
         .bind(&token.used)
-        .fetch_one(&*self.pool)
+        .fetch_one(&mut *conn)
         .await
     }
 
@@ -56,10 +61,29 @@ impl TokenRepository {
         .map(|_| ())
     }
 
+    pub async fn find_all_by_user_id(&self, user_id: i64) -> Result<Vec<Token>, sqlx::Error> {
+        sqlx::query_as::<_, Token>(
+            r#"
+        SELECT id, user_id, token, expires_at, created_at, used
+        FROM tokens
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        "#,
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
     pub async fn invalidate_existing_tokens_for_user(
         &self,
         user_id: i64,
     ) -> Result<(), sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::invalidate_for_user(&mut conn, user_id).await
+    }
+
+    pub async fn invalidate_for_user(conn: &mut PgConnection, user_id: i64) -> Result<(), sqlx::Error> {
         let now = Local::now();
 
         sqlx::query(
@@ -71,7 +95,7 @@ impl TokenRepository {
         )
         .bind(now)
         .bind(user_id)
-        .execute(&*self.pool)
+        .execute(&mut *conn)
         .await
         .map(|_| ())
     }
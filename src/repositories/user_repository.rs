@@ -1,14 +1,23 @@
+use crate::enums::roles::Role;
+use crate::models::audit_log::AuditLog;
+use crate::models::token::Token;
 use crate::models::user::User;
-use sqlx::PgPool;
+use crate::payloads::admin::{AdminUserFilter, AdminUserResponse};
+use crate::payloads::pagination::{compute_pagination, count_with_filters, fetch_with_filters, Page};
+use chrono::{DateTime, Local};
+use crate::repositories::audit_log_repository::AuditLogRepository;
+use crate::repositories::token_repository::TokenRepository;
+use sqlx::{PgPool, Postgres, QueryBuilder};
 use std::sync::Arc;
 
 pub struct UserRepository {
     pub pool: Arc<PgPool>,
+    audit_log_repo: Arc<AuditLogRepository>,
 }
 
 impl UserRepository {
-    pub fn new(pool: Arc<PgPool>) -> Arc<Self> {
-        Arc::new(Self { pool })
+    pub fn new(pool: Arc<PgPool>, audit_log_repo: Arc<AuditLogRepository>) -> Arc<Self> {
+        Arc::new(Self { pool, audit_log_repo })
     }
 
     pub async fn get_user_by_id(&self, user_id: i64) -> Result<User, sqlx::Error> {
@@ -18,6 +27,15 @@ impl UserRepository {
             .await
     }
 
+    /// Like `get_user_by_id`, but fails for deleted or deactivated accounts.
+    /// Use it wherever a lookup leads to issuing a session.
+    pub async fn get_active_user_by_id(&self, user_id: i64) -> Result<User, sqlx::Error> {
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND deleted = FALSE")
+            .bind(user_id)
+            .fetch_one(self.pool.as_ref())
+            .await
+    }
+
     pub async fn save(&self, user: User) -> Result<User, sqlx::Error> {
         sqlx::query_as::<_, User>(
             r#"
@@ -47,7 +65,7 @@ impl UserRepository {
 
     pub async fn exists_by_email(&self, email: String) -> Result<bool, sqlx::Error> {
         let exists = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)"
+            "SELECT EXISTS(SELECT 1 FROM users WHERE email = $1 AND deleted = FALSE)"
         )
             .bind(email)
             .fetch_one(self.pool.as_ref())
@@ -59,25 +77,309 @@ impl UserRepository {
 
 
     pub async fn get_user_by_email(&self, email: String) -> Result<User, sqlx::Error> {
-        sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1 AND deleted = FALSE")
             .bind(email)
             .fetch_one(self.pool.as_ref())
             .await
     }
 
-    pub async fn update_password(&self, user_id: i64, password_hash: String) -> Result<(), sqlx::Error> {
-        sqlx::query(
+    /// Replaces a hash with an upgraded one of the same password. Does nothing if the password
+    /// was changed since `current_hash` was read.
+    pub async fn rehash_password(&self, user_id: i64, current_hash: &str, new_hash: String) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE users SET password = $1 WHERE id = $2 AND password = $3")
+            .bind(new_hash)
+            .bind(user_id)
+            .bind(current_hash)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Stores a new password hash and bumps `token_version`, revoking every JWT issued so far.
+    /// Returns the new version so the caller can issue a replacement token.
+    pub async fn update_password_and_revoke_sessions(&self, user_id: i64, password_hash: String) -> Result<i32, sqlx::Error> {
+        sqlx::query_scalar::<_, i32>(
             r#"
             UPDATE users
-            SET password = $1, updated_at = NOW() AT TIME ZONE 'utc'
+            SET password = $1, token_version = token_version + 1, updated_at = NOW() AT TIME ZONE 'utc'
             WHERE id = $2
+            RETURNING token_version
             "#,
         )
             .bind(password_hash)
             .bind(user_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn update_email(&self, user_id: i64, email: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET email = $1, updated_at = NOW() AT TIME ZONE 'utc'
+            WHERE id = $2
+            "#,
+        )
+            .bind(email)
+            .bind(user_id)
             .execute(&*self.pool)
             .await
             .map(|_| ())
     }
 
+    pub async fn find_with_filters(&self, filter: AdminUserFilter) -> Result<Page<AdminUserResponse>, sqlx::Error> {
+        let total = count_with_filters(
+            "SELECT COUNT(*) FROM users",
+            |b| apply_user_filters(b, filter.clone()),
+            self.pool.as_ref(),
+        )
+            .await?;
+
+        let (page, size, offset, total_pages) = compute_pagination(filter.page, filter.size, total);
+
+        let users: Vec<User> = fetch_with_filters(
+            "SELECT * FROM users",
+            |b| apply_user_filters(b, filter),
+            size,
+            offset,
+            self.pool.as_ref(),
+        )
+            .await?;
+
+        let data: Vec<AdminUserResponse> = users.iter().map(AdminUserResponse::from_user).collect();
+        Ok(Page::offset(data, page, total, total_pages))
+    }
+
+    /// Changes the role and bumps `token_version`, since the role is embedded in issued JWTs.
+    /// The audit entry is written in the same transaction.
+    pub async fn update_role(&self, user_id: i64, role: &Role, audit: AuditLog) -> Result<User, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET role = $1, token_version = token_version + 1
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+            .bind(role)
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        self.audit_log_repo.append(&mut tx, audit).await?;
+
+        tx.commit().await?;
+        Ok(user)
+    }
+
+    /// Soft-deletes the user through `soft_delete_user` and revokes their sessions. The audit
+    /// entry is written in the same transaction.
+    pub async fn deactivate(&self, user_id: i64, audit: AuditLog) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT soft_delete_user($1)")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE users SET token_version = token_version + 1 WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        self.audit_log_repo.append(&mut tx, audit).await?;
+
+        tx.commit().await
+    }
+
+    /// Restores a soft-deleted user. The audit entry is written in the same transaction.
+    pub async fn reactivate(&self, user_id: i64, audit: AuditLog) -> Result<User, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET deleted = FALSE, deleted_at = NULL, purge_after = NULL
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        self.audit_log_repo.append(&mut tx, audit).await?;
+
+        tx.commit().await?;
+        Ok(user)
+    }
+
+    /// Replaces the password, revokes sessions and pending reset tokens, and saves a new reset
+    /// token, all in one transaction together with the audit entry.
+    pub async fn force_password_reset(
+        &self,
+        user_id: i64,
+        password_hash: String,
+        reset_token: Token,
+        audit: AuditLog,
+    ) -> Result<Token, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET password = $1, token_version = token_version + 1, updated_at = NOW() AT TIME ZONE 'utc'
+            WHERE id = $2
+            "#,
+        )
+            .bind(password_hash)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        TokenRepository::invalidate_for_user(&mut tx, user_id).await?;
+        let reset_token = TokenRepository::insert(&mut tx, reset_token).await?;
+        self.audit_log_repo.append(&mut tx, audit).await?;
+
+        tx.commit().await?;
+        Ok(reset_token)
+    }
+
+    /// Soft-deletes the user through `soft_delete_user`, revokes their sessions and
+    /// schedules the account for purging once `purge_after` has passed.
+    pub async fn schedule_deletion(&self, user_id: i64, purge_after: DateTime<Local>) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT soft_delete_user($1)")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE users SET purge_after = $1, token_version = token_version + 1 WHERE id = $2")
+            .bind(purge_after)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await
+    }
+
+    pub async fn find_pending_deletion_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as::<_, User>(
+            r#"
+            SELECT * FROM users
+            WHERE email = $1 AND deleted = TRUE AND purge_after > $2
+            ORDER BY deleted_at DESC
+            LIMIT 1
+            "#,
+        )
+            .bind(email)
+            .bind(Local::now())
+            .fetch_optional(&*self.pool)
+            .await
+    }
+
+    /// Permanently removes accounts whose grace period has ended, together with their
+    /// applications, statuses and tokens. Returns the number of purged accounts.
+    pub async fn purge_expired_deletions(&self) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let user_ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM users WHERE deleted = TRUE AND purge_after <= $1 FOR UPDATE SKIP LOCKED",
+        )
+            .bind(Local::now())
+            .fetch_all(&mut *tx)
+            .await?;
+
+        if user_ids.is_empty() {
+            return Ok(0);
+        }
+
+        sqlx::query(
+            r#"
+            DELETE FROM application_statuses
+            WHERE created_by = ANY($1)
+               OR application_id IN (SELECT id FROM applications WHERE created_by = ANY($1))
+            "#,
+        )
+            .bind(&user_ids)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM applications WHERE created_by = ANY($1)")
+            .bind(&user_ids)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM tokens WHERE user_id = ANY($1)")
+            .bind(&user_ids)
+            .execute(&mut *tx)
+            .await?;
+
+        // Remaining per-user data (MFA, passkeys, identities, access tokens) cascades.
+        let purged = sqlx::query("DELETE FROM users WHERE id = ANY($1)")
+            .bind(&user_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok(purged)
+    }
+
+    /// Updates the given profile fields, leaving `None` fields untouched.
+    pub async fn update_profile(
+        &self,
+        user_id: i64,
+        first_name: Option<String>,
+        last_name: Option<String>,
+        phone_number: Option<String>,
+    ) -> Result<User, sqlx::Error> {
+        sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET first_name = COALESCE($1, first_name),
+                last_name = COALESCE($2, last_name),
+                phone_number = COALESCE($3, phone_number)
+            WHERE id = $4
+            RETURNING *
+            "#,
+        )
+            .bind(first_name)
+            .bind(last_name)
+            .bind(phone_number)
+            .bind(user_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+}
+
+fn apply_user_filters(mut builder: QueryBuilder<'_, Postgres>, filter: AdminUserFilter) -> QueryBuilder<'_, Postgres> {
+    builder.push(" WHERE 1 = 1");
+
+    if let Some(search) = filter.search {
+        let pattern = format!("%{}%", search);
+        builder
+            .push(" AND (email ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR first_name ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR last_name ILIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+
+    if let Some(role) = filter.role {
+        builder.push(" AND role = ").push_bind(role);
+    }
+
+    if let Some(deactivated) = filter.deactivated {
+        builder.push(" AND deleted = ").push_bind(deactivated);
+    }
+
+    builder
 }
@@ -0,0 +1,288 @@
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::errors::app_error::{extract_validation_errors, AppError};
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::models::audit_log::AuditLog;
+use crate::models::invite::Invite;
+use crate::models::token::Token;
+use crate::models::user::User;
+use crate::payloads::admin::{
+    AdminUserFilter, AdminUserResponse, AuditChainVerificationResponse, AuditLogFilter, AuditLogResponse,
+    ChangeRoleRequest,
+};
+use crate::payloads::invite::{CreateInviteRequest, InviteFilter, InviteResponse};
+use crate::payloads::pagination::Page;
+use crate::repositories::invite_repository::InviteRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::audit_service::AuditService;
+use crate::services::email_service::EmailService;
+use crate::services::password_hash_service::PasswordHashService;
+use crate::utils::token_util::{generate_token, hash_token};
+use chrono::Duration;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+const DEFAULT_INVITE_EXPIRY_IN_DAYS: i64 = 7;
+
+pub struct AdminService {
+    user_repo: Arc<UserRepository>,
+    audit_service: Arc<AuditService>,
+    invite_repo: Arc<InviteRepository>,
+    email_service: Arc<EmailService>,
+    password_hasher: Arc<PasswordHashService>,
+}
+
+impl AdminService {
+    pub fn new(
+        user_repo: Arc<UserRepository>,
+        audit_service: Arc<AuditService>,
+        invite_repo: Arc<InviteRepository>,
+        email_service: Arc<EmailService>,
+        password_hasher: Arc<PasswordHashService>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            user_repo,
+            audit_service,
+            invite_repo,
+            email_service,
+            password_hasher,
+        })
+    }
+
+    pub async fn list_users(&self, filter: AdminUserFilter) -> Result<Page<AdminUserResponse>, AppError> {
+        self.user_repo
+            .find_with_filters(filter)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn change_role(
+        &self,
+        admin_id: i64,
+        user_id: i64,
+        req: ChangeRoleRequest,
+        client: &ClientInfo,
+    ) -> Result<AdminUserResponse, AppError> {
+        if admin_id == user_id {
+            return Err(AppError::BadRequest("You cannot change your own role.".into()));
+        }
+
+        let user = self.find_user(user_id).await?;
+        if user.role == req.role {
+            return Ok(AdminUserResponse::from_user(&user));
+        }
+
+        let audit = self.audit(
+            admin_id,
+            AuditAction::RoleChanged,
+            user_id,
+            json!({ "from": user.role, "to": req.role }),
+            client,
+        );
+
+        let updated = self
+            .user_repo
+            .update_role(user_id, &req.role, audit)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(AdminUserResponse::from_user(&updated))
+    }
+
+    pub async fn deactivate_user(&self, admin_id: i64, user_id: i64, client: &ClientInfo) -> Result<(), AppError> {
+        if admin_id == user_id {
+            return Err(AppError::BadRequest("You cannot deactivate your own account.".into()));
+        }
+
+        let user = self.find_user(user_id).await?;
+        if user.deleted {
+            return Err(AppError::BadRequest("User is already deactivated.".into()));
+        }
+
+        let audit = self.audit(admin_id, AuditAction::AccountDeactivated, user_id, json!({ "email": user.email }), client);
+
+        self.user_repo
+            .deactivate(user_id, audit)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn reactivate_user(&self, admin_id: i64, user_id: i64, client: &ClientInfo) -> Result<AdminUserResponse, AppError> {
+        let user = self.find_user(user_id).await?;
+        if !user.deleted {
+            return Err(AppError::BadRequest("User is not deactivated.".into()));
+        }
+
+        let audit = self.audit(admin_id, AuditAction::AccountReactivated, user_id, json!({ "email": user.email }), client);
+
+        // Another account may have registered the address while this one was deactivated.
+        let reactivated = self.user_repo.reactivate(user_id, audit).await.map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                AppError::ResourceExists("Another active account already uses this email.".into())
+            }
+            e => AppError::DatabaseError(e.to_string()),
+        })?;
+
+        Ok(AdminUserResponse::from_user(&reactivated))
+    }
+
+    /// Replaces the password with an unusable one, signs the user out everywhere
+    /// and emails them a reset link.
+    pub async fn force_password_reset(&self, admin_id: i64, user_id: i64, client: &ClientInfo) -> Result<(), AppError> {
+        let user = self.find_user(user_id).await?;
+
+        let unusable_password = self.password_hasher.hash(&Uuid::new_v4().to_string()).await?;
+
+        let audit = self.audit(admin_id, AuditAction::PasswordResetForced, user_id, json!({ "email": user.email }), client);
+
+        let reset_token = self
+            .user_repo
+            .force_password_reset(user.id, unusable_password, Token::new(user.id), audit)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let email_service = self.email_service.clone();
+        let full_name = format!("{} {}", user.first_name, user.last_name);
+        let user_email = user.email.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = email_service
+                .send_password_reset_email(&user_email, &full_name, &reset_token.token, &reset_token.expires_at)
+                .await
+            {
+                error!("Failed to send forced password reset email to {}: {:?}", user_email, e);
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn list_audit_logs(&self, filter: AuditLogFilter) -> Result<Page<AuditLogResponse>, AppError> {
+        self.audit_service.list(filter).await
+    }
+
+    pub async fn verify_audit_chain(&self) -> Result<AuditChainVerificationResponse, AppError> {
+        self.audit_service.verify_chain().await
+    }
+
+    /// Creates an invite and emails the sign-up link. Only the hash of the token is stored.
+    pub async fn create_invite(
+        &self,
+        admin_id: i64,
+        req: CreateInviteRequest,
+        client: &ClientInfo,
+    ) -> Result<InviteResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let email = req.email.trim().to_string();
+
+        let exists = self
+            .user_repo
+            .exists_by_email(email.clone())
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if exists {
+            return Err(AppError::ResourceExists("An account with this email already exists.".into()));
+        }
+
+        let admin = self.find_user(admin_id).await?;
+        let token = generate_token();
+        let expires_in = Duration::days(req.expires_in_days.unwrap_or(DEFAULT_INVITE_EXPIRY_IN_DAYS));
+
+        let invite = self
+            .invite_repo
+            .save(Invite::new(email, hash_token(&token), req.role, admin_id, expires_in), |invite| {
+                self.record(
+                    admin_id,
+                    AuditAction::InviteCreated,
+                    AuditTargetType::Invite,
+                    invite.id,
+                    json!({ "email": invite.email, "role": invite.role }),
+                    client,
+                )
+            })
+            .await
+            .map_err(|e| {
+                error!("Failed to save invite for {}: {:?}", req.email, e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        let email_service = self.email_service.clone();
+        let inviter_name = format!("{} {}", admin.first_name, admin.last_name);
+        let invite_email = invite.email.clone();
+        let expires_at = invite.expires_at;
+
+        tokio::spawn(async move {
+            if let Err(e) = email_service
+                .send_invite(&invite_email, &inviter_name, &token, &expires_at)
+                .await
+            {
+                error!("Failed to send invite email to {}: {:?}", invite_email, e);
+            }
+        });
+
+        Ok(InviteResponse::from_invite(&invite))
+    }
+
+    pub async fn list_invites(&self, filter: InviteFilter) -> Result<Page<InviteResponse>, AppError> {
+        self.invite_repo
+            .find_with_filters(filter)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn revoke_invite(&self, admin_id: i64, invite_id: i64, client: &ClientInfo) -> Result<(), AppError> {
+        self.invite_repo
+            .revoke(invite_id, |invite| {
+                self.record(
+                    admin_id,
+                    AuditAction::InviteRevoked,
+                    AuditTargetType::Invite,
+                    invite.id,
+                    json!({ "email": invite.email }),
+                    client,
+                )
+            })
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::ResourceNotFound("Pending invite not found.".into()))?;
+
+        Ok(())
+    }
+
+    async fn find_user(&self, user_id: i64) -> Result<User, AppError> {
+        self.user_repo
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound("User not found.".into()))
+    }
+
+    fn audit(
+        &self,
+        admin_id: i64,
+        action: AuditAction,
+        user_id: i64,
+        details: Value,
+        client: &ClientInfo,
+    ) -> AuditLog {
+        self.record(admin_id, action, AuditTargetType::User, user_id, details, client)
+    }
+
+    /// Builds the audit entry that the repository writes together with the action.
+    fn record(
+        &self,
+        admin_id: i64,
+        action: AuditAction,
+        target_type: AuditTargetType,
+        target_id: i64,
+        details: Value,
+        client: &ClientInfo,
+    ) -> AuditLog {
+        info!("Admin {} performed {:?} on {:?} {}", admin_id, action, target_type, target_id);
+
+        AuditLog::new(Some(admin_id), action, target_type, Some(target_id), details).with_client(client)
+    }
+}
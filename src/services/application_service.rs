@@ -1,34 +1,97 @@
-use crate::enums::application::Status;
+use crate::enums::application::{BulkOutcome, DuplicateMatch, Status};
 use crate::errors::app_error::{extract_validation_errors, AppError};
 use crate::models::application::{Application, ApplicationStatus};
 use crate::payloads::application::{
     ApplicationFilter, ApplicationRequest, ApplicationStatusRequest, ApplicationStatusResponse,
-    ApplicationsResponse,
+    ApplicationsResponse, CompanySuggestion, CompanySuggestionQuery, CreatedApplicationResponse,
+    BulkApplicationAction, BulkApplicationRequest, BulkApplicationResponse, DuplicateCandidate,
+    DuplicateCluster, DuplicateScanQuery,
 };
 use crate::payloads::dashboard::{ApplicationTrendsRequest, ApplicationTrendsResponse, DashboardCount, SuccessRate};
+use crate::payloads::pagination::{Cursor, Page};
+use crate::models::preferences::UserPreferences;
 use crate::repositories::application_repository::ApplicationRepository;
-use serde_json::Value;
-use std::collections::HashMap;
+use crate::repositories::preferences_repository::PreferencesRepository;
+use crate::repositories::saved_view_repository::SavedViewRepository;
+use chrono::Duration;
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 use validator::Validate;
 
+const DEFAULT_COMPANY_SUGGESTIONS: i64 = 10;
+const DEFAULT_DUPLICATE_WINDOW_DAYS: i32 = 90;
+const MAX_BULK_APPLICATIONS: i64 = 500;
+/// Similarity above which another spelling of a company is reported as a likely typo when
+/// registering an application.
+const NEAR_DUPLICATE_COMPANY_SCORE: f32 = 0.5;
+
 pub struct ApplicationService {
     application_repo: Arc<ApplicationRepository>,
+    preferences_repo: Arc<PreferencesRepository>,
+    saved_view_repo: Arc<SavedViewRepository>,
 }
 
 impl ApplicationService {
-    pub fn new(application_repo: Arc<ApplicationRepository>) -> Arc<Self> {
-        Arc::new(Self { application_repo })
+    pub fn new(
+        application_repo: Arc<ApplicationRepository>,
+        preferences_repo: Arc<PreferencesRepository>,
+        saved_view_repo: Arc<SavedViewRepository>,
+    ) -> Arc<Self> {
+        Arc::new(Self { application_repo, preferences_repo, saved_view_repo })
+    }
+
+    async fn preferences(&self, user_id: i64) -> Result<UserPreferences, AppError> {
+        self.preferences_repo
+            .find_by_user_id(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
     }
 
     pub async fn create_application(
         &self,
-        req: ApplicationRequest,
+        mut req: ApplicationRequest,
         user_id: i64,
-    ) -> Result<ApplicationsResponse, AppError> {
+    ) -> Result<CreatedApplicationResponse, AppError> {
         req.validate()
             .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
 
+        if !req.allow_duplicate {
+            let duplicates = self
+                .application_repo
+                .find_possible_duplicates(
+                    user_id,
+                    &req.company,
+                    &req.position,
+                    req.website.as_deref(),
+                    DEFAULT_DUPLICATE_WINDOW_DAYS,
+                )
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+            if !duplicates.is_empty() {
+                return Err(AppError::Conflict {
+                    message: "You recently applied to this position, set allowDuplicate to register it anyway.".into(),
+                    details: json!({ "duplicates": duplicates }),
+                });
+            }
+        }
+
+        // Looked up before saving so the new application does not match itself.
+        let similar_companies = self
+            .application_repo
+            .find_similar_companies(user_id, &req.company, DEFAULT_COMPANY_SUGGESTIONS)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .filter(|s| s.score >= NEAR_DUPLICATE_COMPANY_SCORE && s.company != req.company.trim())
+            .map(|s| s.company)
+            .collect();
+
+        if req.application_type.is_none() {
+            req.application_type = self.preferences(user_id).await?.default_application_type;
+        }
+
         let application = self
             .application_repo
             .save(Application::from_application_request(&req, user_id))
@@ -48,12 +111,105 @@ impl ApplicationService {
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))?;
 
-        Ok(ApplicationsResponse::from_application_and_status(
-            &application,
-            &vec![default_status],
+        Ok(CreatedApplicationResponse {
+            application: ApplicationsResponse::from_application_and_status(
+                &application,
+                &vec![default_status],
+            ),
+            similar_companies,
+        })
+    }
+
+    pub async fn bulk_update(
+        &self,
+        user_id: i64,
+        req: BulkApplicationRequest,
+    ) -> Result<BulkApplicationResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let action = req.action.normalized();
+        let mut ids = match (req.ids, req.filter) {
+            (Some(ids), None) => ids,
+            (None, Some(_)) if matches!(action, BulkApplicationAction::Restore) => {
+                return Err(AppError::BadRequest("Deleted applications can only be restored by id.".into()));
+            }
+            (None, Some(filter)) => {
+                let filter = self.resolve_filter(user_id, filter).await?;
+                let ids = self
+                    .application_repo
+                    .find_ids_by_user_with_filters(user_id, filter, MAX_BULK_APPLICATIONS + 1)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                if ids.len() as i64 > MAX_BULK_APPLICATIONS {
+                    return Err(AppError::BadRequest(format!(
+                        "The filter matches more than {} applications, narrow it down.",
+                        MAX_BULK_APPLICATIONS
+                    )));
+                }
+                ids
+            }
+            _ => return Err(AppError::BadRequest("Give either ids or a filter.".into())),
+        };
+
+        let mut seen = HashSet::new();
+        ids.retain(|id| seen.insert(*id));
+
+        let results = self
+            .application_repo
+            .apply_bulk_action(user_id, &ids, &action)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let count = |outcome| results.iter().filter(|result| result.outcome == outcome).count();
+        Ok(BulkApplicationResponse {
+            updated: count(BulkOutcome::Updated),
+            skipped: count(BulkOutcome::Skipped),
+            not_found: count(BulkOutcome::NotFound),
+            results,
+        })
+    }
+
+    pub async fn find_duplicates(
+        &self,
+        user_id: i64,
+        query: DuplicateScanQuery,
+    ) -> Result<Vec<DuplicateCluster>, AppError> {
+        query
+            .validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let candidates = self
+            .application_repo
+            .find_duplicate_candidates(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(cluster_duplicates(
+            candidates,
+            Duration::days(query.window_days.unwrap_or(DEFAULT_DUPLICATE_WINDOW_DAYS).into()),
         ))
     }
 
+    pub async fn suggest_companies(
+        &self,
+        user_id: i64,
+        query: CompanySuggestionQuery,
+    ) -> Result<Vec<CompanySuggestion>, AppError> {
+        query
+            .validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        self.application_repo
+            .find_similar_companies(
+                user_id,
+                &query.search,
+                query.limit.unwrap_or(DEFAULT_COMPANY_SUGGESTIONS),
+            )
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
     pub async fn add_application_status(
         &self,
         user_id: i64,
@@ -61,7 +217,7 @@ impl ApplicationService {
     ) -> Result<ApplicationStatusResponse, AppError> {
         match self
             .application_repo
-            .exists_by_application_id(req.application_id)
+            .exists_by_application_id(req.application_id, user_id)
             .await
         {
             Ok(false) => {
@@ -82,16 +238,64 @@ impl ApplicationService {
             .map_err(|e| AppError::DatabaseError(e.to_string()))
     }
 
+    /// Validates the filter after filling it in from the saved view it refers to, if any.
+    async fn resolve_filter(&self, created_by: i64, mut filter: ApplicationFilter) -> Result<ApplicationFilter, AppError> {
+        if let Some(view_id) = filter.view {
+            let view = self
+                .saved_view_repo
+                .find_by_id(view_id, created_by)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                .ok_or_else(|| AppError::ResourceNotFound("Saved view not found.".into()))?;
+            filter = filter.or_saved(view.filter.0);
+        }
+
+        filter
+            .validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+        Ok(filter)
+    }
+
     pub async fn fetch_applications_for_user_with_filters(
         &self,
         created_by: i64,
         filter: ApplicationFilter,
-    ) -> Result<HashMap<String, Value>, AppError> {
-        self.application_repo
-            .find_applications_by_user_with_filters(created_by, filter)
-            .await
-            .map(|paginated_response| paginated_response)
-            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    ) -> Result<Page<ApplicationsResponse>, AppError> {
+        let mut filter = self.resolve_filter(created_by, filter).await?;
+
+        if filter.size.is_none() {
+            filter.size = Some(self.preferences(created_by).await?.default_page_size);
+        }
+
+        let facet_filter = filter.facets.is_some().then(|| filter.clone());
+
+        let page = match filter.cursor.clone() {
+            Some(token) => {
+                let cursor = decode_cursor(&token, &filter)?;
+
+                self.application_repo
+                    .find_applications_by_user_with_cursor(created_by, filter, cursor)
+                    .await
+            }
+            None => {
+                self.application_repo
+                    .find_applications_by_user_with_filters(created_by, filter)
+                    .await
+            }
+        }
+        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        match facet_filter {
+            Some(filter) => {
+                let facets = self
+                    .application_repo
+                    .find_facet_counts(created_by, &filter, filter.facets.as_deref().unwrap_or_default())
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+                Ok(page.with_facets(facets))
+            }
+            None => Ok(page),
+        }
     }
 
     pub async fn compute_stats(&self, created_by: i64) -> Result<DashboardCount, AppError> {
@@ -110,9 +314,247 @@ impl ApplicationService {
     }
 
     pub async fn get_chart_data(&self, user_id: i64, req: ApplicationTrendsRequest) -> Result<ApplicationTrendsResponse, AppError> {
+        let timezone = self.preferences(user_id).await?.timezone;
+
         self.application_repo
-            .get_chart_data(user_id, req)
+            .get_chart_data(user_id, req, &timezone)
             .await
             .map_err(|e| AppError::DatabaseError(e.to_string()))
     }
 }
+
+/// Decodes the cursor of a page request; an empty one starts from the first page. Cursors
+/// issued for another sort order or search are rejected.
+fn decode_cursor(token: &str, filter: &ApplicationFilter) -> Result<Option<Cursor>, AppError> {
+    match token.trim() {
+        "" => Ok(None),
+        token => Cursor::decode(token)
+            .filter(|cursor| cursor.ordering == filter.ordering())
+            .map(Some)
+            .ok_or_else(|| AppError::BadRequest("Invalid cursor, or one issued for a different sort order or search.".into())),
+    }
+}
+
+/// Groups applications to the same position at the same company that were made within `window`
+/// of each other. A shared website alone is not enough, since many companies post through the
+/// same job boards; it is only reported alongside the company. Candidates must be ordered oldest
+/// first, so only neighbours sharing a key need comparing. Clusters are returned most recently
+/// active first.
+fn cluster_duplicates(candidates: Vec<DuplicateCandidate>, window: Duration) -> Vec<DuplicateCluster> {
+    let mut keys: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        if !candidate.position_key.is_empty() && !candidate.company_key.is_empty() {
+            keys.entry((&candidate.position_key, &candidate.company_key))
+                .or_default()
+                .push(i);
+        }
+    }
+
+    let mut links = Vec::new();
+    for members in keys.values() {
+        for pair in members.windows(2) {
+            let (first, second) = (&candidates[pair[0]], &candidates[pair[1]]);
+            if second.application.created_at - first.application.created_at <= window {
+                links.push((pair[0], pair[1], DuplicateMatch::Company));
+                if first.domain.is_some() && first.domain == second.domain {
+                    links.push((pair[0], pair[1], DuplicateMatch::Website));
+                }
+            }
+        }
+    }
+
+    let mut parents: Vec<usize> = (0..candidates.len()).collect();
+    fn root(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+    for &(a, b, _) in &links {
+        let (a, b) = (root(&mut parents, a), root(&mut parents, b));
+        parents[a.max(b)] = a.min(b);
+    }
+
+    let mut matched_on: HashMap<usize, BTreeSet<DuplicateMatch>> = HashMap::new();
+    for &(a, _, reason) in &links {
+        matched_on.entry(root(&mut parents, a)).or_default().insert(reason);
+    }
+
+    let mut clusters: HashMap<usize, DuplicateCluster> = HashMap::new();
+    for (i, candidate) in candidates.into_iter().enumerate() {
+        let cluster_root = root(&mut parents, i);
+        if let Some(reasons) = matched_on.get(&cluster_root) {
+            clusters
+                .entry(cluster_root)
+                .or_insert_with(|| DuplicateCluster {
+                    matched_on: reasons.iter().copied().collect(),
+                    applications: Vec::new(),
+                })
+                .applications
+                .push(candidate.application);
+        }
+    }
+
+    let mut clusters: Vec<DuplicateCluster> = clusters.into_values().collect();
+    clusters.sort_by_key(|cluster| {
+        std::cmp::Reverse(cluster.applications.last().map(|application| application.created_at))
+    });
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payloads::application::DuplicateApplication;
+    use chrono::{Local, TimeZone};
+
+    const WINDOW: Duration = Duration::days(90);
+
+    /// Candidates are built with the keys the repository would compute; `day` is days since
+    /// an arbitrary start.
+    fn candidate(id: i64, company: &str, position: &str, domain: Option<&str>, day: i64) -> DuplicateCandidate {
+        DuplicateCandidate {
+            application: DuplicateApplication {
+                id,
+                company: company.to_string(),
+                position: position.to_string(),
+                website: domain.map(|domain| format!("https://{}/jobs/{}", domain, id)),
+                created_at: Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap() + Duration::days(day),
+                matched_on: Vec::new(),
+            },
+            company_key: company.to_lowercase(),
+            position_key: position.to_lowercase(),
+            domain: domain.map(String::from),
+        }
+    }
+
+    fn ids(cluster: &DuplicateCluster) -> Vec<i64> {
+        cluster.applications.iter().map(|application| application.id).collect()
+    }
+
+    fn search(term: &str) -> ApplicationFilter {
+        ApplicationFilter { search: Some(term.to_string()), ..ApplicationFilter::default() }
+    }
+
+    fn cursor_for(filter: &ApplicationFilter) -> String {
+        Cursor { ordering: filter.ordering(), backward: false, values: vec![json!(0.5), json!(42)] }.encode()
+    }
+
+    #[test]
+    fn a_cursor_is_only_accepted_for_the_search_it_was_issued_for() {
+        let token = cursor_for(&search("acme engineer"));
+
+        assert!(decode_cursor(&token, &search("acme engineer")).unwrap().is_some());
+        // Case and punctuation do not change the ranking.
+        assert!(decode_cursor(&token, &search("Acme, Engineer")).unwrap().is_some());
+
+        assert!(matches!(decode_cursor(&token, &search("globex")), Err(AppError::BadRequest(_))));
+        assert!(matches!(decode_cursor(&token, &search("acme")), Err(AppError::BadRequest(_))));
+        assert!(matches!(decode_cursor(&token, &ApplicationFilter::default()), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn a_search_without_searchable_words_uses_the_default_ordering() {
+        let token = cursor_for(&ApplicationFilter::default());
+
+        assert!(decode_cursor(&token, &search("!!!")).unwrap().is_some());
+        assert!(decode_cursor("  ", &search("acme")).unwrap().is_none());
+        assert!(decode_cursor("not-a-cursor", &ApplicationFilter::default()).is_err());
+    }
+
+    #[test]
+    fn only_applications_within_the_window_are_duplicates() {
+        let clusters = cluster_duplicates(
+            vec![
+                candidate(1, "acme", "engineer", None, 0),
+                candidate(2, "acme", "engineer", None, 90),
+                candidate(3, "acme", "engineer", None, 181),
+            ],
+            WINDOW,
+        );
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(ids(&clusters[0]), vec![1, 2]);
+    }
+
+    #[test]
+    fn a_shared_website_alone_is_not_a_duplicate() {
+        let clusters = cluster_duplicates(
+            vec![
+                candidate(1, "acme", "engineer", Some("jobs.example.com"), 0),
+                candidate(2, "globex", "engineer", Some("jobs.example.com"), 1),
+                candidate(3, "acme", "designer", Some("jobs.example.com"), 2),
+            ],
+            WINDOW,
+        );
+
+        assert!(clusters.is_empty());
+    }
+
+    #[test]
+    fn applications_without_company_or_position_are_never_grouped() {
+        let clusters = cluster_duplicates(
+            vec![candidate(1, "", "engineer", None, 0), candidate(2, "", "engineer", None, 1)],
+            WINDOW,
+        );
+
+        assert!(clusters.is_empty());
+    }
+
+    #[test]
+    fn chains_of_close_applications_form_one_cluster() {
+        // 1 and 3 are further apart than the window, but each is close to 2.
+        let clusters = cluster_duplicates(
+            vec![
+                candidate(1, "acme", "engineer", None, 0),
+                candidate(2, "acme", "engineer", None, 80),
+                candidate(3, "acme", "engineer", None, 160),
+            ],
+            WINDOW,
+        );
+
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(ids(&clusters[0]), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn matched_on_covers_every_link_in_the_cluster() {
+        let clusters = cluster_duplicates(
+            vec![
+                candidate(1, "acme", "engineer", Some("acme.com"), 0),
+                candidate(2, "acme", "engineer", Some("acme.com"), 10),
+                candidate(3, "acme", "engineer", None, 20),
+                candidate(4, "globex", "engineer", Some("globex.com"), 30),
+                candidate(5, "globex", "engineer", Some("boards.example.com"), 40),
+            ],
+            WINDOW,
+        );
+
+        let acme = clusters.iter().find(|cluster| ids(cluster).contains(&1)).unwrap();
+        assert_eq!(ids(acme), vec![1, 2, 3]);
+        assert_eq!(acme.matched_on, vec![DuplicateMatch::Company, DuplicateMatch::Website]);
+
+        let globex = clusters.iter().find(|cluster| ids(cluster).contains(&4)).unwrap();
+        assert_eq!(ids(globex), vec![4, 5]);
+        assert_eq!(globex.matched_on, vec![DuplicateMatch::Company]);
+    }
+
+    #[test]
+    fn clusters_are_ordered_most_recently_active_first() {
+        let clusters = cluster_duplicates(
+            vec![
+                candidate(1, "acme", "engineer", None, 0),
+                candidate(2, "globex", "designer", None, 5),
+                candidate(3, "globex", "designer", None, 10),
+                candidate(4, "initech", "analyst", None, 15),
+                candidate(5, "initech", "analyst", None, 20),
+                candidate(6, "acme", "engineer", None, 30),
+            ],
+            WINDOW,
+        );
+
+        let order: Vec<Vec<i64>> = clusters.iter().map(ids).collect();
+        assert_eq!(order, vec![vec![1, 6], vec![4, 5], vec![2, 3]]);
+    }
+}
@@ -1,55 +1,130 @@
+use crate::enums::audit::{AuditAction, AuditTargetType};
 use crate::errors::app_error::{AppError, extract_validation_errors};
-use crate::payloads::auth::{ForgotPasswordRequest, LoginRequest, ResetPasswordRequest};
+use crate::payloads::auth::{
+    ChangeEmailRequest, ChangePasswordRequest, ConfirmEmailChangeRequest, ForgotPasswordRequest,
+    LoginRequest, LoginResponse, ResetPasswordRequest,
+};
+use crate::payloads::password::{PasswordStrengthRequest, PasswordStrengthResponse};
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::payloads::mfa::MfaChallengeResponse;
+use crate::repositories::mfa_repository::MfaRepository;
 use crate::repositories::user_repository::UserRepository;
-use crate::utils::jwt::{JwtToken, create_jwt};
-use bcrypt::{hash, verify, DEFAULT_COST};
+use crate::utils::jwt::{create_mfa_challenge_token, validate_refresh_token, JwtToken, MFA_CHALLENGE_EXPIRY_IN_MINUTES};
+use serde_json::{json, Value};
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, info};
 use validator::Validate;
+use crate::models::audit_log::AuditLog;
 use crate::models::token::Token;
 use crate::repositories::token_repository::TokenRepository;
+use crate::services::audit_service::AuditService;
 use crate::services::email_service::EmailService;
+use crate::services::password_hash_service::PasswordHashService;
+use crate::services::password_policy_service::PasswordPolicyService;
+use crate::services::session_service::{SessionService, SESSION_REVOKED};
+use crate::models::email_change::EmailChangeRequest;
+use crate::models::preferences::UserPreferences;
+use crate::repositories::email_change_repository::EmailChangeRepository;
+use crate::repositories::preferences_repository::PreferencesRepository;
 
 pub struct AuthService {
     pub user_repo: Arc<UserRepository>,
     pub token_repo: Arc<TokenRepository>,
     pub email_service: Arc<EmailService>,
+    pub mfa_repo: Arc<MfaRepository>,
+    pub email_change_repo: Arc<EmailChangeRepository>,
+    pub password_policy: Arc<PasswordPolicyService>,
+    pub password_hasher: Arc<PasswordHashService>,
+    pub session_service: Arc<SessionService>,
+    pub audit_service: Arc<AuditService>,
+    pub preferences_repo: Arc<PreferencesRepository>,
 }
 
 const INVALID_CREDENTIALS: &str = "Invalid email or password. Please check and try again.";
 
 impl AuthService {
-    pub fn new(user_repo: Arc<UserRepository>, token_repo: Arc<TokenRepository>, email_service: Arc<EmailService>) -> Arc<Self> {
-        Arc::new(Self { user_repo, token_repo, email_service })
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repo: Arc<UserRepository>,
+        token_repo: Arc<TokenRepository>,
+        email_service: Arc<EmailService>,
+        mfa_repo: Arc<MfaRepository>,
+        email_change_repo: Arc<EmailChangeRepository>,
+        password_policy: Arc<PasswordPolicyService>,
+        password_hasher: Arc<PasswordHashService>,
+        session_service: Arc<SessionService>,
+        audit_service: Arc<AuditService>,
+        preferences_repo: Arc<PreferencesRepository>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            user_repo,
+            token_repo,
+            email_service,
+            mfa_repo,
+            email_change_repo,
+            password_policy,
+            password_hasher,
+            session_service,
+            audit_service,
+            preferences_repo,
+        })
     }
 
-    pub async fn login(&self, req: LoginRequest) -> Result<JwtToken, AppError> {
+    pub async fn login(&self, req: LoginRequest, client: &ClientInfo) -> Result<LoginResponse, AppError> {
         req.validate()
             .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
 
-        let user = self
-            .user_repo
-            .get_user_by_email(req.email.clone())
-            .await
-            .map_err(|e| {
+        let user = match self.user_repo.get_user_by_email(req.email.clone()).await {
+            Ok(user) => user,
+            Err(e) => {
                 error!("Failed to find user by email {}: {:?}", req.email, e);
-                AppError::BadRequest(String::from(INVALID_CREDENTIALS))
-            })?;
+                self.password_hasher.verify_dummy(&req.password).await;
+                return Err(self.reject_login(&req.email, None, "unknown_email", client).await);
+            }
+        };
 
-        let is_password_valid = verify(&req.password, &user.password)
-            .map_err(|e| {
+        let password_check = match self.password_hasher.verify(&req.password, &user.password).await {
+            Ok(password_check) => password_check,
+            Err(e) => {
                 error!("Password verification failed for user_id {}: {:?}", user.id, e);
-                AppError::BadRequest(String::from(INVALID_CREDENTIALS))
-            })?;
+                return Err(self.reject_login(&req.email, Some(user.id), "invalid_password", client).await);
+            }
+        };
+
+        if !password_check.is_valid {
+            return Err(self.reject_login(&req.email, Some(user.id), "invalid_password", client).await);
+        }
 
-        if !is_password_valid {
-            return Err(AppError::BadRequest(String::from(INVALID_CREDENTIALS)));
+        if password_check.needs_rehash {
+            self.upgrade_password_hash(user.id, &user.password, &req.password).await;
         }
 
-        Ok(create_jwt(&user.id, &user.role, req.remember_me))
+        let mfa_enabled = self
+            .mfa_repo
+            .find_by_user_id(user.id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .is_some_and(|mfa| mfa.enabled);
+
+        if mfa_enabled {
+            return Ok(LoginResponse::MfaRequired(MfaChallengeResponse {
+                mfa_required: true,
+                challenge_token: create_mfa_challenge_token(&user.id, req.remember_me),
+                expires_in: MFA_CHALLENGE_EXPIRY_IN_MINUTES,
+            }));
+        }
+
+        let token = self.session_service.start(&user, client, req.remember_me).await?;
+        Ok(LoginResponse::Authenticated(token))
     }
 
-    pub async fn logout(&self, user_id: i64) -> Result<(), AppError> {
+    pub async fn logout(&self, user_id: i64, session_id: Option<i64>, client: &ClientInfo) -> Result<(), AppError> {
+        match session_id {
+            Some(session_id) => self.session_service.log_out(user_id, session_id, client).await?,
+            // Tokens issued before sessions existed have nothing to revoke.
+            None => self.audit(Some(user_id), AuditAction::Logout, user_id, json!({}), client).await?,
+        }
+
         self.token_repo
             .invalidate_existing_tokens_for_user(user_id)
             .await
@@ -59,7 +134,26 @@ impl AuthService {
             })
     }
 
-    pub async fn forgot_password(&self, req: ForgotPasswordRequest) -> Result<(), AppError> {
+    /// Exchanges a refresh token for new tokens. Refresh tokens of deactivated users, of revoked
+    /// sessions and from before a token version bump are rejected.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<JwtToken, AppError> {
+        let claims = validate_refresh_token(refresh_token)
+            .map_err(|e| AppError::InvalidToken(e.to_string()))?;
+
+        let user = self
+            .user_repo
+            .get_active_user_by_id(claims.subject)
+            .await
+            .map_err(|_| AppError::InvalidToken(SESSION_REVOKED.into()))?;
+
+        if user.token_version != claims.token_version {
+            return Err(AppError::InvalidToken(SESSION_REVOKED.into()));
+        }
+
+        self.session_service.resume(&user, &claims).await
+    }
+
+    pub async fn forgot_password(&self, req: ForgotPasswordRequest, client: &ClientInfo) -> Result<(), AppError> {
         req.validate()
             .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
 
@@ -79,6 +173,9 @@ impl AuthService {
             return Err(AppError::DatabaseError(e.to_string()));
         }
 
+        // Anyone can ask for a reset link, so there is no actor.
+        self.audit(None, AuditAction::PasswordResetRequested, user.id, json!({}), client).await?;
+
 
         // Clone data for the closure
         let email_service = self.email_service.clone();
@@ -97,7 +194,7 @@ impl AuthService {
         Ok(())
     }
 
-    pub async fn reset_password(&self, req: ResetPasswordRequest) -> Result<(), AppError> {
+    pub async fn reset_password(&self, req: ResetPasswordRequest, client: &ClientInfo) -> Result<(), AppError> {
         req.validate()
             .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
 
@@ -124,11 +221,15 @@ impl AuthService {
                 AppError::BadRequest("Invalid token".into())
             })?;
 
-        let password_hash = hash(&req.password, DEFAULT_COST)
-            .map_err(|e| AppError::AuthError(format!("Failed to hash password: {}", e)))?;
+        self.password_policy
+            .enforce(&req.password, &[&user.email, &user.first_name, &user.last_name])?;
 
+        let password_hash = self.password_hasher.hash(&req.password).await?;
+
+        // A reset usually means the account may be compromised, so every existing token and
+        // session is revoked, not just the other ones.
         self.user_repo
-            .update_password(user.id, password_hash)
+            .update_password_and_revoke_sessions(user.id, password_hash)
             .await
             .map_err(|e| {
                 error!("Failed to update password for user {}: {:?}", user.id, e);
@@ -143,7 +244,226 @@ impl AuthService {
                 AppError::DatabaseError(e.to_string())
             })?;
 
+        self.audit(Some(user.id), AuditAction::PasswordReset, user.id, json!({}), client).await?;
+
+        self.session_service.revoke_others(user.id, None, client).await.map(|_| ())
+    }
+
+    pub fn check_password_strength(&self, req: PasswordStrengthRequest) -> Result<PasswordStrengthResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let user_inputs: Vec<&str> = [&req.email, &req.first_name, &req.last_name]
+            .into_iter()
+            .filter_map(|input| input.as_deref())
+            .collect();
+
+        Ok(self.password_policy.evaluate(&req.password, &user_inputs))
+    }
+
+    /// Changes the password of a logged-in user and revokes all of their existing sessions.
+    /// A fresh token is returned so the session making the change stays signed in.
+    pub async fn change_password(
+        &self,
+        user_id: i64,
+        session_id: Option<i64>,
+        req: ChangePasswordRequest,
+        client: &ClientInfo,
+    ) -> Result<JwtToken, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let user = self.user_repo
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound("User not found.".into()))?;
+
+        self.verify_current_password(&user.password, &req.current_password).await?;
+
+        if self.password_hasher.matches(&req.new_password, &user.password).await {
+            return Err(AppError::BadRequest("New password must be different from the current password.".into()));
+        }
+
+        self.password_policy
+            .enforce(&req.new_password, &[&user.email, &user.first_name, &user.last_name])?;
+
+        let password_hash = self.password_hasher.hash(&req.new_password).await?;
+
+        let token_version = self.user_repo
+            .update_password_and_revoke_sessions(user.id, password_hash)
+            .await
+            .map_err(|e| {
+                error!("Failed to change password for user {}: {:?}", user.id, e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        self.audit(Some(user.id), AuditAction::PasswordChanged, user.id, json!({}), client).await?;
+
+        self.session_service.revoke_others(user.id, session_id, client).await?;
+
+        self.session_service.renew(&user, token_version, session_id, client).await
+    }
+
+    pub async fn change_email(&self, user_id: i64, req: ChangeEmailRequest, client: &ClientInfo) -> Result<(), AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let user = self.user_repo
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound("User not found.".into()))?;
+
+        self.verify_current_password(&user.password, &req.current_password).await?;
+
+        let new_email = req.new_email.trim().to_string();
+        if new_email.eq_ignore_ascii_case(&user.email) {
+            return Err(AppError::BadRequest("New email must be different from the current email.".into()));
+        }
+
+        if self.user_repo.exists_by_email(new_email.clone()).await? {
+            return Err(AppError::ResourceExists("Email is already in use.".into()));
+        }
+
+        let request = self.email_change_repo
+            .save(EmailChangeRequest::new(user.id, new_email))
+            .await
+            .map_err(|e| {
+                error!("Failed to save email change request for user {}: {:?}", user.id, e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        self.audit(
+            Some(user.id),
+            AuditAction::EmailChangeRequested,
+            user.id,
+            json!({ "from": user.email, "to": request.new_email }),
+            client,
+        )
+        .await?;
+
+        let email_service = self.email_service.clone();
+        let preferences_repo = self.preferences_repo.clone();
+        let user_id = user.id;
+        let full_name = format!("{} {}", user.first_name, user.last_name);
+        let old_email = user.email.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = email_service.send_email_change_confirmation(&request.new_email, &full_name, &request.token, &request.expires_at).await {
+                error!("Failed to send email change confirmation to {}: {:?}", request.new_email, e);
+            }
+
+            // The notice to the current address is a security alert, which the user can turn off.
+            let preferences = preferences_repo.find_by_user_id(user_id).await.unwrap_or_else(|e| {
+                error!("Failed to load preferences of user {}: {:?}", user_id, e);
+                UserPreferences::default()
+            });
+
+            if !preferences.notifications.security_alerts {
+                return;
+            }
+
+            if let Err(e) = email_service
+                .send_email_change_notice(&old_email, &full_name, &request.new_email, &preferences.locale)
+                .await
+            {
+                error!("Failed to send email change notice to {}: {:?}", old_email, e);
+            }
+        });
+
+        Ok(())
+    }
+
+    pub async fn confirm_email_change(&self, req: ConfirmEmailChangeRequest, client: &ClientInfo) -> Result<(), AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let request = self.email_change_repo
+            .consume(&req.token)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Invalid or expired token".into()))?;
+
+        // The address may have been taken since the request was made; the unique index on
+        // active emails is the final arbiter.
+        self.user_repo
+            .update_email(request.user_id, &request.new_email)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                    AppError::ResourceExists("Email is already in use.".into())
+                }
+                e => {
+                    error!("Failed to update email for user {}: {:?}", request.user_id, e);
+                    AppError::DatabaseError(e.to_string())
+                }
+            })?;
+
+        self.audit(
+            Some(request.user_id),
+            AuditAction::EmailChanged,
+            request.user_id,
+            json!({ "to": request.new_email }),
+            client,
+        )
+        .await
+    }
+
+    async fn verify_current_password(&self, password_hash: &str, password: &str) -> Result<(), AppError> {
+        let password_check = self.password_hasher.verify(password, password_hash).await.map_err(|e| {
+            error!("Password verification failed: {:?}", e);
+            AppError::BadRequest("Current password is incorrect.".into())
+        })?;
+
+        if !password_check.is_valid {
+            return Err(AppError::BadRequest("Current password is incorrect.".into()));
+        }
         Ok(())
     }
 
+    /// Records a failed login and returns the error to send back, which never reveals whether
+    /// the email exists.
+    async fn reject_login(&self, email: &str, user_id: Option<i64>, reason: &str, client: &ClientInfo) -> AppError {
+        let log = AuditLog::new(
+            None,
+            AuditAction::LoginFailed,
+            AuditTargetType::User,
+            user_id,
+            json!({ "email": email, "reason": reason }),
+        );
+
+        match self.audit_service.record(log.with_client(client)).await {
+            Ok(()) => AppError::BadRequest(String::from(INVALID_CREDENTIALS)),
+            Err(e) => e,
+        }
+    }
+
+    async fn audit(
+        &self,
+        actor_id: Option<i64>,
+        action: AuditAction,
+        user_id: i64,
+        details: Value,
+        client: &ClientInfo,
+    ) -> Result<(), AppError> {
+        self.audit_service
+            .record(AuditLog::new(actor_id, action, AuditTargetType::User, Some(user_id), details).with_client(client))
+            .await
+    }
+
+    /// Re-hashes a legacy or outdated hash with the current scheme after a successful login.
+    /// Failures are only logged, the old hash keeps working.
+    async fn upgrade_password_hash(&self, user_id: i64, current_hash: &str, password: &str) {
+        let new_hash = match self.password_hasher.hash(password).await {
+            Ok(new_hash) => new_hash,
+            Err(e) => {
+                error!("Failed to rehash password for user {}: {:?}", user_id, e);
+                return;
+            }
+        };
+
+        match self.user_repo.rehash_password(user_id, current_hash, new_hash).await {
+            Ok(true) => info!("Upgraded password hash for user {}", user_id),
+            Ok(false) => (),
+            Err(e) => error!("Failed to store upgraded password hash for user {}: {:?}", user_id, e),
+        }
+    }
 }
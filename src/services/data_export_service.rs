@@ -0,0 +1,267 @@
+use crate::errors::app_error::AppError;
+use crate::models::data_export::DataExport;
+use crate::models::user::User;
+use crate::payloads::data_export::{
+    ApplicationExport, ApplicationTagExport, ApplicationWithHistoryExport, AuditLogExport, DataExportResponse,
+    EmailChangeRequestExport, PasswordResetTokenExport, PersonalAccessTokenExport, SavedViewExport, UserExport,
+};
+use crate::repositories::application_repository::ApplicationRepository;
+use crate::repositories::audit_log_repository::AuditLogRepository;
+use crate::repositories::data_export_repository::DataExportRepository;
+use crate::repositories::email_change_repository::EmailChangeRepository;
+use crate::repositories::identity_repository::IdentityRepository;
+use crate::repositories::mfa_repository::MfaRepository;
+use crate::repositories::passkey_repository::PasskeyRepository;
+use crate::repositories::personal_access_token_repository::PersonalAccessTokenRepository;
+use crate::repositories::preferences_repository::PreferencesRepository;
+use crate::repositories::saved_view_repository::SavedViewRepository;
+use crate::repositories::session_repository::SessionRepository;
+use crate::repositories::token_repository::TokenRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::email_service::EmailService;
+use crate::utils::export_util::ExportArchive;
+use crate::utils::token_util::{generate_token, hash_token};
+use chrono::{Duration, Local};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info};
+
+const DOWNLOAD_LINK_TTL_IN_HOURS: i64 = 48;
+
+pub struct DataExportService {
+    user_repo: Arc<UserRepository>,
+    application_repo: Arc<ApplicationRepository>,
+    token_repo: Arc<TokenRepository>,
+    personal_access_token_repo: Arc<PersonalAccessTokenRepository>,
+    preferences_repo: Arc<PreferencesRepository>,
+    passkey_repo: Arc<PasskeyRepository>,
+    identity_repo: Arc<IdentityRepository>,
+    mfa_repo: Arc<MfaRepository>,
+    email_change_repo: Arc<EmailChangeRepository>,
+    session_repo: Arc<SessionRepository>,
+    saved_view_repo: Arc<SavedViewRepository>,
+    audit_log_repo: Arc<AuditLogRepository>,
+    data_export_repo: Arc<DataExportRepository>,
+    email_service: Arc<EmailService>,
+}
+
+impl DataExportService {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repo: Arc<UserRepository>,
+        application_repo: Arc<ApplicationRepository>,
+        token_repo: Arc<TokenRepository>,
+        personal_access_token_repo: Arc<PersonalAccessTokenRepository>,
+        preferences_repo: Arc<PreferencesRepository>,
+        passkey_repo: Arc<PasskeyRepository>,
+        identity_repo: Arc<IdentityRepository>,
+        mfa_repo: Arc<MfaRepository>,
+        email_change_repo: Arc<EmailChangeRepository>,
+        session_repo: Arc<SessionRepository>,
+        saved_view_repo: Arc<SavedViewRepository>,
+        audit_log_repo: Arc<AuditLogRepository>,
+        data_export_repo: Arc<DataExportRepository>,
+        email_service: Arc<EmailService>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            user_repo,
+            application_repo,
+            token_repo,
+            personal_access_token_repo,
+            preferences_repo,
+            passkey_repo,
+            identity_repo,
+            mfa_repo,
+            email_change_repo,
+            session_repo,
+            saved_view_repo,
+            audit_log_repo,
+            data_export_repo,
+            email_service,
+        })
+    }
+
+    /// Records a pending export and builds the archive in the background.
+    /// The download link is only ever sent by email, once the archive is ready.
+    pub async fn request_export(self: &Arc<Self>, user_id: i64) -> Result<DataExportResponse, AppError> {
+        let user = self
+            .user_repo
+            .get_active_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound("User not found.".into()))?;
+
+        let has_pending_export = self
+            .data_export_repo
+            .exists_pending_for_user(user.id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if has_pending_export {
+            return Err(AppError::ResourceExists("An export is already being prepared.".into()));
+        }
+
+        let download_token = generate_token();
+
+        let export = self
+            .data_export_repo
+            .save(DataExport::new(user.id, hash_token(&download_token)))
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                    AppError::ResourceExists("An export is already being prepared.".into())
+                }
+                e => {
+                    error!("Failed to create data export for user {}: {:?}", user.id, e);
+                    AppError::DatabaseError(e.to_string())
+                }
+            })?;
+
+        let service = self.clone();
+        let export_id = export.id;
+        tokio::spawn(async move { service.complete_export(export_id, user, download_token).await });
+
+        info!("Data export {} requested by user {}", export.id, user_id);
+        Ok(DataExportResponse::from_export(&export))
+    }
+
+    pub async fn list_exports(&self, user_id: i64) -> Result<Vec<DataExportResponse>, AppError> {
+        self.data_export_repo
+            .find_all_by_user_id(user_id)
+            .await
+            .map(|exports| exports.iter().map(DataExportResponse::from_export).collect())
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    /// Returns the archive file name and content for a valid download link.
+    pub async fn download(&self, download_token: &str) -> Result<(String, Vec<u8>), AppError> {
+        let (export_id, archive) = self
+            .data_export_repo
+            .find_downloadable_archive(&hash_token(download_token))
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::InvalidToken("Download link is invalid or has expired.".into()))?;
+
+        Ok((format!("appliq-export-{}.zip", export_id), archive))
+    }
+
+    async fn complete_export(&self, export_id: i64, user: User, download_token: String) {
+        let archive = match self.build_archive(&user).await {
+            Ok(archive) => archive,
+            Err(e) => {
+                error!("Failed to build data export {} for user {}: {}", export_id, user.id, e);
+                if let Err(e) = self.data_export_repo.mark_failed(export_id).await {
+                    error!("Failed to mark data export {} as failed: {:?}", export_id, e);
+                }
+                return;
+            }
+        };
+
+        let expires_at = Local::now() + Duration::hours(DOWNLOAD_LINK_TTL_IN_HOURS);
+
+        if let Err(e) = self.data_export_repo.mark_ready(export_id, &archive, expires_at).await {
+            error!("Failed to store data export {}: {:?}", export_id, e);
+            if let Err(e) = self.data_export_repo.mark_failed(export_id).await {
+                error!("Failed to mark data export {} as failed: {:?}", export_id, e);
+            }
+            return;
+        }
+
+        info!("Data export {} for user {} is ready ({} bytes)", export_id, user.id, archive.len());
+
+        if let Err(e) = self
+            .email_service
+            .send_data_export_ready(&user.email, &user.first_name, &download_token, &expires_at)
+            .await
+        {
+            error!("Failed to send data export notification for export {}: {}", export_id, e);
+        }
+    }
+
+    async fn build_archive(&self, user: &User) -> Result<Vec<u8>, AppError> {
+        let db_error = |e: sqlx::Error| AppError::DatabaseError(e.to_string());
+
+        let applications = self.application_repo.find_all_by_user_id(user.id).await.map_err(db_error)?;
+        let statuses = self.application_repo.find_statuses_by_user_id(user.id).await.map_err(db_error)?;
+        let personal_access_tokens = self
+            .personal_access_token_repo
+            .find_history_by_user_id(user.id)
+            .await
+            .map_err(db_error)?;
+        let password_reset_tokens = self.token_repo.find_all_by_user_id(user.id).await.map_err(db_error)?;
+        let preferences = self.preferences_repo.find_by_user_id(user.id).await.map_err(db_error)?;
+        let passkeys = self.passkey_repo.find_all_by_user_id(user.id).await.map_err(db_error)?;
+        let identities = self.identity_repo.find_all_by_user_id(user.id).await.map_err(db_error)?;
+        let mfa = self.mfa_repo.find_by_user_id(user.id).await.map_err(db_error)?;
+        let recovery_codes = self.mfa_repo.find_recovery_codes_by_user_id(user.id).await.map_err(db_error)?;
+        let email_changes = self.email_change_repo.find_all_by_user_id(user.id).await.map_err(db_error)?;
+        let sessions = self.session_repo.find_all_by_user_id(user.id).await.map_err(db_error)?;
+        let saved_views = self.saved_view_repo.find_all_by_user_id(user.id).await.map_err(db_error)?;
+        let audit_logs = self.audit_log_repo.find_all_by_user_id(user.id).await.map_err(db_error)?;
+
+        let user_export = [UserExport::from_user(user)];
+        let application_exports: Vec<ApplicationExport> =
+            applications.iter().map(ApplicationExport::from_application).collect();
+        let personal_access_token_exports: Vec<PersonalAccessTokenExport> =
+            personal_access_tokens.iter().map(PersonalAccessTokenExport::from_token).collect();
+        let password_reset_token_exports: Vec<PasswordResetTokenExport> =
+            password_reset_tokens.iter().map(PasswordResetTokenExport::from_token).collect();
+        let email_change_exports: Vec<EmailChangeRequestExport> =
+            email_changes.iter().map(EmailChangeRequestExport::from_request).collect();
+        let saved_view_exports: Vec<SavedViewExport> = saved_views.iter().map(SavedViewExport::from_view).collect();
+        let audit_log_exports: Vec<AuditLogExport> =
+            audit_logs.iter().map(|log| AuditLogExport::for_user(log, user.id)).collect();
+        let application_tag_exports: Vec<ApplicationTagExport> = applications
+            .iter()
+            .flat_map(|application| {
+                application.tags.iter().map(|tag| ApplicationTagExport {
+                    application_id: application.id,
+                    tag: tag.clone(),
+                })
+            })
+            .collect();
+
+        let mut status_map = HashMap::new();
+        for status in &statuses {
+            status_map.entry(status.application_id).or_insert_with(Vec::new).push(status.clone());
+        }
+
+        let applications_with_history: Vec<ApplicationWithHistoryExport> = applications
+            .iter()
+            .map(|application| ApplicationWithHistoryExport {
+                application: ApplicationExport::from_application(application),
+                tags: application.tags.clone(),
+                status_history: status_map.remove(&application.id).unwrap_or_default(),
+            })
+            .collect();
+
+        let mut archive = ExportArchive::new();
+        archive.add_json("user.json", &user_export[0])?;
+        archive.add_csv("user.csv", &user_export)?;
+        archive.add_json("preferences.json", &preferences)?;
+        archive.add_json("applications.json", &applications_with_history)?;
+        archive.add_csv("applications.csv", &application_exports)?;
+        archive.add_csv("application_statuses.csv", &statuses)?;
+        archive.add_csv("application_tags.csv", &application_tag_exports)?;
+        archive.add_json("saved_views.json", &saved_view_exports)?;
+        archive.add_csv("saved_views.csv", &saved_view_exports)?;
+        archive.add_json("passkeys.json", &passkeys)?;
+        archive.add_csv("passkeys.csv", &passkeys)?;
+        archive.add_json("linked_identities.json", &identities)?;
+        archive.add_csv("linked_identities.csv", &identities)?;
+        archive.add_json("mfa.json", &mfa)?;
+        archive.add_csv("mfa.csv", mfa.as_slice())?;
+        archive.add_json("mfa_recovery_codes.json", &recovery_codes)?;
+        archive.add_csv("mfa_recovery_codes.csv", &recovery_codes)?;
+        archive.add_json("email_change_requests.json", &email_change_exports)?;
+        archive.add_csv("email_change_requests.csv", &email_change_exports)?;
+        archive.add_json("sessions.json", &sessions)?;
+        archive.add_csv("sessions.csv", &sessions)?;
+        archive.add_json("audit_logs.json", &audit_log_exports)?;
+        archive.add_csv("audit_logs.csv", &audit_log_exports)?;
+        archive.add_json("personal_access_tokens.json", &personal_access_token_exports)?;
+        archive.add_csv("personal_access_tokens.csv", &personal_access_token_exports)?;
+        archive.add_json("password_reset_tokens.json", &password_reset_token_exports)?;
+        archive.add_csv("password_reset_tokens.csv", &password_reset_token_exports)?;
+        archive.finish()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::configs::routes::RESET_PASSWORD;
+use crate::configs::routes::{CONFIRM_EMAIL_CHANGE, RESET_PASSWORD, USER_EXPORT_DOWNLOAD, USER_REGISTER};
 use crate::errors::app_error::AppError;
 use crate::utils::date_util::format_relative_time;
 use chrono::{DateTime, Local};
@@ -103,4 +103,119 @@ impl EmailService {
             html_body,
         )
     }
+
+    pub async fn send_email_change_confirmation(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        token: &str,
+        expires_at: &DateTime<Local>,
+    ) -> Result<(), AppError> {
+        info!("Preparing to send email change confirmation to {}", to_email);
+
+        let confirm_link = format!("{}{}?token={}", self.app_url, CONFIRM_EMAIL_CHANGE, token);
+
+        let mut context = Context::new();
+        context.insert("user_name", user_name);
+        context.insert("confirm_link", &confirm_link);
+        context.insert("expires_in", &format_relative_time(expires_at));
+
+        self.render_and_send("email_change_confirmation.html", &context, to_email, "Confirm your new AppliQ email")
+    }
+
+    pub async fn send_email_change_notice(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        new_email: &str,
+        locale: &str,
+    ) -> Result<(), AppError> {
+        info!("Preparing to send email change notice to {}", to_email);
+
+        let mut context = Context::new();
+        context.insert("locale", locale);
+        context.insert("user_name", user_name);
+        context.insert("new_email", new_email);
+
+        self.render_and_send("email_change_notice.html", &context, to_email, "AppliQ email change requested")
+    }
+
+    pub async fn send_data_export_ready(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        token: &str,
+        expires_at: &DateTime<Local>,
+    ) -> Result<(), AppError> {
+        info!("Preparing to send data export notification to {}", to_email);
+
+        let download_link = format!("{}{}?token={}", self.app_url, USER_EXPORT_DOWNLOAD, token);
+
+        let mut context = Context::new();
+        context.insert("user_name", user_name);
+        context.insert("download_link", &download_link);
+        context.insert("expires_in", &format_relative_time(expires_at));
+
+        self.render_and_send("data_export_ready.html", &context, to_email, "Your AppliQ data export is ready")
+    }
+
+    pub async fn send_invite(
+        &self,
+        to_email: &str,
+        inviter_name: &str,
+        token: &str,
+        expires_at: &DateTime<Local>,
+    ) -> Result<(), AppError> {
+        info!("Preparing to send invite to {}", to_email);
+
+        let invite_link = format!("{}{}?inviteToken={}", self.app_url, USER_REGISTER, token);
+
+        let mut context = Context::new();
+        context.insert("inviter_name", inviter_name);
+        context.insert("invite_link", &invite_link);
+        context.insert("expires_in", &format_relative_time(expires_at));
+
+        self.render_and_send("invite.html", &context, to_email, "You have been invited to AppliQ")
+    }
+
+    pub async fn send_new_device_login(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+        signed_in_at: &str,
+        locale: &str,
+    ) -> Result<(), AppError> {
+        info!("Preparing to send new device login alert to {}", to_email);
+
+        let mut context = Context::new();
+        context.insert("locale", locale);
+        context.insert("user_name", user_name);
+        context.insert("user_agent", user_agent.unwrap_or("Unknown device"));
+        context.insert("ip_address", ip_address.unwrap_or("Unknown"));
+        context.insert("signed_in_at", signed_in_at);
+
+        self.render_and_send("new_device_login.html", &context, to_email, "New sign-in to your AppliQ account")
+    }
+
+    fn render_and_send(
+        &self,
+        template: &str,
+        context: &Context,
+        to_email: &str,
+        subject: &str,
+    ) -> Result<(), AppError> {
+        let html_body = self.templates.render(template, context).map_err(|e| {
+            error!("Failed to render HTML template {}: {}", template, e);
+            AppError::EmailError("Failed to render HTML template".to_string())
+        })?;
+
+        let to_email: Mailbox = to_email.parse().map_err(|e| {
+            error!("Invalid recipient email format: {}", e);
+            AppError::EmailError("Invalid recipient email format".to_string())
+        })?;
+
+        send_email(&self.transport, &self.from_email, &to_email, subject, html_body)
+    }
 }
@@ -0,0 +1,315 @@
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::errors::app_error::{extract_validation_errors, AppError};
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::models::audit_log::AuditLog;
+use crate::models::mfa::UserMfa;
+use crate::payloads::mfa::{
+    MfaConfirmRequest, MfaDisableRequest, MfaEnrollmentResponse, MfaVerifyRequest,
+    RecoveryCodesResponse,
+};
+use crate::repositories::mfa_repository::MfaRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::audit_service::AuditService;
+use crate::services::password_hash_service::PasswordHashService;
+use crate::services::session_service::SessionService;
+use crate::utils::jwt::{validate_mfa_challenge_token, JwtToken};
+use crate::utils::totp_util::{
+    build_totp, find_recovery_code, generate_recovery_codes, generate_secret, hash_recovery_code,
+    render_qr_svg, system_clock, verify_code, verify_unused_code, Clock,
+};
+use chrono::{DateTime, Local};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::error;
+use validator::Validate;
+
+pub struct MfaService {
+    user_repo: Arc<UserRepository>,
+    mfa_repo: Arc<MfaRepository>,
+    password_hasher: Arc<PasswordHashService>,
+    session_service: Arc<SessionService>,
+    audit_service: Arc<AuditService>,
+    clock: Clock,
+}
+
+const INVALID_MFA_CODE: &str = "Invalid authentication code.";
+const MFA_LOCKED: &str = "Too many invalid authentication codes. Try again later.";
+const MAX_FAILED_ATTEMPTS: i32 = 5;
+const LOCKOUT_IN_SECONDS: u64 = 15 * 60;
+
+impl MfaService {
+    pub fn new(
+        user_repo: Arc<UserRepository>,
+        mfa_repo: Arc<MfaRepository>,
+        password_hasher: Arc<PasswordHashService>,
+        session_service: Arc<SessionService>,
+        audit_service: Arc<AuditService>,
+    ) -> Arc<Self> {
+        Self::with_clock(user_repo, mfa_repo, password_hasher, session_service, audit_service, system_clock())
+    }
+
+    pub fn with_clock(
+        user_repo: Arc<UserRepository>,
+        mfa_repo: Arc<MfaRepository>,
+        password_hasher: Arc<PasswordHashService>,
+        session_service: Arc<SessionService>,
+        audit_service: Arc<AuditService>,
+        clock: Clock,
+    ) -> Arc<Self> {
+        Arc::new(Self { user_repo, mfa_repo, password_hasher, session_service, audit_service, clock })
+    }
+
+    pub async fn enroll(&self, user_id: i64) -> Result<MfaEnrollmentResponse, AppError> {
+        let user = self
+            .user_repo
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound("User not found.".into()))?;
+
+        if self.find_mfa(user_id).await?.is_some_and(|mfa| mfa.enabled) {
+            return Err(AppError::BadRequest("MFA is already enabled.".into()));
+        }
+
+        let secret = generate_secret();
+        let totp = build_totp(&secret, &user.email)?;
+        let otpauth_uri = totp.get_url();
+        let qr_code_svg = render_qr_svg(&otpauth_uri)?;
+
+        self.mfa_repo
+            .save_pending_secret(user_id, &secret)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(MfaEnrollmentResponse {
+            secret,
+            otpauth_uri,
+            qr_code_svg,
+        })
+    }
+
+    pub async fn confirm(
+        &self,
+        user_id: i64,
+        req: MfaConfirmRequest,
+        client: &ClientInfo,
+    ) -> Result<RecoveryCodesResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let user = self
+            .user_repo
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound("User not found.".into()))?;
+
+        let mfa = match self.find_mfa(user_id).await? {
+            Some(mfa) if mfa.enabled => return Err(AppError::BadRequest("MFA is already enabled.".into())),
+            Some(mfa) => mfa,
+            None => return Err(AppError::BadRequest("MFA enrollment has not been started.".into())),
+        };
+
+        let totp = build_totp(&mfa.secret, &user.email)?;
+        let step = verify_code(&totp, &req.code, (self.clock)())
+            .ok_or_else(|| AppError::BadRequest(INVALID_MFA_CODE.into()))?;
+
+        let recovery_codes = generate_recovery_codes();
+        let hashes: Vec<String> = recovery_codes.iter().map(|c| hash_recovery_code(c)).collect();
+
+        self.mfa_repo
+            .enable(user_id, step as i64, &hashes)
+            .await
+            .map_err(|e| {
+                error!("Failed to enable MFA for user {}: {:?}", user_id, e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        self.audit(Some(user_id), AuditAction::MfaEnabled, user_id, json!({}), client).await?;
+
+        Ok(RecoveryCodesResponse { recovery_codes })
+    }
+
+    pub async fn verify(&self, req: MfaVerifyRequest, client: &ClientInfo) -> Result<JwtToken, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let challenge = validate_mfa_challenge_token(&req.challenge_token)
+            .map_err(|e| AppError::InvalidToken(e.to_string()))?;
+
+        let user = self
+            .user_repo
+            .get_active_user_by_id(challenge.subject)
+            .await
+            .map_err(|_| AppError::AuthError(INVALID_MFA_CODE.into()))?;
+
+        let mfa = self
+            .find_mfa(user.id)
+            .await?
+            .filter(|mfa| mfa.enabled)
+            .ok_or_else(|| AppError::AuthError(INVALID_MFA_CODE.into()))?;
+
+        let now = (self.clock)();
+        if is_locked(&mfa, now) {
+            return Err(AppError::AuthError(MFA_LOCKED.into()));
+        }
+
+        let (method, accepted) = match (req.code.as_deref(), req.recovery_code.as_deref()) {
+            (Some(code), _) => ("totp", self.consume_code(&user.email, &mfa, code, now).await?),
+            (None, Some(recovery_code)) => ("recoveryCode", self.consume_recovery_code(user.id, recovery_code).await?),
+            (None, None) => {
+                return Err(AppError::ValidationError(
+                    "Either code or recoveryCode must be provided".into(),
+                ));
+            }
+        };
+
+        if !accepted {
+            self.record_failed_attempt(user.id, now).await?;
+            // The second factor was not proven, so like a failed login there is no actor.
+            self.audit(None, AuditAction::MfaVerificationFailed, user.id, json!({ "method": method }), client)
+                .await?;
+            return Err(AppError::AuthError(INVALID_MFA_CODE.into()));
+        }
+
+        if req.code.is_none() {
+            self.audit(Some(user.id), AuditAction::RecoveryCodeUsed, user.id, json!({}), client).await?;
+        }
+
+        self.mfa_repo
+            .reset_failed_attempts(user.id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        self.session_service.start(&user, client, challenge.remember_me).await
+    }
+
+    pub async fn disable(&self, user_id: i64, req: MfaDisableRequest, client: &ClientInfo) -> Result<(), AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let user = self
+            .user_repo
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound("User not found.".into()))?;
+
+        let password_check = self.password_hasher.verify(&req.password, &user.password).await?;
+
+        if !password_check.is_valid {
+            return Err(AppError::AuthError("Password is incorrect.".into()));
+        }
+
+        // A pending setup that was never confirmed is not MFA being enabled.
+        if !self.find_mfa(user_id).await?.is_some_and(|mfa| mfa.enabled) {
+            return Err(AppError::BadRequest("MFA is not enabled.".into()));
+        }
+
+        self.mfa_repo
+            .delete_for_user(user_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to disable MFA for user {}: {:?}", user_id, e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        self.audit(Some(user_id), AuditAction::MfaDisabled, user_id, json!({}), client).await
+    }
+
+    /// Accepts a TOTP code at most once. The conditional update also rejects a replay that
+    /// races a concurrent verification of the same code.
+    async fn consume_code(&self, email: &str, mfa: &UserMfa, code: &str, now: u64) -> Result<bool, AppError> {
+        let totp = build_totp(&mfa.secret, email)?;
+        let Some(step) = verify_unused_code(&totp, code, now, mfa.last_used_step) else {
+            return Ok(false);
+        };
+
+        self.mfa_repo
+            .update_last_used_step(mfa.user_id, step as i64)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    async fn consume_recovery_code(&self, user_id: i64, recovery_code: &str) -> Result<bool, AppError> {
+        let codes = self
+            .mfa_repo
+            .find_unused_recovery_codes(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let Some(code) = find_recovery_code(&codes, recovery_code) else {
+            return Ok(false);
+        };
+
+        self.mfa_repo
+            .mark_recovery_code_used(code.id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    async fn record_failed_attempt(&self, user_id: i64, now: u64) -> Result<(), AppError> {
+        let locked_until = DateTime::from_timestamp((now + LOCKOUT_IN_SECONDS) as i64, 0)
+            .ok_or_else(|| AppError::InternalServerError("Invalid lockout time.".into()))?
+            .with_timezone(&Local);
+
+        self.mfa_repo
+            .record_failed_attempt(user_id, MAX_FAILED_ATTEMPTS, locked_until)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    async fn audit(
+        &self,
+        actor_id: Option<i64>,
+        action: AuditAction,
+        user_id: i64,
+        details: Value,
+        client: &ClientInfo,
+    ) -> Result<(), AppError> {
+        self.audit_service
+            .record(AuditLog::new(actor_id, action, AuditTargetType::User, Some(user_id), details).with_client(client))
+            .await
+    }
+
+    async fn find_mfa(&self, user_id: i64) -> Result<Option<UserMfa>, AppError> {
+        self.mfa_repo
+            .find_by_user_id(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+}
+
+fn is_locked(mfa: &UserMfa, now: u64) -> bool {
+    mfa.locked_until.is_some_and(|until| until.timestamp() > now as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn mfa(locked_until: Option<DateTime<Local>>) -> UserMfa {
+        let created_at = Local.timestamp_opt(NOW as i64, 0).unwrap();
+        UserMfa {
+            user_id: 1,
+            secret: String::new(),
+            enabled: true,
+            last_used_step: None,
+            failed_attempts: 0,
+            locked_until,
+            confirmed_at: Some(created_at),
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    #[test]
+    fn locked_until_the_lockout_expires() {
+        let until = Local.timestamp_opt(NOW as i64, 0).unwrap() + Duration::seconds(60);
+
+        assert!(!is_locked(&mfa(None), NOW));
+        assert!(is_locked(&mfa(Some(until)), NOW));
+        assert!(is_locked(&mfa(Some(until)), NOW + 59));
+        assert!(!is_locked(&mfa(Some(until)), NOW + 60));
+    }
+}
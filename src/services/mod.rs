@@ -2,4 +2,16 @@ pub(crate) mod user_service;
 pub(crate) mod auth_service;
 pub(crate) mod application_service;
 pub(crate) mod email_service;
-pub(crate) mod dashboard_service;
\ No newline at end of file
+pub(crate) mod dashboard_service;
+pub(crate) mod mfa_service;
+pub(crate) mod passkey_service;
+pub(crate) mod oidc_service;
+pub(crate) mod personal_access_token_service;
+pub(crate) mod admin_service;
+pub(crate) mod data_export_service;
+pub(crate) mod registration_service;
+pub(crate) mod password_policy_service;
+pub(crate) mod password_hash_service;
+pub(crate) mod session_service;
+pub(crate) mod audit_service;
+pub(crate) mod saved_view_service;
\ No newline at end of file
@@ -0,0 +1,719 @@
+use crate::configs::oidc::OidcProviderConfig;
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::enums::roles::Role;
+use crate::errors::app_error::{extract_validation_errors, AppError};
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::models::audit_log::AuditLog;
+use crate::models::identity::{OidcLoginState, UserIdentity};
+use crate::models::user::User;
+use crate::payloads::oidc::{
+    IdentityResponse, OidcAuthorizationResponse, OidcAuthorizeRequest, OidcCallbackRequest, OidcProviderResponse,
+};
+use crate::repositories::identity_repository::IdentityRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::audit_service::AuditService;
+use crate::services::password_hash_service::PasswordHashService;
+use crate::services::registration_service::RegistrationService;
+use crate::services::session_service::SessionService;
+use crate::utils::jwt::JwtToken;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Local;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+const LOGIN_STATE_TTL: Duration = Duration::from_secs(600);
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(3600);
+const SSO_FAILED: &str = "Single sign-on failed. Please try again.";
+
+#[derive(Clone, Deserialize)]
+struct OidcDiscovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    email: Option<String>,
+    email_verified: Option<Value>,
+    nonce: Option<String>,
+    given_name: Option<String>,
+    family_name: Option<String>,
+    name: Option<String>,
+}
+
+impl IdTokenClaims {
+    /// Some providers encode `email_verified` as a string rather than a boolean.
+    fn is_email_verified(&self) -> bool {
+        matches!(&self.email_verified, Some(Value::Bool(true)))
+            || matches!(&self.email_verified, Some(Value::String(s)) if s == "true")
+    }
+}
+
+pub struct OidcService {
+    user_repo: Arc<UserRepository>,
+    identity_repo: Arc<IdentityRepository>,
+    registration_service: Arc<RegistrationService>,
+    password_hasher: Arc<PasswordHashService>,
+    session_service: Arc<SessionService>,
+    audit_service: Arc<AuditService>,
+    providers: HashMap<String, OidcProviderConfig>,
+    client: OidcClient,
+}
+
+/// Talks to identity providers: discovery, the code exchange and ID token validation.
+/// Discovery documents are cached for the lifetime of the process and key sets for `jwks_ttl`.
+struct OidcClient {
+    http_client: reqwest::Client,
+    discovery_cache: RwLock<HashMap<String, OidcDiscovery>>,
+    jwks_cache: RwLock<HashMap<String, (Instant, JwkSet)>>,
+    jwks_ttl: Duration,
+}
+
+impl OidcService {
+    pub fn new(
+        user_repo: Arc<UserRepository>,
+        identity_repo: Arc<IdentityRepository>,
+        registration_service: Arc<RegistrationService>,
+        password_hasher: Arc<PasswordHashService>,
+        session_service: Arc<SessionService>,
+        audit_service: Arc<AuditService>,
+        providers: HashMap<String, OidcProviderConfig>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            user_repo,
+            identity_repo,
+            registration_service,
+            password_hasher,
+            session_service,
+            audit_service,
+            providers,
+            client: OidcClient::new(JWKS_CACHE_TTL),
+        })
+    }
+
+    pub fn list_providers(&self) -> Vec<OidcProviderResponse> {
+        let mut providers: Vec<OidcProviderResponse> = self
+            .providers
+            .values()
+            .map(|provider| OidcProviderResponse {
+                name: provider.name.clone(),
+                display_name: provider.display_name.clone(),
+            })
+            .collect();
+        providers.sort_by(|a, b| a.name.cmp(&b.name));
+        providers
+    }
+
+    pub async fn authorize(
+        &self,
+        provider_name: &str,
+        req: OidcAuthorizeRequest,
+    ) -> Result<OidcAuthorizationResponse, AppError> {
+        let provider = self.provider(provider_name)?;
+        let discovery = self.client.discover(provider).await?;
+
+        let state = random_token();
+        let nonce = random_token();
+        let code_verifier = random_token();
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        self.identity_repo
+            .save_login_state(OidcLoginState::new(
+                provider.name.clone(),
+                state.clone(),
+                nonce.clone(),
+                code_verifier,
+                req.remember_me,
+                LOGIN_STATE_TTL,
+            ))
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let authorization_url = reqwest::Url::parse_with_params(
+            &discovery.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", provider.client_id.as_str()),
+                ("redirect_uri", provider.redirect_uri.as_str()),
+                ("scope", provider.scopes.as_str()),
+                ("state", state.as_str()),
+                ("nonce", nonce.as_str()),
+                ("code_challenge", code_challenge.as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| AppError::InternalServerError(format!("Invalid authorization endpoint: {}", e)))?;
+
+        Ok(OidcAuthorizationResponse {
+            authorization_url: authorization_url.to_string(),
+            state,
+        })
+    }
+
+    pub async fn callback(&self, provider_name: &str, req: OidcCallbackRequest, client: &ClientInfo) -> Result<JwtToken, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let provider = self.provider(provider_name)?;
+
+        let login_state = self
+            .identity_repo
+            .consume_login_state(&provider.name, &req.state)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .filter(|state| state.expires_at > Local::now())
+            .ok_or_else(|| AppError::BadRequest("Invalid or expired login state.".into()))?;
+
+        let discovery = self.client.discover(provider).await?;
+        let id_token = self
+            .client
+            .exchange_code(provider, &discovery, &req.code, &login_state.code_verifier)
+            .await?;
+        let claims = self.client.validate_id_token(provider, &discovery, &id_token).await?;
+
+        if claims.nonce.as_deref() != Some(login_state.nonce.as_str()) {
+            return Err(AppError::AuthError(SSO_FAILED.into()));
+        }
+
+        let email = match &claims.email {
+            Some(email) if claims.is_email_verified() => email.to_lowercase(),
+            _ => {
+                return Err(AppError::AuthError(
+                    "Your identity provider did not return a verified email address.".into(),
+                ));
+            }
+        };
+
+        let user = self.link_or_create_user(provider, &claims, &email, client).await?;
+        info!("User {} signed in through OIDC provider '{}'", user.id, provider.name);
+
+        self.session_service.start(&user, client, login_state.remember_me).await
+    }
+
+    async fn link_or_create_user(
+        &self,
+        provider: &OidcProviderConfig,
+        claims: &IdTokenClaims,
+        email: &str,
+        client: &ClientInfo,
+    ) -> Result<User, AppError> {
+        let identity = self
+            .identity_repo
+            .find_by_provider_subject(&provider.name, &claims.sub)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if let Some(identity) = identity {
+            self.identity_repo
+                .touch_last_login(identity.id, email)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+            return self
+                .user_repo
+                .get_active_user_by_id(identity.user_id)
+                .await
+                .map_err(|_| AppError::AuthError(SSO_FAILED.into()));
+        }
+
+        let user = match self.user_repo.get_user_by_email(email.to_string()).await {
+            Ok(user) => user,
+            Err(sqlx::Error::RowNotFound) => self.create_user(claims, email).await?,
+            Err(e) => return Err(AppError::DatabaseError(e.to_string())),
+        };
+
+        let identity = self
+            .identity_repo
+            .save(UserIdentity::new(
+                user.id,
+                provider.name.clone(),
+                claims.sub.clone(),
+                email.to_string(),
+            ))
+            .await
+            .map_err(|e| {
+                error!("Failed to link OIDC identity for user {}: {:?}", user.id, e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        self.audit(user.id, AuditAction::IdentityLinked, &identity, client).await?;
+
+        Ok(user)
+    }
+
+    pub async fn list_identities(&self, user_id: i64) -> Result<Vec<IdentityResponse>, AppError> {
+        self.identity_repo
+            .find_all_by_user_id(user_id)
+            .await
+            .map(|identities| identities.iter().map(IdentityResponse::from_identity).collect())
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    /// Unlinks a provider account, after which signing in with it no longer reaches this user.
+    /// Signing in with the same email links it again, as on the first sign-in.
+    pub async fn unlink_identity(&self, user_id: i64, identity_id: i64, client: &ClientInfo) -> Result<(), AppError> {
+        let identity = self
+            .identity_repo
+            .delete(identity_id, user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::ResourceNotFound("Identity not found.".into()))?;
+
+        info!("User {} unlinked OIDC identity {}", user_id, identity.id);
+        self.audit(user_id, AuditAction::IdentityUnlinked, &identity, client).await
+    }
+
+    async fn audit(&self, user_id: i64, action: AuditAction, identity: &UserIdentity, client: &ClientInfo) -> Result<(), AppError> {
+        let details = json!({ "provider": identity.provider, "email": identity.email });
+
+        self.audit_service
+            .record(AuditLog::new(Some(user_id), action, AuditTargetType::Identity, Some(identity.id), details).with_client(client))
+            .await
+    }
+
+    async fn create_user(&self, claims: &IdTokenClaims, email: &str) -> Result<User, AppError> {
+        let invite = self.registration_service.authorize_verified_email(email).await?;
+
+        let (first_name, last_name) = match (&claims.given_name, &claims.family_name, &claims.name) {
+            (Some(given), Some(family), _) => (given.clone(), family.clone()),
+            (_, _, Some(name)) => match name.split_once(' ') {
+                Some((first, last)) => (first.to_string(), last.to_string()),
+                None => (name.clone(), String::new()),
+            },
+            _ => (email.split('@').next().unwrap_or(email).to_string(), String::new()),
+        };
+
+        // SSO users get an unusable random password; they can set one through the reset flow.
+        let password_hash = self.password_hasher.hash(&Uuid::new_v4().to_string()).await?;
+
+        let role = invite
+            .as_ref()
+            .and_then(|invite| invite.role.clone())
+            .unwrap_or(Role::User);
+        let mut user = User::new(first_name, last_name, email.to_string(), None, password_hash, Some(role));
+        user.is_verified = true;
+
+        let user = self
+            .user_repo
+            .save(user)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if let Some(invite) = invite {
+            self.registration_service.accept(&invite, user.id).await;
+        }
+
+        Ok(user)
+    }
+
+    fn provider(&self, name: &str) -> Result<&OidcProviderConfig, AppError> {
+        self.providers
+            .get(&name.to_lowercase())
+            .ok_or_else(|| AppError::ResourceNotFound("Identity provider not found.".into()))
+    }
+}
+
+impl OidcClient {
+    fn new(jwks_ttl: Duration) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            http_client,
+            discovery_cache: RwLock::new(HashMap::new()),
+            jwks_cache: RwLock::new(HashMap::new()),
+            jwks_ttl,
+        }
+    }
+
+    async fn exchange_code(
+        &self,
+        provider: &OidcProviderConfig,
+        discovery: &OidcDiscovery,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<String, AppError> {
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", provider.redirect_uri.as_str()),
+            ("client_id", provider.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &provider.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+
+        let response = self
+            .http_client
+            .post(&discovery.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("OIDC token request to '{}' failed: {}", provider.name, e);
+                AppError::AuthError(SSO_FAILED.into())
+            })?;
+
+        if !response.status().is_success() {
+            error!("OIDC token endpoint of '{}' returned {}", provider.name, response.status());
+            return Err(AppError::AuthError(SSO_FAILED.into()));
+        }
+
+        response
+            .json::<TokenResponse>()
+            .await
+            .map(|token| token.id_token)
+            .map_err(|e| {
+                error!("Invalid OIDC token response from '{}': {}", provider.name, e);
+                AppError::AuthError(SSO_FAILED.into())
+            })
+    }
+
+    async fn validate_id_token(
+        &self,
+        provider: &OidcProviderConfig,
+        discovery: &OidcDiscovery,
+        id_token: &str,
+    ) -> Result<IdTokenClaims, AppError> {
+        let header = decode_header(id_token).map_err(|_| AppError::AuthError(SSO_FAILED.into()))?;
+
+        // Only accept asymmetric signatures so a token can never be verified with a shared secret.
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(AppError::AuthError(SSO_FAILED.into()));
+        }
+
+        let mut jwks = self.jwks(discovery, false).await?;
+
+        // An unknown key ID usually means the provider rotated its keys since they were cached.
+        if header.kid.as_ref().is_some_and(|kid| jwks.find(kid).is_none()) {
+            jwks = self.jwks(discovery, true).await?;
+        }
+
+        let jwk = match &header.kid {
+            Some(kid) => jwks.find(kid),
+            None => jwks.keys.first(),
+        }
+        .ok_or_else(|| AppError::AuthError(SSO_FAILED.into()))?;
+
+        let decoding_key = DecodingKey::from_jwk(jwk).map_err(|_| AppError::AuthError(SSO_FAILED.into()))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&discovery.issuer]);
+        validation.set_audience(&[&provider.client_id]);
+
+        decode::<IdTokenClaims>(id_token, &decoding_key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| {
+                error!("Invalid ID token from '{}': {}", provider.name, e);
+                AppError::AuthError(SSO_FAILED.into())
+            })
+    }
+
+    async fn discover(&self, provider: &OidcProviderConfig) -> Result<OidcDiscovery, AppError> {
+        if let Some(discovery) = self.discovery_cache.read().await.get(&provider.name) {
+            return Ok(discovery.clone());
+        }
+
+        let discovery: OidcDiscovery = self
+            .fetch_json(&format!("{}/.well-known/openid-configuration", provider.issuer))
+            .await?;
+
+        if discovery.issuer.trim_end_matches('/') != provider.issuer {
+            error!("OIDC provider '{}' reported unexpected issuer {}", provider.name, discovery.issuer);
+            return Err(AppError::InternalServerError("OIDC issuer mismatch.".into()));
+        }
+
+        self.discovery_cache
+            .write()
+            .await
+            .insert(provider.name.clone(), discovery.clone());
+
+        Ok(discovery)
+    }
+
+    async fn jwks(&self, discovery: &OidcDiscovery, refresh: bool) -> Result<JwkSet, AppError> {
+        if !refresh
+            && let Some((fetched_at, jwks)) = self.jwks_cache.read().await.get(&discovery.jwks_uri)
+            && fetched_at.elapsed() < self.jwks_ttl
+        {
+            return Ok(jwks.clone());
+        }
+
+        let jwks: JwkSet = self.fetch_json(&discovery.jwks_uri).await?;
+        self.jwks_cache
+            .write()
+            .await
+            .insert(discovery.jwks_uri.clone(), (Instant::now(), jwks.clone()));
+
+        Ok(jwks)
+    }
+
+    async fn fetch_json<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<T, AppError> {
+        self.http_client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| {
+                error!("Failed to fetch {}: {}", url, e);
+                AppError::InternalServerError("Identity provider is unavailable.".into())
+            })?
+            .json::<T>()
+            .await
+            .map_err(|e| {
+                error!("Invalid response from {}: {}", url, e);
+                AppError::InternalServerError("Identity provider returned an invalid response.".into())
+            })
+    }
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::routing::{get, post};
+    use axum::{Form, Json, Router};
+    use chrono::Utc;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use p256::ecdsa::SigningKey;
+    use p256::pkcs8::EncodePrivateKey;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    const CLIENT_ID: &str = "appliq";
+    const NONCE: &str = "nonce-123";
+    const CODE: &str = "authorization-code";
+
+    struct SigningJwk {
+        kid: String,
+        key: SigningKey,
+    }
+
+    impl SigningJwk {
+        fn new(kid: &str) -> Self {
+            let mut secret = [0u8; 32];
+            rand::rng().fill_bytes(&mut secret);
+            Self { kid: kid.to_string(), key: SigningKey::from_slice(&secret).unwrap() }
+        }
+
+        fn public_jwk(&self) -> Value {
+            let point = self.key.verifying_key().to_encoded_point(false);
+            json!({
+                "kty": "EC",
+                "crv": "P-256",
+                "use": "sig",
+                "alg": "ES256",
+                "kid": self.kid,
+                "x": URL_SAFE_NO_PAD.encode(point.x().unwrap()),
+                "y": URL_SAFE_NO_PAD.encode(point.y().unwrap()),
+            })
+        }
+
+        fn sign(&self, claims: &Value) -> String {
+            let mut header = Header::new(Algorithm::ES256);
+            header.kid = Some(self.kid.clone());
+            let der = self.key.to_pkcs8_der().unwrap();
+            encode(&header, claims, &EncodingKey::from_ec_der(der.as_bytes())).unwrap()
+        }
+    }
+
+    /// A minimal identity provider serving discovery, a key set and a token endpoint.
+    struct MockIdp {
+        issuer: String,
+        keys: Mutex<Vec<Value>>,
+        id_token: Mutex<String>,
+        jwks_requests: AtomicUsize,
+    }
+
+    impl MockIdp {
+        async fn start() -> Arc<Self> {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let idp = Arc::new(Self {
+                issuer: format!("http://{}", listener.local_addr().unwrap()),
+                keys: Mutex::new(Vec::new()),
+                id_token: Mutex::new(String::new()),
+                jwks_requests: AtomicUsize::new(0),
+            });
+
+            let router = Router::new()
+                .route("/.well-known/openid-configuration", get(Self::discovery))
+                .route("/jwks", get(Self::jwks))
+                .route("/token", post(Self::token))
+                .with_state(idp.clone());
+            tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+            idp
+        }
+
+        async fn discovery(State(idp): State<Arc<Self>>) -> Json<Value> {
+            Json(json!({
+                "issuer": idp.issuer,
+                "authorization_endpoint": format!("{}/authorize", idp.issuer),
+                "token_endpoint": format!("{}/token", idp.issuer),
+                "jwks_uri": format!("{}/jwks", idp.issuer),
+            }))
+        }
+
+        async fn jwks(State(idp): State<Arc<Self>>) -> Json<Value> {
+            idp.jwks_requests.fetch_add(1, Ordering::SeqCst);
+            Json(json!({ "keys": *idp.keys.lock().unwrap() }))
+        }
+
+        async fn token(
+            State(idp): State<Arc<Self>>,
+            Form(form): Form<HashMap<String, String>>,
+        ) -> Result<Json<Value>, axum::http::StatusCode> {
+            if form.get("code").map(String::as_str) != Some(CODE) || !form.contains_key("code_verifier") {
+                return Err(axum::http::StatusCode::BAD_REQUEST);
+            }
+
+            Ok(Json(json!({ "id_token": *idp.id_token.lock().unwrap() })))
+        }
+
+        fn publish(&self, keys: &[&SigningJwk]) {
+            *self.keys.lock().unwrap() = keys.iter().map(|key| key.public_jwk()).collect();
+        }
+
+        fn provider(&self) -> OidcProviderConfig {
+            OidcProviderConfig {
+                name: "mock".to_string(),
+                display_name: "Mock".to_string(),
+                issuer: self.issuer.clone(),
+                client_id: CLIENT_ID.to_string(),
+                client_secret: None,
+                redirect_uri: "http://localhost/callback".to_string(),
+                scopes: "openid email".to_string(),
+            }
+        }
+
+        fn claims(&self, audience: &str) -> Value {
+            json!({
+                "iss": self.issuer,
+                "aud": audience,
+                "sub": "subject-1",
+                "email": "sso@example.com",
+                "email_verified": "true",
+                "nonce": NONCE,
+                "iat": Utc::now().timestamp(),
+                "exp": Utc::now().timestamp() + 300,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_in_with_a_code_from_the_provider() {
+        let idp = MockIdp::start().await;
+        let key = SigningJwk::new("k1");
+        idp.publish(&[&key]);
+        *idp.id_token.lock().unwrap() = key.sign(&idp.claims(CLIENT_ID));
+
+        let client = OidcClient::new(JWKS_CACHE_TTL);
+        let provider = idp.provider();
+        let discovery = client.discover(&provider).await.unwrap();
+
+        assert!(client.exchange_code(&provider, &discovery, "wrong-code", "verifier").await.is_err());
+
+        let id_token = client.exchange_code(&provider, &discovery, CODE, "verifier").await.unwrap();
+        let claims = client.validate_id_token(&provider, &discovery, &id_token).await.unwrap();
+
+        assert_eq!(claims.sub, "subject-1");
+        assert_eq!(claims.email.as_deref(), Some("sso@example.com"));
+        assert_eq!(claims.nonce.as_deref(), Some(NONCE));
+        assert!(claims.is_email_verified());
+    }
+
+    #[tokio::test]
+    async fn caches_the_key_set_until_the_ttl_expires() {
+        let idp = MockIdp::start().await;
+        let key = SigningJwk::new("k1");
+        idp.publish(&[&key]);
+        let id_token = key.sign(&idp.claims(CLIENT_ID));
+        let provider = idp.provider();
+
+        let client = OidcClient::new(JWKS_CACHE_TTL);
+        let discovery = client.discover(&provider).await.unwrap();
+        for _ in 0..3 {
+            client.validate_id_token(&provider, &discovery, &id_token).await.unwrap();
+        }
+        assert_eq!(idp.jwks_requests.load(Ordering::SeqCst), 1);
+
+        let expiring_client = OidcClient::new(Duration::ZERO);
+        for _ in 0..2 {
+            expiring_client.validate_id_token(&provider, &discovery, &id_token).await.unwrap();
+        }
+        assert_eq!(idp.jwks_requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn refreshes_the_key_set_when_the_provider_rotates_keys() {
+        let idp = MockIdp::start().await;
+        let old_key = SigningJwk::new("k1");
+        let new_key = SigningJwk::new("k2");
+        idp.publish(&[&old_key]);
+        let provider = idp.provider();
+
+        let client = OidcClient::new(JWKS_CACHE_TTL);
+        let discovery = client.discover(&provider).await.unwrap();
+        client
+            .validate_id_token(&provider, &discovery, &old_key.sign(&idp.claims(CLIENT_ID)))
+            .await
+            .unwrap();
+
+        idp.publish(&[&new_key]);
+        client
+            .validate_id_token(&provider, &discovery, &new_key.sign(&idp.claims(CLIENT_ID)))
+            .await
+            .unwrap();
+
+        assert_eq!(idp.jwks_requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_tokens_for_another_client_or_signed_by_an_unpublished_key() {
+        let idp = MockIdp::start().await;
+        let key = SigningJwk::new("k1");
+        let impostor = SigningJwk::new("k1");
+        idp.publish(&[&key]);
+        let provider = idp.provider();
+
+        let client = OidcClient::new(JWKS_CACHE_TTL);
+        let discovery = client.discover(&provider).await.unwrap();
+
+        let other_audience = key.sign(&idp.claims("another-client"));
+        let forged = impostor.sign(&idp.claims(CLIENT_ID));
+
+        assert!(client.validate_id_token(&provider, &discovery, &other_audience).await.is_err());
+        assert!(client.validate_id_token(&provider, &discovery, &forged).await.is_err());
+    }
+}
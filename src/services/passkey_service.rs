@@ -0,0 +1,638 @@
+use crate::configs::webauthn::WebauthnConfig;
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::enums::passkey::Ceremony;
+use crate::errors::app_error::{extract_validation_errors, AppError};
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::models::audit_log::AuditLog;
+use crate::models::passkey::{PasskeyCredential, WebauthnChallenge};
+use crate::payloads::passkey::{
+    AuthenticatorSelection, PasskeyLoginOptions, PasskeyLoginOptionsRequest, PasskeyLoginRequest,
+    PasskeyRegistrationOptions, PasskeyRegistrationRequest, PasskeyResponse, PasskeyUserEntity,
+    PublicKeyCredentialDescriptor, PublicKeyCredentialParameters, RelyingParty,
+    RenamePasskeyRequest,
+};
+use crate::repositories::passkey_repository::PasskeyRepository;
+use crate::repositories::user_repository::UserRepository;
+use crate::services::audit_service::AuditService;
+use crate::services::session_service::SessionService;
+use crate::utils::jwt::JwtToken;
+use crate::utils::webauthn_util::{
+    decode_base64url, encode_base64url, generate_challenge, parse_attestation_object,
+    parse_authenticator_data, parse_client_data, verify_signature, CLIENT_DATA_TYPE_CREATE,
+    CLIENT_DATA_TYPE_GET, COSE_ALG_ES256,
+};
+use chrono::Local;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::error;
+use validator::Validate;
+
+const PUBLIC_KEY_CREDENTIAL_TYPE: &str = "public-key";
+const CEREMONY_TIMEOUT: Duration = Duration::from_secs(300);
+const INVALID_PASSKEY: &str = "Passkey authentication failed.";
+
+pub struct PasskeyService {
+    user_repo: Arc<UserRepository>,
+    passkey_repo: Arc<PasskeyRepository>,
+    session_service: Arc<SessionService>,
+    audit_service: Arc<AuditService>,
+    config: WebauthnConfig,
+}
+
+impl PasskeyService {
+    pub fn new(
+        user_repo: Arc<UserRepository>,
+        passkey_repo: Arc<PasskeyRepository>,
+        session_service: Arc<SessionService>,
+        audit_service: Arc<AuditService>,
+        config: WebauthnConfig,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            user_repo,
+            passkey_repo,
+            session_service,
+            audit_service,
+            config,
+        })
+    }
+
+    pub async fn registration_options(&self, user_id: i64) -> Result<PasskeyRegistrationOptions, AppError> {
+        let user = self
+            .user_repo
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound("User not found.".into()))?;
+
+        let exclude_credentials = self
+            .find_credentials(user_id)
+            .await?
+            .into_iter()
+            .map(|credential| Self::descriptor(credential.credential_id))
+            .collect();
+
+        let challenge = self.issue_challenge(Some(user_id), Ceremony::Registration).await?;
+
+        Ok(PasskeyRegistrationOptions {
+            challenge,
+            rp: RelyingParty {
+                id: self.config.rp_id.clone(),
+                name: self.config.rp_name.clone(),
+            },
+            user: PasskeyUserEntity {
+                id: Self::user_handle(user.id),
+                name: user.email,
+                display_name: format!("{} {}", user.first_name, user.last_name),
+            },
+            pub_key_cred_params: vec![PublicKeyCredentialParameters {
+                type_: PUBLIC_KEY_CREDENTIAL_TYPE.to_string(),
+                alg: COSE_ALG_ES256,
+            }],
+            timeout: CEREMONY_TIMEOUT.as_millis() as u64,
+            attestation: "none".to_string(),
+            exclude_credentials,
+            authenticator_selection: AuthenticatorSelection {
+                resident_key: "preferred".to_string(),
+                user_verification: "required".to_string(),
+            },
+        })
+    }
+
+    pub async fn register(
+        &self,
+        user_id: i64,
+        req: PasskeyRegistrationRequest,
+        client: &ClientInfo,
+    ) -> Result<PasskeyResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let credential = Self::verify_registration(&self.config, user_id, &req, async |challenge| {
+            self.passkey_repo
+                .consume_challenge(challenge)
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))
+        })
+        .await?;
+
+        let already_registered = self
+            .passkey_repo
+            .find_by_credential_id(&credential.credential_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .is_some();
+
+        if already_registered {
+            return Err(AppError::ResourceExists("Passkey is already registered.".into()));
+        }
+
+        let credential = self.passkey_repo.save(credential).await.map_err(|e| {
+            error!("Failed to save passkey for user {}: {:?}", user_id, e);
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        self.audit(user_id, AuditAction::PasskeyRegistered, credential.id, json!({ "name": credential.name }), client)
+            .await?;
+
+        Ok(PasskeyResponse::from_credential(&credential))
+    }
+
+    pub async fn login_options(&self, req: PasskeyLoginOptionsRequest) -> Result<PasskeyLoginOptions, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        // Without an email the browser offers any discoverable passkey for this relying party.
+        let user = match req.email {
+            Some(email) => self.user_repo.get_user_by_email(email).await.ok(),
+            None => None,
+        };
+
+        let allow_credentials = match &user {
+            Some(user) => self
+                .find_credentials(user.id)
+                .await?
+                .into_iter()
+                .map(|credential| Self::descriptor(credential.credential_id))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let challenge = self
+            .issue_challenge(user.map(|user| user.id), Ceremony::Authentication)
+            .await?;
+
+        Ok(PasskeyLoginOptions {
+            challenge,
+            rp_id: self.config.rp_id.clone(),
+            timeout: CEREMONY_TIMEOUT.as_millis() as u64,
+            user_verification: "required".to_string(),
+            allow_credentials,
+        })
+    }
+
+    pub async fn login(&self, req: PasskeyLoginRequest, client: &ClientInfo) -> Result<JwtToken, AppError> {
+        let (credential, sign_count) = Self::verify_assertion(
+            &self.config,
+            &req,
+            async |challenge| {
+                self.passkey_repo
+                    .consume_challenge(challenge)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))
+            },
+            async |credential_id| {
+                self.passkey_repo
+                    .find_by_credential_id(credential_id)
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))
+            },
+        )
+        .await?;
+
+        let updated = self
+            .passkey_repo
+            .update_sign_count(credential.id, credential.sign_count, sign_count)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !updated {
+            return Err(AppError::AuthError(INVALID_PASSKEY.into()));
+        }
+
+        let user = self
+            .user_repo
+            .get_active_user_by_id(credential.user_id)
+            .await
+            .map_err(|_| AppError::AuthError(INVALID_PASSKEY.into()))?;
+
+        self.session_service.start(&user, client, req.remember_me).await
+    }
+
+    pub async fn list_passkeys(&self, user_id: i64) -> Result<Vec<PasskeyResponse>, AppError> {
+        Ok(self
+            .find_credentials(user_id)
+            .await?
+            .iter()
+            .map(PasskeyResponse::from_credential)
+            .collect())
+    }
+
+    pub async fn rename_passkey(
+        &self,
+        user_id: i64,
+        passkey_id: i64,
+        req: RenamePasskeyRequest,
+    ) -> Result<PasskeyResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        self.passkey_repo
+            .rename(passkey_id, user_id, &req.name)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .map(|credential| PasskeyResponse::from_credential(&credential))
+            .ok_or_else(|| AppError::ResourceNotFound("Passkey not found.".into()))
+    }
+
+    pub async fn revoke_passkey(&self, user_id: i64, passkey_id: i64, client: &ClientInfo) -> Result<(), AppError> {
+        let deleted = self
+            .passkey_repo
+            .delete(passkey_id, user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !deleted {
+            return Err(AppError::ResourceNotFound("Passkey not found.".into()));
+        }
+
+        self.audit(user_id, AuditAction::PasskeyRevoked, passkey_id, json!({}), client).await
+    }
+
+    async fn audit(
+        &self,
+        user_id: i64,
+        action: AuditAction,
+        passkey_id: i64,
+        details: Value,
+        client: &ClientInfo,
+    ) -> Result<(), AppError> {
+        self.audit_service
+            .record(AuditLog::new(Some(user_id), action, AuditTargetType::Passkey, Some(passkey_id), details).with_client(client))
+            .await
+    }
+
+    async fn issue_challenge(&self, user_id: Option<i64>, ceremony: Ceremony) -> Result<String, AppError> {
+        self.passkey_repo
+            .save_challenge(WebauthnChallenge::new(
+                user_id,
+                generate_challenge(),
+                ceremony,
+                CEREMONY_TIMEOUT,
+            ))
+            .await
+            .map(|challenge| challenge.challenge)
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    /// Checks an attestation against the challenge it answers and returns the credential to store.
+    /// `consume_challenge` marks the challenge named in the client data as used and returns it,
+    /// or `None` if it is unknown or was used before.
+    async fn verify_registration(
+        config: &WebauthnConfig,
+        user_id: i64,
+        req: &PasskeyRegistrationRequest,
+        consume_challenge: impl AsyncFnOnce(&str) -> Result<Option<WebauthnChallenge>, AppError>,
+    ) -> Result<PasskeyCredential, AppError> {
+        if req.credential.type_ != PUBLIC_KEY_CREDENTIAL_TYPE {
+            return Err(AppError::BadRequest("Unsupported credential type.".into()));
+        }
+
+        let client_data_json = decode_base64url(&req.credential.response.client_data_json)?;
+        let client_data = parse_client_data(&client_data_json, CLIENT_DATA_TYPE_CREATE, &config.origin)?;
+
+        let challenge = Self::check_challenge(consume_challenge(&client_data.challenge).await?, Ceremony::Registration)?;
+        if challenge.user_id != Some(user_id) {
+            return Err(AppError::BadRequest("Challenge was not issued for this user.".into()));
+        }
+
+        let authenticator_data =
+            parse_attestation_object(&decode_base64url(&req.credential.response.attestation_object)?)?;
+
+        if !authenticator_data.matches_rp_id(&config.rp_id) {
+            return Err(AppError::BadRequest("Relying party ID mismatch.".into()));
+        }
+
+        if !authenticator_data.user_present() || !authenticator_data.user_verified() {
+            return Err(AppError::BadRequest("User verification is required.".into()));
+        }
+
+        let attested = authenticator_data
+            .attested_credential
+            .ok_or_else(|| AppError::BadRequest("Attestation is missing credential data.".into()))?;
+
+        if attested.credential_id != Self::raw_credential_id(&req.credential.id, &req.credential.raw_id)? {
+            return Err(AppError::BadRequest("Credential ID mismatch.".into()));
+        }
+
+        Ok(PasskeyCredential::new(
+            user_id,
+            encode_base64url(&attested.credential_id),
+            attested.public_key,
+            authenticator_data.sign_count as i64,
+            req.name.clone(),
+            Some(attested.aaguid),
+        ))
+    }
+
+    /// Checks an assertion and returns the credential it was made with along with its new
+    /// signature counter. `find_credential` looks a credential up by its base64url ID.
+    async fn verify_assertion(
+        config: &WebauthnConfig,
+        req: &PasskeyLoginRequest,
+        consume_challenge: impl AsyncFnOnce(&str) -> Result<Option<WebauthnChallenge>, AppError>,
+        find_credential: impl AsyncFnOnce(&str) -> Result<Option<PasskeyCredential>, AppError>,
+    ) -> Result<(PasskeyCredential, i64), AppError> {
+        if req.credential.type_ != PUBLIC_KEY_CREDENTIAL_TYPE {
+            return Err(AppError::BadRequest("Unsupported credential type.".into()));
+        }
+
+        let response = &req.credential.response;
+        let client_data_json = decode_base64url(&response.client_data_json)?;
+        let client_data = parse_client_data(&client_data_json, CLIENT_DATA_TYPE_GET, &config.origin)?;
+
+        let challenge = Self::check_challenge(consume_challenge(&client_data.challenge).await?, Ceremony::Authentication)?;
+
+        let credential_id =
+            encode_base64url(&Self::raw_credential_id(&req.credential.id, &req.credential.raw_id)?);
+        let credential = find_credential(&credential_id)
+            .await?
+            .ok_or_else(|| AppError::AuthError(INVALID_PASSKEY.into()))?;
+
+        if challenge.user_id.is_some_and(|user_id| user_id != credential.user_id) {
+            return Err(AppError::AuthError(INVALID_PASSKEY.into()));
+        }
+
+        if let Some(user_handle) = &response.user_handle
+            && decode_base64url(user_handle)? != credential.user_id.to_string().as_bytes()
+        {
+            return Err(AppError::AuthError(INVALID_PASSKEY.into()));
+        }
+
+        let authenticator_data_bytes = decode_base64url(&response.authenticator_data)?;
+        let authenticator_data = parse_authenticator_data(&authenticator_data_bytes)?;
+
+        if !authenticator_data.matches_rp_id(&config.rp_id)
+            || !authenticator_data.user_present()
+            || !authenticator_data.user_verified()
+        {
+            return Err(AppError::AuthError(INVALID_PASSKEY.into()));
+        }
+
+        verify_signature(
+            &credential.public_key,
+            &authenticator_data_bytes,
+            &client_data_json,
+            &decode_base64url(&response.signature)?,
+        )?;
+
+        // Authenticators that do not implement counters always report zero.
+        let sign_count = authenticator_data.sign_count as i64;
+        if (sign_count > 0 || credential.sign_count > 0) && sign_count <= credential.sign_count {
+            error!("Passkey {} reported a non-increasing signature counter", credential.id);
+            return Err(AppError::AuthError(INVALID_PASSKEY.into()));
+        }
+
+        Ok((credential, sign_count))
+    }
+
+    fn check_challenge(challenge: Option<WebauthnChallenge>, ceremony: Ceremony) -> Result<WebauthnChallenge, AppError> {
+        let invalid = || AppError::BadRequest("Invalid or expired challenge.".into());
+        let challenge = challenge.ok_or_else(invalid)?;
+
+        if challenge.ceremony != ceremony || challenge.expires_at < Local::now() {
+            return Err(invalid());
+        }
+
+        Ok(challenge)
+    }
+
+    async fn find_credentials(&self, user_id: i64) -> Result<Vec<PasskeyCredential>, AppError> {
+        self.passkey_repo
+            .find_all_by_user_id(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    fn descriptor(credential_id: String) -> PublicKeyCredentialDescriptor {
+        PublicKeyCredentialDescriptor {
+            type_: PUBLIC_KEY_CREDENTIAL_TYPE.to_string(),
+            id: credential_id,
+        }
+    }
+
+    /// Decodes `rawId`, rejecting credentials whose `id` does not encode the same bytes.
+    fn raw_credential_id(id: &str, raw_id: &str) -> Result<Vec<u8>, AppError> {
+        let raw_id = decode_base64url(raw_id)?;
+        if decode_base64url(id)? != raw_id {
+            return Err(AppError::BadRequest("Credential ID mismatch.".into()));
+        }
+
+        Ok(raw_id)
+    }
+
+    fn user_handle(user_id: i64) -> String {
+        encode_base64url(user_id.to_string().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::roles::Role;
+    use crate::utils::jwt::{create_jwt, validate_jwt};
+    use crate::utils::webauthn_util::software_authenticator::{Authenticator, CREDENTIAL_ID, ORIGIN, RP_ID};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    const USER_ID: i64 = 7;
+
+    fn config() -> WebauthnConfig {
+        WebauthnConfig {
+            rp_id: RP_ID.to_string(),
+            rp_name: "AppliQ".to_string(),
+            origin: ORIGIN.to_string(),
+        }
+    }
+
+    /// Stands in for the `webauthn_challenges` table.
+    #[derive(Default)]
+    struct Challenges(Mutex<HashMap<String, WebauthnChallenge>>);
+
+    impl Challenges {
+        fn issue(&self, user_id: Option<i64>, ceremony: Ceremony) -> String {
+            self.insert(WebauthnChallenge::new(user_id, generate_challenge(), ceremony, CEREMONY_TIMEOUT))
+        }
+
+        fn insert(&self, challenge: WebauthnChallenge) -> String {
+            let value = challenge.challenge.clone();
+            self.0.lock().unwrap().insert(value.clone(), challenge);
+            value
+        }
+
+        /// Hands out each challenge once, like `PasskeyRepository::consume_challenge`.
+        fn consume(&self, challenge: &str) -> Result<Option<WebauthnChallenge>, AppError> {
+            let mut challenges = self.0.lock().unwrap();
+            Ok(challenges.get_mut(challenge).filter(|c| !c.used).map(|c| {
+                c.used = true;
+                c.clone()
+            }))
+        }
+    }
+
+    fn registration(authenticator: &Authenticator, challenge: &str) -> PasskeyRegistrationRequest {
+        serde_json::from_value(json!({
+            "name": "Laptop",
+            "credential": {
+                "id": encode_base64url(CREDENTIAL_ID),
+                "rawId": encode_base64url(CREDENTIAL_ID),
+                "type": PUBLIC_KEY_CREDENTIAL_TYPE,
+                "response": {
+                    "clientDataJSON": encode_base64url(&authenticator.create(challenge)),
+                    "attestationObject": encode_base64url(&authenticator.attestation_object(COSE_ALG_ES256)),
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn assertion(authenticator: &Authenticator, challenge: &str, sign_count: u32) -> PasskeyLoginRequest {
+        let (authenticator_data, client_data_json, signature) = authenticator.assert(challenge, sign_count);
+        serde_json::from_value(json!({
+            "credential": {
+                "id": encode_base64url(CREDENTIAL_ID),
+                "rawId": encode_base64url(CREDENTIAL_ID),
+                "type": PUBLIC_KEY_CREDENTIAL_TYPE,
+                "response": {
+                    "clientDataJSON": encode_base64url(&client_data_json),
+                    "authenticatorData": encode_base64url(&authenticator_data),
+                    "signature": encode_base64url(&signature),
+                    "userHandle": PasskeyService::user_handle(USER_ID),
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    async fn register(challenges: &Challenges, authenticator: &Authenticator, challenge: &str) -> Result<PasskeyCredential, AppError> {
+        PasskeyService::verify_registration(&config(), USER_ID, &registration(authenticator, challenge), async |c| {
+            challenges.consume(c)
+        })
+        .await
+    }
+
+    async fn login(
+        challenges: &Challenges,
+        stored: &PasskeyCredential,
+        request: &PasskeyLoginRequest,
+    ) -> Result<(PasskeyCredential, i64), AppError> {
+        PasskeyService::verify_assertion(
+            &config(),
+            request,
+            async |c| challenges.consume(c),
+            async |credential_id| Ok(Some(stored.clone()).filter(|stored| stored.credential_id == credential_id)),
+        )
+        .await
+    }
+
+    fn invalid<T>(result: Result<T, AppError>) -> bool {
+        matches!(result, Err(AppError::BadRequest(_)))
+    }
+
+    /// Registers the authenticator's credential and returns it as the repository would store it.
+    async fn registered(challenges: &Challenges, authenticator: &Authenticator) -> PasskeyCredential {
+        let challenge = challenges.issue(Some(USER_ID), Ceremony::Registration);
+        PasskeyCredential { id: 1, ..register(challenges, authenticator, &challenge).await.unwrap() }
+    }
+
+    #[tokio::test]
+    async fn registers_a_passkey_and_signs_in_with_it() {
+        let challenges = Challenges::default();
+        let authenticator = Authenticator::new();
+
+        let challenge = challenges.issue(Some(USER_ID), Ceremony::Registration);
+        let credential = register(&challenges, &authenticator, &challenge).await.unwrap();
+
+        assert_eq!(credential.user_id, USER_ID);
+        assert_eq!(credential.credential_id, encode_base64url(CREDENTIAL_ID));
+        assert_eq!(credential.public_key, authenticator.public_key());
+        assert_eq!(credential.sign_count, 0);
+        assert_eq!(credential.name, "Laptop");
+
+        let stored = PasskeyCredential { id: 1, ..credential };
+        let challenge = challenges.issue(None, Ceremony::Authentication);
+        let (signed_in, sign_count) = login(&challenges, &stored, &assertion(&authenticator, &challenge, 1))
+            .await
+            .unwrap();
+
+        assert_eq!(signed_in.id, stored.id);
+        assert_eq!(sign_count, 1);
+
+        // The session service issues the tokens for the credential's owner.
+        let token = create_jwt(&signed_in.user_id, &Role::User, 0, 1, false);
+        assert_eq!(validate_jwt(&token.access_token).unwrap().subject, USER_ID);
+    }
+
+    #[tokio::test]
+    async fn challenges_are_single_use_and_bound_to_their_ceremony_and_user() {
+        let challenges = Challenges::default();
+        let authenticator = Authenticator::new();
+        let stored = registered(&challenges, &authenticator).await;
+
+        let challenge = challenges.issue(None, Ceremony::Authentication);
+        let request = assertion(&authenticator, &challenge, 1);
+        assert!(login(&challenges, &stored, &request).await.is_ok());
+        assert!(invalid(login(&challenges, &stored, &request).await), "a challenge can only be used once");
+
+        let challenge = challenges.issue(Some(USER_ID), Ceremony::Registration);
+        assert!(invalid(login(&challenges, &stored, &assertion(&authenticator, &challenge, 2)).await));
+
+        let challenge = challenges.issue(None, Ceremony::Authentication);
+        assert!(invalid(register(&challenges, &authenticator, &challenge).await));
+
+        let challenge = challenges.issue(Some(USER_ID + 1), Ceremony::Registration);
+        assert!(invalid(register(&challenges, &authenticator, &challenge).await));
+
+        let challenge = challenges.insert(WebauthnChallenge {
+            expires_at: Local::now() - chrono::Duration::seconds(1),
+            ..WebauthnChallenge::new(None, generate_challenge(), Ceremony::Authentication, CEREMONY_TIMEOUT)
+        });
+        assert!(invalid(login(&challenges, &stored, &assertion(&authenticator, &challenge, 2)).await));
+
+        assert!(invalid(login(&challenges, &stored, &assertion(&authenticator, &generate_challenge(), 2)).await));
+    }
+
+    #[tokio::test]
+    async fn a_challenge_issued_for_another_user_is_rejected() {
+        let challenges = Challenges::default();
+        let authenticator = Authenticator::new();
+        let stored = registered(&challenges, &authenticator).await;
+
+        let challenge = challenges.issue(Some(USER_ID + 1), Ceremony::Authentication);
+        let result = login(&challenges, &stored, &assertion(&authenticator, &challenge, 1)).await;
+
+        assert!(matches!(result, Err(AppError::AuthError(_))));
+    }
+
+    #[tokio::test]
+    async fn the_signature_counter_must_increase() {
+        let challenges = Challenges::default();
+        let authenticator = Authenticator::new();
+        let stored = PasskeyCredential { sign_count: 5, ..registered(&challenges, &authenticator).await };
+
+        for sign_count in [0, 4, 5] {
+            let challenge = challenges.issue(None, Ceremony::Authentication);
+            let result = login(&challenges, &stored, &assertion(&authenticator, &challenge, sign_count)).await;
+            assert!(matches!(result, Err(AppError::AuthError(_))), "counter {} was accepted", sign_count);
+        }
+
+        let challenge = challenges.issue(None, Ceremony::Authentication);
+        let (_, sign_count) = login(&challenges, &stored, &assertion(&authenticator, &challenge, 6)).await.unwrap();
+        assert_eq!(sign_count, 6);
+
+        // Authenticators without a counter always report zero.
+        let without_counter = PasskeyCredential { sign_count: 0, ..stored };
+        let challenge = challenges.issue(None, Ceremony::Authentication);
+        assert!(login(&challenges, &without_counter, &assertion(&authenticator, &challenge, 0)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn an_assertion_signed_by_another_key_is_rejected() {
+        let challenges = Challenges::default();
+        let stored = registered(&challenges, &Authenticator::new()).await;
+
+        let challenge = challenges.issue(None, Ceremony::Authentication);
+        let result = login(&challenges, &stored, &assertion(&Authenticator::new(), &challenge, 1)).await;
+
+        assert!(matches!(result, Err(AppError::AuthError(_))));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::errors::app_error::AppError;
+use crate::utils::password_hasher::{Argon2idHasher, BcryptHasher, PasswordHasher};
+use std::env::var;
+use std::sync::Arc;
+use tokio::task::spawn_blocking;
+use tracing::info;
+use uuid::Uuid;
+
+// OWASP recommended minimum for Argon2id: 19 MiB, 2 iterations, 1 lane.
+const DEFAULT_ARGON2_MEMORY_KIB: u32 = 19_456;
+const DEFAULT_ARGON2_ITERATIONS: u32 = 2;
+const DEFAULT_ARGON2_PARALLELISM: u32 = 1;
+const DEFAULT_BCRYPT_COST: u32 = bcrypt::DEFAULT_COST;
+
+pub struct PasswordCheck {
+    pub is_valid: bool,
+    /// Set when the password is valid but its hash uses a legacy scheme or weaker settings.
+    pub needs_rehash: bool,
+}
+
+/// Hashes new passwords with the configured scheme and verifies hashes made by any supported one.
+pub struct PasswordHashService {
+    hashers: Vec<Arc<dyn PasswordHasher>>,
+    /// Verified against when there is no stored hash, see `verify_dummy`.
+    dummy_hash: String,
+}
+
+impl PasswordHashService {
+    /// Reads `PASSWORD_HASH_ALGORITHM` (`argon2id` or `bcrypt`, defaults to `argon2id`),
+    /// `ARGON2_MEMORY_KIB`, `ARGON2_ITERATIONS`, `ARGON2_PARALLELISM` and `BCRYPT_COST`.
+    pub fn new() -> Arc<Self> {
+        let argon2: Arc<dyn PasswordHasher> = Arc::new(
+            Argon2idHasher::new(
+                read_env("ARGON2_MEMORY_KIB", DEFAULT_ARGON2_MEMORY_KIB),
+                read_env("ARGON2_ITERATIONS", DEFAULT_ARGON2_ITERATIONS),
+                read_env("ARGON2_PARALLELISM", DEFAULT_ARGON2_PARALLELISM),
+            )
+            .unwrap_or_else(|e| panic!("{}", e)),
+        );
+        let bcrypt: Arc<dyn PasswordHasher> = Arc::new(BcryptHasher::new(read_env("BCRYPT_COST", DEFAULT_BCRYPT_COST)));
+
+        let hashers = match var("PASSWORD_HASH_ALGORITHM").unwrap_or_default().trim().to_lowercase().as_str() {
+            "" | "argon2id" => vec![argon2, bcrypt],
+            "bcrypt" => vec![bcrypt, argon2],
+            other => panic!("PASSWORD_HASH_ALGORITHM '{}' is not supported", other),
+        };
+
+        info!("Password hashing algorithm: {}", hashers[0].name());
+        Arc::new(Self::with_hashers(hashers))
+    }
+
+    /// The first hasher is used for new hashes, the rest only verify existing ones.
+    fn with_hashers(hashers: Vec<Arc<dyn PasswordHasher>>) -> Self {
+        let dummy_hash = hashers[0]
+            .hash(&Uuid::new_v4().to_string())
+            .unwrap_or_else(|e| panic!("Failed to create the dummy password hash: {}", e));
+
+        Self { hashers, dummy_hash }
+    }
+
+    pub async fn hash(&self, password: &str) -> Result<String, AppError> {
+        let hasher = self.hashers[0].clone();
+        let password = password.to_string();
+
+        spawn_blocking(move || hasher.hash(&password))
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Password hashing task failed: {}", e)))?
+    }
+
+    pub async fn verify(&self, password: &str, password_hash: &str) -> Result<PasswordCheck, AppError> {
+        let Some(index) = self.hashers.iter().position(|hasher| hasher.recognizes(password_hash)) else {
+            return Ok(PasswordCheck { is_valid: false, needs_rehash: false });
+        };
+
+        let hasher = self.hashers[index].clone();
+        let password = password.to_string();
+        let password_hash = password_hash.to_string();
+
+        spawn_blocking(move || {
+            let is_valid = hasher.verify(&password, &password_hash)?;
+            let needs_rehash = is_valid && (index != 0 || hasher.needs_rehash(&password_hash));
+            Ok(PasswordCheck { is_valid, needs_rehash })
+        })
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Password verification task failed: {}", e)))?
+    }
+
+    /// Verifies the password against a hash made with the current settings and discards the
+    /// result. Logins for unknown emails call this, so their response time does not reveal
+    /// whether an account exists.
+    pub async fn verify_dummy(&self, password: &str) {
+        let _ = self.verify(password, &self.dummy_hash).await;
+    }
+
+    /// Verification for checks where a malformed hash simply means the password does not match.
+    pub async fn matches(&self, password: &str, password_hash: &str) -> bool {
+        self.verify(password, password_hash).await.is_ok_and(|check| check.is_valid)
+    }
+}
+
+fn read_env(key: &str, default: u32) -> u32 {
+    var(key)
+        .ok()
+        .map(|value| value.parse().unwrap_or_else(|_| panic!("{} must be a valid number", key)))
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> PasswordHashService {
+        PasswordHashService::with_hashers(vec![
+            Arc::new(Argon2idHasher::new(2048, 2, 1).unwrap()),
+            Arc::new(BcryptHasher::new(4)),
+        ])
+    }
+
+    #[tokio::test]
+    async fn current_hashes_verify_without_rehashing() {
+        let service = service();
+        let hash = service.hash("correct horse").await.unwrap();
+
+        let check = service.verify("correct horse", &hash).await.unwrap();
+        assert!(check.is_valid && !check.needs_rehash);
+
+        let check = service.verify("wrong horse", &hash).await.unwrap();
+        assert!(!check.is_valid && !check.needs_rehash);
+    }
+
+    #[tokio::test]
+    async fn bcrypt_hashes_verify_and_are_rehashed() {
+        let hash = BcryptHasher::new(4).hash("correct horse").unwrap();
+
+        let check = service().verify("correct horse", &hash).await.unwrap();
+        assert!(check.is_valid);
+        assert!(check.needs_rehash);
+    }
+
+    #[tokio::test]
+    async fn argon2id_hashes_with_weaker_parameters_are_rehashed() {
+        let hash = Argon2idHasher::new(1024, 1, 1).unwrap().hash("correct horse").unwrap();
+
+        let check = service().verify("correct horse", &hash).await.unwrap();
+        assert!(check.is_valid);
+        assert!(check.needs_rehash);
+    }
+
+    #[tokio::test]
+    async fn unrecognised_hashes_are_not_valid() {
+        let service = service();
+
+        for hash in ["", "correct horse", "$1$saltsalt$md5cryptdigest", "$scrypt$ln=16,r=8,p=1$c2FsdA$aGFzaA"] {
+            let check = service.verify("correct horse", hash).await.unwrap();
+            assert!(!check.is_valid, "{:?} was accepted", hash);
+            assert!(!check.needs_rehash);
+        }
+    }
+
+    #[tokio::test]
+    async fn the_dummy_hash_uses_the_current_scheme() {
+        let service = service();
+
+        assert!(service.hashers[0].recognizes(&service.dummy_hash));
+        assert!(!service.hashers[0].needs_rehash(&service.dummy_hash));
+        assert!(!service.matches("", &service.dummy_hash).await);
+        service.verify_dummy("correct horse").await;
+    }
+}
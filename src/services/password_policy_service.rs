@@ -0,0 +1,169 @@
+use crate::errors::app_error::AppError;
+use crate::payloads::password::PasswordStrengthResponse;
+use crate::utils::password_strength::estimate_strength;
+use std::collections::HashSet;
+use std::env::var;
+use std::fs;
+use std::sync::Arc;
+use tracing::info;
+
+const DEFAULT_MIN_LENGTH: usize = 8;
+const DEFAULT_MAX_LENGTH: usize = 128;
+const DEFAULT_MIN_STRENGTH: u8 = 2;
+/// Bundled into the binary so the policy works without the resources directory.
+const DEFAULT_BLOCKLIST: &str = include_str!("../../resources/passwords/common-passwords.txt");
+
+/// The single password policy applied to registration, password reset and password change.
+pub struct PasswordPolicyService {
+    min_length: usize,
+    max_length: usize,
+    min_strength: u8,
+    blocklist: HashSet<String>,
+}
+
+impl PasswordPolicyService {
+    /// Reads `PASSWORD_MIN_LENGTH`, `PASSWORD_MAX_LENGTH`, `PASSWORD_MIN_STRENGTH` (0 to 4) and
+    /// `PASSWORD_BLOCKLIST_PATH` (one password per line, replaces the bundled list), all optional.
+    pub fn new() -> Arc<Self> {
+        let min_length = read_env("PASSWORD_MIN_LENGTH", DEFAULT_MIN_LENGTH);
+        let max_length = read_env("PASSWORD_MAX_LENGTH", DEFAULT_MAX_LENGTH);
+        let min_strength = read_env("PASSWORD_MIN_STRENGTH", DEFAULT_MIN_STRENGTH);
+        assert!(min_length <= max_length, "PASSWORD_MIN_LENGTH must not exceed PASSWORD_MAX_LENGTH");
+        assert!(min_strength <= 4, "PASSWORD_MIN_STRENGTH must be between 0 and 4");
+
+        let (source, contents) = match var("PASSWORD_BLOCKLIST_PATH") {
+            Ok(path) => {
+                let contents = fs::read_to_string(&path)
+                    .unwrap_or_else(|e| panic!("Failed to read password blocklist {}: {}", path, e));
+                (path, contents)
+            }
+            Err(_) => ("the bundled list".to_string(), DEFAULT_BLOCKLIST.to_string()),
+        };
+
+        let blocklist: HashSet<String> = contents
+            .lines()
+            .map(|line| line.trim().to_lowercase())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        info!("Loaded {} blocked passwords from {}", blocklist.len(), source);
+
+        Arc::new(Self {
+            min_length,
+            max_length,
+            min_strength,
+            blocklist,
+        })
+    }
+
+    /// Scores a password and explains how to improve it. `user_inputs` are the user's own
+    /// details (email, names), which make a password easier to guess.
+    pub fn evaluate(&self, password: &str, user_inputs: &[&str]) -> PasswordStrengthResponse {
+        let strength = estimate_strength(password, &self.blocklist, user_inputs);
+        let length = password.chars().count();
+
+        let mut feedback = Vec::new();
+        if length < self.min_length {
+            feedback.push(format!("Use at least {} characters.", self.min_length));
+        }
+        if length > self.max_length {
+            feedback.push(format!("Use at most {} characters.", self.max_length));
+        }
+        if !strength.is_common && strength.score < self.min_strength {
+            feedback.push("This password is too easy to guess.".to_string());
+        }
+        feedback.extend(strength.feedback);
+
+        let acceptable = length >= self.min_length
+            && length <= self.max_length
+            && !strength.is_common
+            && strength.score >= self.min_strength;
+
+        PasswordStrengthResponse {
+            score: strength.score,
+            acceptable,
+            feedback,
+        }
+    }
+
+    pub fn enforce(&self, password: &str, user_inputs: &[&str]) -> Result<(), AppError> {
+        let result = self.evaluate(password, user_inputs);
+
+        if result.acceptable {
+            Ok(())
+        } else {
+            Err(AppError::ValidationError(result.feedback.join(" ")))
+        }
+    }
+}
+
+fn read_env<T: std::str::FromStr>(key: &str, default: T) -> T {
+    var(key)
+        .ok()
+        .map(|value| value.parse().unwrap_or_else(|_| panic!("{} must be a valid number", key)))
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(min_length: usize, min_strength: u8) -> PasswordPolicyService {
+        PasswordPolicyService {
+            min_length,
+            max_length: DEFAULT_MAX_LENGTH,
+            min_strength,
+            blocklist: ["password", "dragon"].into_iter().map(String::from).collect(),
+        }
+    }
+
+    fn rejection(result: Result<(), AppError>) -> String {
+        match result {
+            Err(AppError::ValidationError(message)) => message,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn accepts_a_long_random_password() {
+        assert!(policy(8, 3).enforce("Vb7#kPz2wQ9m", &[]).is_ok());
+    }
+
+    #[test]
+    fn rejects_passwords_below_the_minimum_length() {
+        let message = rejection(policy(16, 0).enforce("Vb7#kPz2wQ9m", &[]));
+
+        assert!(message.contains("Use at least 16 characters."), "{}", message);
+    }
+
+    #[test]
+    fn rejects_passwords_below_the_minimum_strength() {
+        let message = rejection(policy(8, 3).enforce("abcdefgh", &[]));
+
+        assert!(message.contains("This password is too easy to guess."), "{}", message);
+        assert!(policy(8, 0).enforce("abcdefgh", &[]).is_ok());
+    }
+
+    #[test]
+    fn rejects_blocklisted_passwords_whatever_the_minimum_strength() {
+        let message = rejection(policy(8, 0).enforce("p4ssw0rd", &[]));
+
+        assert!(message.contains("commonly used password"), "{}", message);
+    }
+
+    #[test]
+    fn rejects_passwords_built_from_the_users_email() {
+        let strong = "janedoe1984!x";
+        assert!(policy(8, 3).enforce(strong, &[]).is_ok());
+
+        let message = rejection(policy(8, 3).enforce(strong, &["janedoe@example.com"]));
+        assert!(message.contains("your name or email address"), "{}", message);
+    }
+
+    #[test]
+    fn empty_and_non_ascii_passwords_are_rejected_without_panicking() {
+        assert!(policy(8, 2).enforce("", &[]).is_err());
+        assert!(policy(1, 0).enforce("ß", &["ß"]).is_ok());
+        assert!(policy(8, 2).enforce("пароль", &["пароль@example.com"]).is_err());
+    }
+}
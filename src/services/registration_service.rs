@@ -0,0 +1,87 @@
+use crate::configs::registration::{RegistrationMode, RegistrationPolicy};
+use crate::errors::app_error::AppError;
+use crate::models::invite::Invite;
+use crate::repositories::invite_repository::InviteRepository;
+use crate::utils::token_util::hash_token;
+use std::sync::Arc;
+use tracing::{error, warn};
+
+const INVALID_INVITE: &str = "Invite is invalid or has expired.";
+
+/// Decides who may create an account, for both password sign-up and single sign-on.
+pub struct RegistrationService {
+    policy: RegistrationPolicy,
+    invite_repo: Arc<InviteRepository>,
+}
+
+impl RegistrationService {
+    pub fn new(policy: RegistrationPolicy, invite_repo: Arc<InviteRepository>) -> Arc<Self> {
+        Arc::new(Self { policy, invite_repo })
+    }
+
+    /// Checks a password sign-up against the policy. A presented invite is always validated,
+    /// even when registration is open, because it may preassign a role.
+    pub async fn authorize(&self, email: &str, invite_token: Option<&str>) -> Result<Option<Invite>, AppError> {
+        if self.policy.mode == RegistrationMode::Closed {
+            return Err(self.rejection());
+        }
+
+        if let Some(token) = invite_token {
+            let invite = self
+                .invite_repo
+                .find_pending_by_hash(&hash_token(token))
+                .await
+                .map_err(|e| AppError::DatabaseError(e.to_string()))?
+                .filter(|invite| invite.email.eq_ignore_ascii_case(email.trim()))
+                .ok_or_else(|| AppError::BadRequest(INVALID_INVITE.into()))?;
+
+            return Ok(Some(invite));
+        }
+
+        if self.policy.admits_without_invite(email) {
+            Ok(None)
+        } else {
+            Err(self.rejection())
+        }
+    }
+
+    /// Checks an account about to be created through single sign-on. The provider has verified
+    /// the email, so a pending invite for that address is honoured without its token.
+    pub async fn authorize_verified_email(&self, email: &str) -> Result<Option<Invite>, AppError> {
+        if self.policy.mode == RegistrationMode::Closed {
+            return Err(self.rejection());
+        }
+
+        let invite = self
+            .invite_repo
+            .find_pending_by_email(email)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if invite.is_some() || self.policy.admits_without_invite(email) {
+            Ok(invite)
+        } else {
+            Err(self.rejection())
+        }
+    }
+
+    /// Marks the invite as used by the new account. The account is kept even if this fails.
+    pub async fn accept(&self, invite: &Invite, user_id: i64) {
+        match self.invite_repo.mark_accepted(invite.id, user_id).await {
+            Ok(true) => (),
+            Ok(false) => warn!("Invite {} was no longer pending when user {} registered", invite.id, user_id),
+            Err(e) => error!("Failed to mark invite {} as accepted: {:?}", invite.id, e),
+        }
+    }
+
+    fn rejection(&self) -> AppError {
+        let message = match self.policy.mode {
+            RegistrationMode::Closed => "Registration is closed.",
+            RegistrationMode::InviteOnly => "Registration requires an invite.",
+            RegistrationMode::DomainAllowlist => "Registration is limited to approved email domains or invited users.",
+            RegistrationMode::Open => "Registration is not allowed.",
+        };
+
+        AppError::Forbidden(message.into())
+    }
+}
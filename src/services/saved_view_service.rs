@@ -0,0 +1,150 @@
+use crate::errors::app_error::{extract_validation_errors, AppError};
+use crate::models::saved_view::SavedView;
+use crate::payloads::saved_view::{CreateSavedViewRequest, SavedViewResponse, UpdateSavedViewRequest};
+use crate::repositories::application_repository::ApplicationRepository;
+use crate::repositories::saved_view_repository::SavedViewRepository;
+use sqlx::types::Json;
+use std::sync::Arc;
+use tracing::error;
+use validator::Validate;
+
+const MAX_SAVED_VIEWS: i64 = 50;
+
+pub struct SavedViewService {
+    saved_view_repo: Arc<SavedViewRepository>,
+    application_repo: Arc<ApplicationRepository>,
+}
+
+impl SavedViewService {
+    pub fn new(saved_view_repo: Arc<SavedViewRepository>, application_repo: Arc<ApplicationRepository>) -> Arc<Self> {
+        Arc::new(Self {
+            saved_view_repo,
+            application_repo,
+        })
+    }
+
+    pub async fn create(&self, user_id: i64, req: CreateSavedViewRequest) -> Result<SavedViewResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let views = self
+            .saved_view_repo
+            .count_by_user_id(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        if views >= MAX_SAVED_VIEWS {
+            return Err(AppError::BadRequest(format!(
+                "You can keep at most {} saved views, delete one first.",
+                MAX_SAVED_VIEWS
+            )));
+        }
+
+        let view = self
+            .saved_view_repo
+            .save(SavedView::new(
+                user_id,
+                req.name.trim().to_string(),
+                req.filter.into_saved(),
+                req.columns,
+                req.show_count,
+            ))
+            .await
+            .map_err(|e| {
+                error!("Failed to save view for user {}: {:?}", user_id, e);
+                map_save_error(e)
+            })?;
+
+        self.to_response(user_id, &view).await
+    }
+
+    pub async fn list(&self, user_id: i64) -> Result<Vec<SavedViewResponse>, AppError> {
+        let views = self
+            .saved_view_repo
+            .find_all_by_user_id(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let mut responses = Vec::with_capacity(views.len());
+        for view in &views {
+            responses.push(self.to_response(user_id, view).await?);
+        }
+        Ok(responses)
+    }
+
+    pub async fn get(&self, user_id: i64, id: i64) -> Result<SavedViewResponse, AppError> {
+        let view = self.find(user_id, id).await?;
+        self.to_response(user_id, &view).await
+    }
+
+    pub async fn update(
+        &self,
+        user_id: i64,
+        id: i64,
+        req: UpdateSavedViewRequest,
+    ) -> Result<SavedViewResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let mut view = self.find(user_id, id).await?;
+        if let Some(name) = req.name {
+            view.name = name.trim().to_string();
+        }
+        if let Some(filter) = req.filter {
+            view.filter = Json(filter.into_saved());
+        }
+        if let Some(columns) = req.columns {
+            view.columns = Json(columns);
+        }
+        if let Some(show_count) = req.show_count {
+            view.show_count = show_count;
+        }
+
+        let view = self.saved_view_repo.update(&view).await.map_err(map_save_error)?;
+        self.to_response(user_id, &view).await
+    }
+
+    pub async fn delete(&self, user_id: i64, id: i64) -> Result<(), AppError> {
+        let deleted = self
+            .saved_view_repo
+            .delete(id, user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !deleted {
+            return Err(AppError::ResourceNotFound("Saved view not found.".into()));
+        }
+        Ok(())
+    }
+
+    async fn find(&self, user_id: i64, id: i64) -> Result<SavedView, AppError> {
+        self.saved_view_repo
+            .find_by_id(id, user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::ResourceNotFound("Saved view not found.".into()))
+    }
+
+    async fn to_response(&self, user_id: i64, view: &SavedView) -> Result<SavedViewResponse, AppError> {
+        let count = if view.show_count {
+            Some(
+                self.application_repo
+                    .count_applications_by_user_with_filters(user_id, view.filter.0.clone())
+                    .await
+                    .map_err(|e| AppError::DatabaseError(e.to_string()))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(SavedViewResponse::from_saved_view(view, count))
+    }
+}
+
+fn map_save_error(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            AppError::ResourceExists("You already have a saved view with this name.".into())
+        }
+        e => AppError::DatabaseError(e.to_string()),
+    }
+}
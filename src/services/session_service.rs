@@ -0,0 +1,230 @@
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::errors::app_error::AppError;
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::models::audit_log::AuditLog;
+use crate::models::preferences::UserPreferences;
+use crate::models::session::UserSession;
+use crate::models::user::User;
+use crate::payloads::session::{RevokedSessionsResponse, SessionResponse};
+use crate::repositories::preferences_repository::PreferencesRepository;
+use crate::repositories::session_repository::SessionRepository;
+use crate::services::audit_service::AuditService;
+use crate::services::email_service::EmailService;
+use crate::utils::jwt::{create_jwt, session_lifetime, JwtToken, RefreshClaims};
+use crate::utils::token_util::hash_token;
+use chrono::Local;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{error, info};
+
+pub const SESSION_REVOKED: &str = "Session has been revoked. Please log in again.";
+
+/// Records every login as a session so users can see and revoke where they are signed in.
+pub struct SessionService {
+    session_repo: Arc<SessionRepository>,
+    preferences_repo: Arc<PreferencesRepository>,
+    email_service: Arc<EmailService>,
+    audit_service: Arc<AuditService>,
+}
+
+impl SessionService {
+    pub fn new(
+        session_repo: Arc<SessionRepository>,
+        preferences_repo: Arc<PreferencesRepository>,
+        email_service: Arc<EmailService>,
+        audit_service: Arc<AuditService>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            session_repo,
+            preferences_repo,
+            email_service,
+            audit_service,
+        })
+    }
+
+    /// Opens a session for a successful login and issues its tokens. The user is alerted by
+    /// email when the device has not been used to log in before. Every login method ends here,
+    /// so this is also where successful logins are audited.
+    pub async fn start(&self, user: &User, client: &ClientInfo, remember_me: bool) -> Result<JwtToken, AppError> {
+        let device_hash = hash_token(client.user_agent.as_deref().unwrap_or_default());
+
+        let known_device = self
+            .session_repo
+            .is_known_device(user.id, &device_hash)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let session = UserSession::new(
+            user.id,
+            client.user_agent.clone(),
+            client.ip_address.clone(),
+            device_hash,
+            Local::now() + session_lifetime(remember_me),
+        );
+
+        let session = self.session_repo.save(session).await.map_err(|e| {
+            error!("Failed to create session for user {}: {:?}", user.id, e);
+            AppError::DatabaseError(e.to_string())
+        })?;
+
+        let new_device = known_device == Some(false);
+
+        self.audit_service
+            .record(
+                AuditLog::new(
+                    Some(user.id),
+                    AuditAction::LoginSucceeded,
+                    AuditTargetType::Session,
+                    Some(session.id),
+                    json!({ "newDevice": new_device, "rememberMe": remember_me }),
+                )
+                .with_client(client),
+            )
+            .await?;
+
+        // No alert for the very first login, there is nothing to compare against.
+        if new_device {
+            self.alert_new_device(user, &session);
+        }
+
+        Ok(create_jwt(&user.id, &user.role, user.token_version, session.id, remember_me))
+    }
+
+    /// Issues fresh tokens for the session of a refresh token, as long as the session has not
+    /// been revoked or expired. The session keeps its original expiry.
+    pub async fn resume(&self, user: &User, claims: &RefreshClaims) -> Result<JwtToken, AppError> {
+        self.session_repo
+            .find_active_by_id(claims.session_id, user.id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?
+            .ok_or_else(|| AppError::InvalidToken(SESSION_REVOKED.into()))?;
+
+        Ok(create_jwt(&user.id, &user.role, user.token_version, claims.session_id, claims.remember_me))
+    }
+
+    /// Issues fresh tokens for an existing session, e.g. after a password change bumped the
+    /// token version. Falls back to a new session for tokens issued before sessions existed.
+    pub async fn renew(
+        &self,
+        user: &User,
+        token_version: i32,
+        session_id: Option<i64>,
+        client: &ClientInfo,
+    ) -> Result<JwtToken, AppError> {
+        let user = User { token_version, ..user.clone() };
+
+        match session_id {
+            Some(session_id) => Ok(create_jwt(&user.id, &user.role, user.token_version, session_id, false)),
+            None => self.start(&user, client, false).await,
+        }
+    }
+
+    pub async fn list(&self, user_id: i64, current_session_id: Option<i64>) -> Result<Vec<SessionResponse>, AppError> {
+        let sessions = self
+            .session_repo
+            .find_active_by_user_id(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        Ok(sessions
+            .iter()
+            .map(|session| SessionResponse::from_session(session, current_session_id))
+            .collect())
+    }
+
+    pub async fn revoke(&self, user_id: i64, session_id: i64, client: &ClientInfo) -> Result<(), AppError> {
+        self.end(user_id, session_id).await?;
+
+        info!("User {} revoked session {}", user_id, session_id);
+        self.audit(user_id, AuditAction::SessionRevoked, Some(session_id), json!({}), client).await
+    }
+
+    /// Ends the session a user is logging out of.
+    pub async fn log_out(&self, user_id: i64, session_id: i64, client: &ClientInfo) -> Result<(), AppError> {
+        self.end(user_id, session_id).await?;
+        self.audit(user_id, AuditAction::Logout, Some(session_id), json!({}), client).await
+    }
+
+    /// Signs the user out everywhere except the session making the request, or everywhere when
+    /// there is no such session.
+    pub async fn revoke_others(
+        &self,
+        user_id: i64,
+        current_session_id: Option<i64>,
+        client: &ClientInfo,
+    ) -> Result<RevokedSessionsResponse, AppError> {
+        let revoked = self
+            .session_repo
+            .revoke_all_except(user_id, current_session_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        info!("User {} revoked {} other sessions", user_id, revoked);
+        if revoked > 0 {
+            self.audit(user_id, AuditAction::OtherSessionsRevoked, current_session_id, json!({ "revoked": revoked }), client)
+                .await?;
+        }
+
+        Ok(RevokedSessionsResponse { revoked })
+    }
+
+    async fn end(&self, user_id: i64, session_id: i64) -> Result<(), AppError> {
+        let revoked = self
+            .session_repo
+            .revoke(session_id, user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        if !revoked {
+            return Err(AppError::ResourceNotFound("Session not found.".into()));
+        }
+        Ok(())
+    }
+
+    async fn audit(
+        &self,
+        user_id: i64,
+        action: AuditAction,
+        session_id: Option<i64>,
+        details: Value,
+        client: &ClientInfo,
+    ) -> Result<(), AppError> {
+        self.audit_service
+            .record(AuditLog::new(Some(user_id), action, AuditTargetType::Session, session_id, details).with_client(client))
+            .await
+    }
+
+    /// Sent unless the user turned security alerts off.
+    fn alert_new_device(&self, user: &User, session: &UserSession) {
+        let preferences_repo = self.preferences_repo.clone();
+        let email_service = self.email_service.clone();
+        let user = user.clone();
+        let session = session.clone();
+
+        tokio::spawn(async move {
+            let preferences = preferences_repo.find_by_user_id(user.id).await.unwrap_or_else(|e| {
+                error!("Failed to load preferences of user {}: {:?}", user.id, e);
+                UserPreferences::default()
+            });
+
+            if !preferences.notifications.security_alerts {
+                return;
+            }
+
+            let user_name = format!("{} {}", user.first_name, user.last_name);
+            if let Err(e) = email_service
+                .send_new_device_login(
+                    &user.email,
+                    user_name.trim(),
+                    session.user_agent.as_deref(),
+                    session.ip_address.as_deref(),
+                    &preferences.format_datetime(&session.created_at),
+                    &preferences.locale,
+                )
+                .await
+            {
+                error!("Failed to send new device alert to user {}: {:?}", user.id, e);
+            }
+        });
+    }
+}
@@ -1,29 +1,83 @@
+use crate::enums::audit::{AuditAction, AuditTargetType};
+use crate::enums::roles::Role;
 use crate::errors::app_error::{AppError, extract_validation_errors};
+use crate::middlewares::client_info_extractor::ClientInfo;
+use crate::models::audit_log::AuditLog;
 use crate::models::user::User;
-use crate::payloads::user::{UserInfo, UserRequest};
+use crate::models::preferences::UserPreferences;
+use crate::payloads::admin::{AuditLogFilter, AuditLogResponse};
+use crate::payloads::pagination::Page;
+use crate::payloads::preferences::UpdatePreferencesRequest;
+use crate::payloads::user::{AccountDeletionResponse, CancelAccountDeletionRequest, DeleteAccountRequest, UpdateProfileRequest, UserInfo, UserRequest};
+use crate::repositories::preferences_repository::PreferencesRepository;
 use crate::repositories::user_repository::UserRepository;
-use bcrypt::{DEFAULT_COST, hash};
+use crate::services::audit_service::AuditService;
+use crate::services::password_hash_service::PasswordHashService;
+use crate::services::password_policy_service::PasswordPolicyService;
+use crate::services::registration_service::RegistrationService;
+use chrono::{Duration, Local};
+use serde_json::{json, Value};
+use std::env::var;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, info};
 use validator::Validate;
 
+const DEFAULT_DELETION_GRACE_PERIOD_IN_DAYS: i64 = 30;
+
 pub struct UserService {
     user_repo: Arc<UserRepository>,
+    preferences_repo: Arc<PreferencesRepository>,
+    registration_service: Arc<RegistrationService>,
+    password_policy: Arc<PasswordPolicyService>,
+    password_hasher: Arc<PasswordHashService>,
+    audit_service: Arc<AuditService>,
+    deletion_grace_period: Duration,
 }
 
 impl UserService {
-    pub fn new(user_repo: Arc<UserRepository>) -> Arc<Self> {
-        Arc::new(Self { user_repo })
+    pub fn new(
+        user_repo: Arc<UserRepository>,
+        preferences_repo: Arc<PreferencesRepository>,
+        registration_service: Arc<RegistrationService>,
+        password_policy: Arc<PasswordPolicyService>,
+        password_hasher: Arc<PasswordHashService>,
+        audit_service: Arc<AuditService>,
+    ) -> Arc<Self> {
+        let grace_period_in_days = var("ACCOUNT_DELETION_GRACE_PERIOD_IN_DAYS")
+            .ok()
+            .map(|days| days.parse().expect("ACCOUNT_DELETION_GRACE_PERIOD_IN_DAYS must be a valid integer"))
+            .unwrap_or(DEFAULT_DELETION_GRACE_PERIOD_IN_DAYS);
+
+        Arc::new(Self {
+            user_repo,
+            preferences_repo,
+            registration_service,
+            password_policy,
+            password_hasher,
+            audit_service,
+            deletion_grace_period: Duration::days(grace_period_in_days),
+        })
     }
 
     pub async fn register_user(
         &self,
         registration_data: UserRequest,
+        client: &ClientInfo,
     ) -> Result<UserInfo, AppError> {
         registration_data
             .validate()
             .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
 
+        self.password_policy.enforce(
+            &registration_data.password,
+            &[&registration_data.email, &registration_data.first_name, &registration_data.last_name],
+        )?;
+
+        let invite = self
+            .registration_service
+            .authorize(&registration_data.email, registration_data.invite_token.as_deref())
+            .await?;
+
         match self
             .user_repo
             .exists_by_email(registration_data.email.clone())
@@ -34,23 +88,40 @@ impl UserService {
             Err(e) => return Err(AppError::DatabaseError(e.to_string())),
         }
 
-        let password_hash = hash(&registration_data.password, DEFAULT_COST)
-            .map_err(|e| AppError::AuthError(format!("Failed to hash password: {}", e)))?;
+        let password_hash = self.password_hasher.hash(&registration_data.password).await?;
 
-        let new_user = User::new(
+        // Only an invite can grant a role other than the default; admins change roles afterwards.
+        let role = invite
+            .as_ref()
+            .and_then(|invite| invite.role.clone())
+            .unwrap_or(Role::User);
+
+        let mut new_user = User::new(
             registration_data.first_name,
             registration_data.last_name,
             registration_data.email,
             Option::from(registration_data.phone_number),
             password_hash,
-            registration_data.role,
+            Some(role),
         );
+        // The invite link was delivered to this address, which proves the user owns it.
+        new_user.is_verified = invite.is_some();
 
-        self.user_repo
+        let user = self
+            .user_repo
             .save(new_user)
             .await
-            .map(|user| UserInfo::from_user(&user))
-            .map_err(|e| AppError::DatabaseError(e.to_string()))
+            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+
+        let invite_id = invite.as_ref().map(|invite| invite.id);
+        if let Some(invite) = invite {
+            self.registration_service.accept(&invite, user.id).await;
+        }
+
+        self.audit(user.id, AuditAction::UserRegistered, json!({ "inviteId": invite_id }), client)
+            .await?;
+
+        Ok(UserInfo::from_user(&user))
     }
 
     pub async fn get_user_data(&self, user_id: i64) -> Result<UserInfo, AppError> {
@@ -75,4 +146,152 @@ impl UserService {
             phone_number: user.phone_number,
         })
     }
+
+    /// Soft-deletes the account right away, which blocks login and revokes sessions.
+    /// The data is purged by the account purge job once the grace period has passed.
+    pub async fn delete_account(
+        &self,
+        user_id: i64,
+        req: DeleteAccountRequest,
+        client: &ClientInfo,
+    ) -> Result<AccountDeletionResponse, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let user = self
+            .user_repo
+            .get_active_user_by_id(user_id)
+            .await
+            .map_err(|_| AppError::ResourceNotFound(String::from("User not found.")))?;
+
+        if !self.password_hasher.matches(&req.password, &user.password).await {
+            return Err(AppError::BadRequest("Password is incorrect.".into()));
+        }
+
+        let purge_after = Local::now() + self.deletion_grace_period;
+
+        self.user_repo
+            .schedule_deletion(user.id, purge_after)
+            .await
+            .map_err(|e| {
+                error!("Failed to delete account of user {}: {:?}", user.id, e);
+                AppError::DatabaseError(e.to_string())
+            })?;
+
+        info!("User {} deleted their account; purge scheduled after {}", user.id, purge_after);
+        self.audit(user.id, AuditAction::AccountDeletionRequested, json!({ "purgeAfter": purge_after }), client)
+            .await?;
+
+        Ok(AccountDeletionResponse { purge_after })
+    }
+
+    pub async fn cancel_account_deletion(
+        &self,
+        req: CancelAccountDeletionRequest,
+        client: &ClientInfo,
+    ) -> Result<UserInfo, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let invalid = || AppError::BadRequest("Invalid email or password, or the account can no longer be restored.".into());
+
+        let user = self
+            .user_repo
+            .find_pending_deletion_by_email(&req.email)
+            .await?
+            .ok_or_else(invalid)?;
+
+        if !self.password_hasher.matches(&req.password, &user.password).await {
+            return Err(invalid());
+        }
+
+        // The email may have been registered by someone else during the grace period.
+        let audit = AuditLog::new(Some(user.id), AuditAction::AccountDeletionCancelled, AuditTargetType::User, Some(user.id), json!({}))
+            .with_client(client);
+
+        let user = self.user_repo.reactivate(user.id, audit).await.map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                AppError::ResourceExists("Email is already in use by another account.".into())
+            }
+            e => AppError::DatabaseError(e.to_string()),
+        })?;
+
+        info!("User {} cancelled their account deletion", user.id);
+
+        Ok(UserInfo::from_user(&user))
+    }
+
+    pub async fn update_profile(&self, user_id: i64, req: UpdateProfileRequest) -> Result<UserInfo, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        self.user_repo
+            .update_profile(
+                user_id,
+                req.first_name.map(|name| name.trim().to_string()),
+                req.last_name.map(|name| name.trim().to_string()),
+                req.phone_number,
+            )
+            .await
+            .map(|user| UserInfo::from_user(&user))
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => AppError::ResourceNotFound(String::from("User not found.")),
+                e => AppError::DatabaseError(e.to_string()),
+            })
+    }
+
+    pub async fn get_preferences(&self, user_id: i64) -> Result<UserPreferences, AppError> {
+        self.preferences_repo
+            .find_by_user_id(user_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn update_preferences(&self, user_id: i64, req: UpdatePreferencesRequest) -> Result<UserPreferences, AppError> {
+        req.validate()
+            .map_err(|err| AppError::ValidationError(extract_validation_errors(&err)))?;
+
+        let mut preferences = self.get_preferences(user_id).await?;
+
+        if let Some(timezone) = req.timezone {
+            preferences.timezone = timezone;
+        }
+        if let Some(locale) = req.locale {
+            preferences.locale = locale;
+        }
+        if let Some(date_format) = req.date_format {
+            preferences.date_format = date_format;
+        }
+        if let Some(default_page_size) = req.default_page_size {
+            preferences.default_page_size = default_page_size;
+        }
+        if let Some(default_application_type) = req.default_application_type {
+            preferences.default_application_type = default_application_type;
+        }
+        if let Some(notifications) = req.notifications {
+            let current = &mut preferences.notifications;
+            current.security_alerts = notifications.security_alerts.unwrap_or(current.security_alerts);
+            current.product_updates = notifications.product_updates.unwrap_or(current.product_updates);
+            current.application_reminders = notifications.application_reminders.unwrap_or(current.application_reminders);
+        }
+
+        self.preferences_repo
+            .save(user_id, &preferences)
+            .await
+            .map_err(|e| {
+                error!("Failed to save preferences for user {}: {:?}", user_id, e);
+                AppError::DatabaseError(e.to_string())
+            })
+    }
+
+    /// The user's own security history: what they did, and what was done to their account.
+    pub async fn list_audit_logs(&self, user_id: i64, filter: AuditLogFilter) -> Result<Page<AuditLogResponse>, AppError> {
+        self.audit_service.list_for_user(user_id, filter).await
+    }
+
+    async fn audit(&self, user_id: i64, action: AuditAction, details: Value, client: &ClientInfo) -> Result<(), AppError> {
+        self.audit_service
+            .record(AuditLog::new(Some(user_id), action, AuditTargetType::User, Some(user_id), details).with_client(client))
+            .await
+    }
 }
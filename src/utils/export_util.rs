@@ -0,0 +1,62 @@
+use crate::errors::app_error::AppError;
+use serde::Serialize;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Builds a zip archive in memory, one JSON or CSV file at a time.
+pub struct ExportArchive {
+    writer: ZipWriter<Cursor<Vec<u8>>>,
+}
+
+impl ExportArchive {
+    pub fn new() -> Self {
+        Self {
+            writer: ZipWriter::new(Cursor::new(Vec::new())),
+        }
+    }
+
+    pub fn add_json<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<(), AppError> {
+        let content = serde_json::to_vec_pretty(value)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to serialize {}: {}", name, e)))?;
+
+        self.add_file(name, &content)
+    }
+
+    pub fn add_csv<T: Serialize>(&mut self, name: &str, rows: &[T]) -> Result<(), AppError> {
+        let mut csv_writer = csv::Writer::from_writer(Vec::new());
+        for row in rows {
+            csv_writer
+                .serialize(row)
+                .map_err(|e| AppError::InternalServerError(format!("Failed to serialize {}: {}", name, e)))?;
+        }
+
+        let content = csv_writer
+            .into_inner()
+            .map_err(|e| AppError::InternalServerError(format!("Failed to write {}: {}", name, e)))?;
+
+        self.add_file(name, &content)
+    }
+
+    pub fn finish(self) -> Result<Vec<u8>, AppError> {
+        self.writer
+            .finish()
+            .map(Cursor::into_inner)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to finish archive: {}", e)))
+    }
+
+    fn add_file(&mut self, name: &str, content: &[u8]) -> Result<(), AppError> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        self.writer
+            .start_file(name, options)
+            .and_then(|_| self.writer.write_all(content).map_err(Into::into))
+            .map_err(|e| AppError::InternalServerError(format!("Failed to add {} to archive: {}", name, e)))
+    }
+}
+
+impl Default for ExportArchive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
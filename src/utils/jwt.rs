@@ -2,6 +2,7 @@ use crate::enums::roles::Role;
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+#[cfg(not(test))]
 use std::env;
 use utoipa::ToSchema;
 
@@ -9,9 +10,45 @@ use utoipa::ToSchema;
 pub struct Claims {
     pub subject: i64,
     pub role: Role,
+    /// Must match the user's current `token_version`; bumping it revokes the token.
+    #[serde(default, rename = "ver")]
+    pub token_version: i32,
+    /// The login session the token belongs to; revoking the session revokes the token.
+    /// Not set for personal access tokens.
+    #[serde(default, rename = "sid", skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<i64>,
     pub exp: usize,
 }
 
+/// Short-lived claims proving the password step of an MFA login succeeded.
+#[derive(Serialize, Deserialize)]
+pub struct MfaChallengeClaims {
+    pub subject: i64,
+    pub purpose: String,
+    #[serde(rename = "rememberMe")]
+    pub remember_me: bool,
+    pub exp: usize,
+}
+
+/// Claims of a refresh token. They carry no `role`, so a refresh token is never accepted
+/// where an access token is expected.
+#[derive(Serialize, Deserialize)]
+pub struct RefreshClaims {
+    pub subject: i64,
+    pub purpose: String,
+    #[serde(rename = "ver")]
+    pub token_version: i32,
+    #[serde(rename = "sid")]
+    pub session_id: i64,
+    #[serde(rename = "rememberMe")]
+    pub remember_me: bool,
+    pub exp: usize,
+}
+
+const MFA_CHALLENGE_PURPOSE: &str = "mfa_challenge";
+const REFRESH_PURPOSE: &str = "refresh";
+pub const MFA_CHALLENGE_EXPIRY_IN_MINUTES: i64 = 5;
+
 struct JwtConfig {
     secret_key: String,
     expiry: i64,
@@ -23,16 +60,21 @@ struct JwtConfig {
 
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct JwtToken {
-    #[serde(rename = "accessToken")]
-    access_token: String,
+    /// Omitted in cookie session mode, where the token is set as an HttpOnly cookie instead.
+    #[serde(rename = "accessToken", skip_serializing_if = "String::is_empty")]
+    pub(crate) access_token: String,
     #[serde(rename = "expiresIn")]
-    expires_in: i64,
-    #[serde(rename = "refreshToken")]
-    refresh_token: String,
+    pub(crate) expires_in: i64,
+    #[serde(rename = "refreshToken", skip_serializing_if = "String::is_empty")]
+    pub(crate) refresh_token: String,
     #[serde(rename = "refreshTokenExpiresIn")]
-    refresh_expires_in: i64,
+    pub(crate) refresh_expires_in: i64,
+    /// Only set in cookie session mode; send it back in the `X-CSRF-Token` header.
+    #[serde(rename = "csrfToken", skip_serializing_if = "Option::is_none")]
+    pub(crate) csrf_token: Option<String>,
 }
 
+#[cfg(not(test))]
 fn get_jwt_config() -> JwtConfig {
     let secret_key = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
     let expiry = env::var("JWT_EXPIRY_IN_MINUTES")
@@ -49,7 +91,19 @@ fn get_jwt_config() -> JwtConfig {
     JwtConfig { secret_key, expiry, refresh_expiry, expiry_for_30_days, refresh_expiry_for_30_days }
 }
 
-pub fn create_jwt(subject: &i64, role: &Role, remember_me: bool) -> JwtToken {
+/// Tests run without the environment the server is started with.
+#[cfg(test)]
+fn get_jwt_config() -> JwtConfig {
+    JwtConfig {
+        secret_key: "test-secret".to_string(),
+        expiry: 60,
+        refresh_expiry: 60 * 24,
+        expiry_for_30_days: 43200,
+        refresh_expiry_for_30_days: 43200 * 24,
+    }
+}
+
+pub fn create_jwt(subject: &i64, role: &Role, token_version: i32, session_id: i64, remember_me: bool) -> JwtToken {
     let config = get_jwt_config();
 
     let access_expires_in = if !remember_me { 
@@ -64,12 +118,8 @@ pub fn create_jwt(subject: &i64, role: &Role, remember_me: bool) -> JwtToken {
         .expect("Valid timestamp")
         .timestamp();
 
-    let refresh_expires_in = if !remember_me { 
-        config.refresh_expiry
-    } else {
-        config.refresh_expiry_for_30_days
-    };
-    
+    let refresh_expires_in = refresh_expiry_in_minutes(&config, remember_me);
+
     let refresh_expiration = Utc::now()
         .checked_add_signed(Duration::minutes(refresh_expires_in))
         .expect("Valid timestamp")
@@ -78,12 +128,17 @@ pub fn create_jwt(subject: &i64, role: &Role, remember_me: bool) -> JwtToken {
     let access_claims = Claims {
         subject: subject.to_owned(),
         role: role.to_owned(),
+        token_version,
+        session_id: Some(session_id),
         exp: access_expiration as usize,
     };
 
-    let refresh_claims = Claims {
+    let refresh_claims = RefreshClaims {
         subject: subject.to_owned(),
-        role: role.to_owned(),
+        purpose: REFRESH_PURPOSE.to_string(),
+        token_version,
+        session_id,
+        remember_me,
         exp: refresh_expiration as usize,
     };
 
@@ -101,6 +156,20 @@ pub fn create_jwt(subject: &i64, role: &Role, remember_me: bool) -> JwtToken {
         expires_in: access_expires_in,
         refresh_token,
         refresh_expires_in,
+        csrf_token: None,
+    }
+}
+
+/// How long a login lasts: the lifetime of its refresh token.
+pub fn session_lifetime(remember_me: bool) -> Duration {
+    Duration::minutes(refresh_expiry_in_minutes(&get_jwt_config(), remember_me))
+}
+
+fn refresh_expiry_in_minutes(config: &JwtConfig, remember_me: bool) -> i64 {
+    if !remember_me {
+        config.refresh_expiry
+    } else {
+        config.refresh_expiry_for_30_days
     }
 }
 
@@ -110,3 +179,50 @@ pub fn validate_jwt(token: &str) -> Result<Claims, jsonwebtoken::errors::Error>
 
     decode::<Claims>(token, &decoding_key, &validation).map(|data| data.claims)
 }
+
+pub fn validate_refresh_token(token: &str) -> Result<RefreshClaims, jsonwebtoken::errors::Error> {
+    let decoding_key = DecodingKey::from_secret(get_jwt_config().secret_key.as_bytes());
+    let validation = Validation::new(Algorithm::HS256);
+
+    let claims = decode::<RefreshClaims>(token, &decoding_key, &validation)?.claims;
+    if claims.purpose != REFRESH_PURPOSE {
+        return Err(jsonwebtoken::errors::ErrorKind::InvalidToken.into());
+    }
+
+    Ok(claims)
+}
+
+pub fn create_mfa_challenge_token(subject: &i64, remember_me: bool) -> String {
+    let config = get_jwt_config();
+
+    let expiration = Utc::now()
+        .checked_add_signed(Duration::minutes(MFA_CHALLENGE_EXPIRY_IN_MINUTES))
+        .expect("Valid timestamp")
+        .timestamp();
+
+    let claims = MfaChallengeClaims {
+        subject: subject.to_owned(),
+        purpose: MFA_CHALLENGE_PURPOSE.to_string(),
+        remember_me,
+        exp: expiration as usize,
+    };
+
+    encode(
+        &Header::new(Algorithm::HS256),
+        &claims,
+        &EncodingKey::from_secret(config.secret_key.as_bytes()),
+    )
+    .expect("Error creating MFA challenge token")
+}
+
+pub fn validate_mfa_challenge_token(token: &str) -> Result<MfaChallengeClaims, jsonwebtoken::errors::Error> {
+    let decoding_key = DecodingKey::from_secret(get_jwt_config().secret_key.as_bytes());
+    let validation = Validation::new(Algorithm::HS256);
+
+    let claims = decode::<MfaChallengeClaims>(token, &decoding_key, &validation)?.claims;
+    if claims.purpose != MFA_CHALLENGE_PURPOSE {
+        return Err(jsonwebtoken::errors::ErrorKind::InvalidToken.into());
+    }
+
+    Ok(claims)
+}
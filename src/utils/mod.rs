@@ -3,4 +3,12 @@ pub(crate) mod custom_formatter;
 pub(crate) mod api_response;
 pub(crate) mod date_util;
 pub(crate) mod email_util;
-pub(crate) mod validator_util;
\ No newline at end of file
+pub(crate) mod validator_util;
+pub(crate) mod totp_util;
+pub(crate) mod webauthn_util;
+pub(crate) mod export_util;
+pub(crate) mod token_util;
+pub(crate) mod password_strength;
+pub(crate) mod password_hasher;
+pub(crate) mod search_util;
+pub(crate) mod query_util;
\ No newline at end of file
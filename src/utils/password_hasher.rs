@@ -0,0 +1,170 @@
+use crate::errors::app_error::AppError;
+use argon2::password_hash::{PasswordHash, PasswordHasher as _, PasswordVerifier as _, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::RngCore;
+
+/// One password hashing scheme. Implementations are CPU heavy by design and must be called from
+/// a blocking thread, never directly on the async runtime.
+pub trait PasswordHasher: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Whether the stored hash was produced by this scheme.
+    fn recognizes(&self, password_hash: &str) -> bool;
+
+    fn hash(&self, password: &str) -> Result<String, AppError>;
+
+    fn verify(&self, password: &str, password_hash: &str) -> Result<bool, AppError>;
+
+    /// Whether a hash of this scheme was made with weaker settings than the current ones.
+    fn needs_rehash(&self, password_hash: &str) -> bool;
+}
+
+pub struct Argon2idHasher {
+    params: Params,
+}
+
+impl Argon2idHasher {
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Result<Self, AppError> {
+        let params = Params::new(memory_kib, iterations, parallelism, None)
+            .map_err(|e| AppError::InternalServerError(format!("Invalid Argon2 parameters: {}", e)))?;
+
+        Ok(Self { params })
+    }
+
+    fn argon2(&self) -> Argon2<'_> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+impl PasswordHasher for Argon2idHasher {
+    fn name(&self) -> &'static str {
+        "argon2id"
+    }
+
+    fn recognizes(&self, password_hash: &str) -> bool {
+        password_hash.starts_with("$argon2id$")
+    }
+
+    fn hash(&self, password: &str) -> Result<String, AppError> {
+        let mut salt = [0u8; 16];
+        rand::rng().fill_bytes(&mut salt);
+        let salt = SaltString::encode_b64(&salt)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to encode salt: {}", e)))?;
+
+        self.argon2()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| AppError::AuthError(format!("Failed to hash password: {}", e)))
+    }
+
+    fn verify(&self, password: &str, password_hash: &str) -> Result<bool, AppError> {
+        let parsed = PasswordHash::new(password_hash)
+            .map_err(|e| AppError::AuthError(format!("Malformed password hash: {}", e)))?;
+
+        // The parameters are read from the hash itself, so older settings still verify.
+        Ok(Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+    }
+
+    fn needs_rehash(&self, password_hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(password_hash) else {
+            return true;
+        };
+        let Ok(current) = Params::try_from(&parsed) else {
+            return true;
+        };
+
+        current.m_cost() < self.params.m_cost()
+            || current.t_cost() < self.params.t_cost()
+            || current.p_cost() < self.params.p_cost()
+    }
+}
+
+pub struct BcryptHasher {
+    cost: u32,
+}
+
+impl BcryptHasher {
+    pub fn new(cost: u32) -> Self {
+        Self { cost }
+    }
+}
+
+impl PasswordHasher for BcryptHasher {
+    fn name(&self) -> &'static str {
+        "bcrypt"
+    }
+
+    fn recognizes(&self, password_hash: &str) -> bool {
+        ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| password_hash.starts_with(prefix))
+    }
+
+    fn hash(&self, password: &str) -> Result<String, AppError> {
+        bcrypt::hash(password, self.cost).map_err(|e| AppError::AuthError(format!("Failed to hash password: {}", e)))
+    }
+
+    fn verify(&self, password: &str, password_hash: &str) -> Result<bool, AppError> {
+        bcrypt::verify(password, password_hash)
+            .map_err(|e| AppError::AuthError(format!("Failed to verify password: {}", e)))
+    }
+
+    fn needs_rehash(&self, password_hash: &str) -> bool {
+        // Hashes look like `$2b$12$...`, with the cost as the second field.
+        password_hash
+            .split('$')
+            .nth(2)
+            .and_then(|cost| cost.parse::<u32>().ok())
+            .is_none_or(|cost| cost < self.cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Low costs keep the tests fast; only their relative order matters.
+    fn argon2(memory_kib: u32, iterations: u32) -> Argon2idHasher {
+        Argon2idHasher::new(memory_kib, iterations, 1).unwrap()
+    }
+
+    #[test]
+    fn argon2id_hashes_verify_with_the_parameters_they_were_made_with() {
+        let weaker = argon2(1024, 1).hash("correct horse").unwrap();
+        let current = argon2(2048, 2);
+
+        assert!(current.recognizes(&weaker));
+        assert!(current.verify("correct horse", &weaker).unwrap());
+        assert!(!current.verify("wrong horse", &weaker).unwrap());
+    }
+
+    #[test]
+    fn argon2id_hashes_with_weaker_parameters_need_rehashing() {
+        let current = argon2(2048, 2);
+
+        assert!(current.needs_rehash(&argon2(1024, 2).hash("password").unwrap()));
+        assert!(current.needs_rehash(&argon2(2048, 1).hash("password").unwrap()));
+        assert!(!current.needs_rehash(&current.hash("password").unwrap()));
+        assert!(!current.needs_rehash(&argon2(4096, 3).hash("password").unwrap()));
+    }
+
+    #[test]
+    fn bcrypt_hashes_verify_and_need_rehashing_below_the_current_cost() {
+        let hash = BcryptHasher::new(4).hash("correct horse").unwrap();
+
+        assert!(BcryptHasher::new(5).recognizes(&hash));
+        assert!(!argon2(1024, 1).recognizes(&hash));
+        assert!(BcryptHasher::new(5).verify("correct horse", &hash).unwrap());
+        assert!(!BcryptHasher::new(5).verify("wrong horse", &hash).unwrap());
+        assert!(BcryptHasher::new(5).needs_rehash(&hash));
+        assert!(!BcryptHasher::new(4).needs_rehash(&hash));
+    }
+
+    #[test]
+    fn malformed_hashes_never_verify_and_need_rehashing() {
+        let hasher = argon2(1024, 1);
+
+        assert!(!hasher.verify("password", "$argon2id$not-a-hash").unwrap_or(false));
+        assert!(!BcryptHasher::new(4).verify("password", "$2b$xx$not-a-hash").unwrap_or(false));
+        assert!(hasher.needs_rehash("$argon2id$v=19$m=lots,t=1,p=1$c2FsdHNhbHQ$aGFzaA"));
+        assert!(BcryptHasher::new(4).needs_rehash("$2b$xx$not-a-hash"));
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashSet;
+
+/// Bits of entropy needed to reach scores 1 to 4.
+const SCORE_THRESHOLDS: [f64; 4] = [28.0, 36.0, 50.0, 70.0];
+
+/// How much a character that follows a predictable pattern (repeat, sequence, keyboard walk,
+/// common word, year or personal info) counts towards the effective length.
+const PREDICTABLE_CHAR_WEIGHT: f64 = 0.2;
+
+const MIN_WORD_LENGTH: usize = 4;
+const MAX_WORD_LENGTH: usize = 20;
+const MIN_USER_INPUT_LENGTH: usize = 3;
+
+const KEYBOARD_ROWS: [&str; 4] = ["`1234567890-=", "qwertyuiop[]\\", "asdfghjkl;'", "zxcvbnm,./"];
+
+const COMMON_PASSWORD_FEEDBACK: &str = "This is a commonly used password that appears in breach lists.";
+const COMMON_WORD_FEEDBACK: &str = "Avoid common words and passwords, even with numbers or symbols added.";
+const USER_INPUT_FEEDBACK: &str = "Avoid using your name or email address in your password.";
+const REPEAT_FEEDBACK: &str = "Avoid repeated characters like 'aaa'.";
+const SEQUENCE_FEEDBACK: &str = "Avoid sequences like 'abc' or '123'.";
+const KEYBOARD_FEEDBACK: &str = "Avoid keyboard patterns like 'qwerty' or 'asdf'.";
+const YEAR_FEEDBACK: &str = "Avoid years and dates, they are easy to guess.";
+const CHARACTER_MIX_FEEDBACK: &str = "Mix upper and lower case letters, numbers and symbols.";
+const LENGTH_FEEDBACK: &str = "Add another word or a few more characters; longer passwords are stronger.";
+
+pub struct PasswordStrength {
+    /// 0 (very weak) to 4 (very strong).
+    pub score: u8,
+    pub is_common: bool,
+    pub feedback: Vec<String>,
+}
+
+/// Estimates how hard a password is to guess. The estimate starts from the brute-force entropy
+/// (character pool and length) and discounts every character that is part of a predictable pattern.
+pub fn estimate_strength(password: &str, blocklist: &HashSet<String>, user_inputs: &[&str]) -> PasswordStrength {
+    let lower: Vec<char> = password.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect();
+    let unleeted: Vec<char> = lower.iter().map(|&c| unleet(c)).collect();
+    let normalized: String = lower.iter().collect();
+
+    if blocklist.contains(&normalized) || blocklist.contains(&unleeted.iter().collect::<String>()) {
+        return PasswordStrength {
+            score: 0,
+            is_common: true,
+            feedback: vec![COMMON_PASSWORD_FEEDBACK.to_string()],
+        };
+    }
+
+    let mut predictable = vec![false; lower.len()];
+    let mut feedback: Vec<&str> = Vec::new();
+    let mut flag = |found: bool, message: &'static str| {
+        if found && !feedback.contains(&message) {
+            feedback.push(message);
+        }
+    };
+
+    let found = mark_words(&lower, &unleeted, blocklist, &mut predictable);
+    flag(found, COMMON_WORD_FEEDBACK);
+
+    let found = mark_user_inputs(&normalized, user_inputs, &mut predictable);
+    flag(found, USER_INPUT_FEEDBACK);
+
+    let found = mark_repeats(&lower, &mut predictable);
+    flag(found, REPEAT_FEEDBACK);
+
+    let found = mark_sequences(&lower, &mut predictable);
+    flag(found, SEQUENCE_FEEDBACK);
+
+    let found = mark_keyboard_walks(&lower, &mut predictable);
+    flag(found, KEYBOARD_FEEDBACK);
+
+    let found = mark_years(&lower, &mut predictable);
+    flag(found, YEAR_FEEDBACK);
+
+    let effective_length: f64 = predictable
+        .iter()
+        .map(|&is_predictable| if is_predictable { PREDICTABLE_CHAR_WEIGHT } else { 1.0 })
+        .sum();
+
+    let (pool_size, character_classes) = character_pool(password);
+    let entropy_bits = effective_length * (pool_size as f64).log2();
+    let score = SCORE_THRESHOLDS.iter().filter(|&&bits| entropy_bits >= bits).count() as u8;
+
+    if score < 3 {
+        flag(character_classes < 3, CHARACTER_MIX_FEEDBACK);
+        flag(true, LENGTH_FEEDBACK);
+    }
+
+    PasswordStrength {
+        score,
+        is_common: false,
+        feedback: feedback.into_iter().map(String::from).collect(),
+    }
+}
+
+/// Marks the longest common word starting at each position. Both the plain and the
+/// de-leeted forms are checked, so `p4ssw0rd` is caught as well as `password`.
+fn mark_words(lower: &[char], unleeted: &[char], blocklist: &HashSet<String>, predictable: &mut [bool]) -> bool {
+    let mut found = false;
+    let mut start = 0;
+
+    while start < lower.len() {
+        let longest = (MIN_WORD_LENGTH..=MAX_WORD_LENGTH.min(lower.len() - start)).rev().find(|&len| {
+            let end = start + len;
+            blocklist.contains(&lower[start..end].iter().collect::<String>())
+                || blocklist.contains(&unleeted[start..end].iter().collect::<String>())
+        });
+
+        match longest {
+            Some(len) => {
+                predictable[start + 1..start + len].iter_mut().for_each(|p| *p = true);
+                found = true;
+                start += len;
+            }
+            None => start += 1,
+        }
+    }
+
+    found
+}
+
+fn mark_user_inputs(normalized: &str, user_inputs: &[&str], predictable: &mut [bool]) -> bool {
+    let mut found = false;
+
+    for input in user_inputs {
+        // Only the part before the `@` of an email address is personal.
+        let input = input.split('@').next().unwrap_or_default().trim().to_lowercase();
+        if input.chars().count() < MIN_USER_INPUT_LENGTH {
+            continue;
+        }
+
+        for (byte_index, _) in normalized.match_indices(&input) {
+            let start = normalized[..byte_index].chars().count();
+            let len = input.chars().count();
+            predictable[start + 1..start + len].iter_mut().for_each(|p| *p = true);
+            found = true;
+        }
+    }
+
+    found
+}
+
+fn mark_repeats(lower: &[char], predictable: &mut [bool]) -> bool {
+    let mut found = false;
+
+    for i in 2..lower.len() {
+        if lower[i] == lower[i - 1] && lower[i - 1] == lower[i - 2] {
+            predictable[i - 1] = true;
+            predictable[i] = true;
+            found = true;
+        }
+    }
+
+    found
+}
+
+fn mark_sequences(lower: &[char], predictable: &mut [bool]) -> bool {
+    let mut found = false;
+
+    for i in 2..lower.len() {
+        let window = &lower[i - 2..=i];
+        if !window.iter().all(|c| c.is_ascii_alphanumeric()) {
+            continue;
+        }
+
+        let first_step = window[1] as i32 - window[0] as i32;
+        let second_step = window[2] as i32 - window[1] as i32;
+        if first_step.abs() == 1 && first_step == second_step {
+            predictable[i - 1] = true;
+            predictable[i] = true;
+            found = true;
+        }
+    }
+
+    found
+}
+
+fn mark_keyboard_walks(lower: &[char], predictable: &mut [bool]) -> bool {
+    let position = |c: char| {
+        KEYBOARD_ROWS
+            .iter()
+            .enumerate()
+            .find_map(|(row, keys)| keys.chars().position(|key| key == c).map(|column| (row, column as i32)))
+    };
+
+    let mut found = false;
+
+    for i in 2..lower.len() {
+        let (Some(a), Some(b), Some(c)) = (position(lower[i - 2]), position(lower[i - 1]), position(lower[i])) else {
+            continue;
+        };
+
+        let same_row = a.0 == b.0 && b.0 == c.0;
+        let step = b.1 - a.1;
+        if same_row && step.abs() == 1 && c.1 - b.1 == step {
+            predictable[i - 1] = true;
+            predictable[i] = true;
+            found = true;
+        }
+    }
+
+    found
+}
+
+/// Marks four-digit years from 1900 to 2099.
+fn mark_years(lower: &[char], predictable: &mut [bool]) -> bool {
+    let mut found = false;
+
+    for start in 0..lower.len().saturating_sub(3) {
+        let window = &lower[start..start + 4];
+        let is_year = window.iter().all(char::is_ascii_digit)
+            && matches!((window[0], window[1]), ('1', '9') | ('2', '0'));
+        let is_delimited = |i: Option<&char>| i.is_none_or(|c| !c.is_ascii_digit());
+
+        if is_year
+            && is_delimited(start.checked_sub(1).and_then(|i| lower.get(i)))
+            && is_delimited(lower.get(start + 4))
+        {
+            predictable[start + 1..start + 4].iter_mut().for_each(|p| *p = true);
+            found = true;
+        }
+    }
+
+    found
+}
+
+/// Returns the size of the character pool the password draws from and how many
+/// character classes it uses.
+fn character_pool(password: &str) -> (u32, u32) {
+    let classes = [
+        (password.chars().any(|c| c.is_ascii_lowercase()), 26),
+        (password.chars().any(|c| c.is_ascii_uppercase()), 26),
+        (password.chars().any(|c| c.is_ascii_digit()), 10),
+        (password.chars().any(|c| c.is_ascii_punctuation() || c == ' '), 33),
+        (!password.is_ascii(), 100),
+    ];
+
+    classes
+        .iter()
+        .filter(|(present, _)| *present)
+        .fold((0, 0), |(pool, count), (_, size)| (pool + size, count + 1))
+}
+
+fn unleet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocklist() -> HashSet<String> {
+        ["password", "dragon", "sunshine"].into_iter().map(String::from).collect()
+    }
+
+    fn estimate(password: &str) -> PasswordStrength {
+        estimate_strength(password, &blocklist(), &[])
+    }
+
+    #[test]
+    fn blocklisted_passwords_score_zero_even_when_leeted() {
+        for password in ["password", "PassWord", "p4ssw0rd", "p@$$w0rd"] {
+            let strength = estimate(password);
+            assert_eq!(strength.score, 0, "{}", password);
+            assert!(strength.is_common, "{}", password);
+            assert_eq!(strength.feedback, vec![COMMON_PASSWORD_FEEDBACK]);
+        }
+    }
+
+    #[test]
+    fn common_words_inside_a_password_are_discounted() {
+        let strength = estimate("Sunshine!Dragon");
+
+        assert!(!strength.is_common);
+        assert!(strength.feedback.iter().any(|f| f == COMMON_WORD_FEEDBACK));
+        assert!(strength.score < estimate("Tqmvhrpe!Xkbzlw").score);
+    }
+
+    #[test]
+    fn keyboard_walks_sequences_and_years_lower_the_score() {
+        let random = estimate("Vb7#kPz2wQ9m");
+        assert_eq!(random.score, 4);
+
+        for (password, message) in [
+            ("Vb7#qwertyui", KEYBOARD_FEEDBACK),
+            ("Vb7#abcdefgh", SEQUENCE_FEEDBACK),
+            ("Vb7#12345678", SEQUENCE_FEEDBACK),
+            ("Vb7#kP1987z!", YEAR_FEEDBACK),
+            ("Vb7#kkkkkkkk", REPEAT_FEEDBACK),
+        ] {
+            let strength = estimate(password);
+            assert!(strength.score < random.score, "{} scored {}", password, strength.score);
+            assert!(strength.feedback.iter().any(|f| f == message), "{}: {:?}", password, strength.feedback);
+        }
+    }
+
+    #[test]
+    fn digits_next_to_a_year_are_not_a_year() {
+        assert!(!estimate("Vb7#kP19875z").feedback.iter().any(|f| f == YEAR_FEEDBACK));
+    }
+
+    #[test]
+    fn the_email_local_part_and_names_are_flagged() {
+        let inputs = ["jane.doe@example.com", "Zyxwbq"];
+
+        let with_email = estimate_strength("jane.doe!Kx92", &blocklist(), &inputs);
+        assert!(with_email.feedback.iter().any(|f| f == USER_INPUT_FEEDBACK));
+        assert!(with_email.score < estimate("jqnv.dxe!Kx92").score);
+
+        let with_name = estimate_strength("ZYXWBQ-4471-hm", &blocklist(), &inputs);
+        assert!(with_name.feedback.iter().any(|f| f == USER_INPUT_FEEDBACK));
+
+        // The domain of the address is not personal.
+        let with_domain = estimate_strength("Kx92!example", &blocklist(), &inputs);
+        assert!(!with_domain.feedback.iter().any(|f| f == USER_INPUT_FEEDBACK));
+    }
+
+    #[test]
+    fn empty_and_non_ascii_passwords_do_not_panic() {
+        let empty = estimate("");
+        assert_eq!(empty.score, 0);
+        assert!(!empty.is_common);
+
+        for password in ["ß", "İstanbul1987", "пароль", "密码密码密码", "ǅǅǅ", "🔑🔑🔑abc"] {
+            let strength = estimate_strength(password, &blocklist(), &["İstanbul", "密码"]);
+            assert!(strength.score <= 4);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use serde::de::value::StrDeserializer;
+use serde::de::{DeserializeOwned, Error, IntoDeserializer, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Reads a list filter written as comma separated values, e.g. `status=Interview,Test`.
+/// A JSON array is accepted too, so the same filter can be stored and read back as JSON.
+pub fn deserialize_comma_separated<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(OneOrMany::One(value)) => value.split(',').map(|part| part.trim().to_string()).collect(),
+        Some(OneOrMany::Many(values)) => values,
+    };
+
+    parse_values::<D, T>(values)
+}
+
+/// Reads a list filter of free text, e.g. company names, which may contain commas. Values are
+/// given as repeated parameters, e.g. `company=Acme, Inc.&company=Globex`, or as a JSON array.
+pub fn deserialize_repeated<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(OneOrMany::One(value)) => vec![value.trim().to_string()],
+        Some(OneOrMany::Many(values)) => values.iter().map(|value| value.trim().to_string()).collect(),
+    };
+
+    parse_values::<D, T>(values)
+}
+
+/// Reads a parameter whose presence matters even without a value, e.g. `cursor=`. Query strings
+/// otherwise read an empty value as a missing one.
+pub fn deserialize_keep_empty<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct KeepEmpty;
+
+    impl<'de> Visitor<'de> for KeepEmpty {
+        type Value = Option<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a string")
+        }
+
+        fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+            Ok(Some(value.to_string()))
+        }
+
+        fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    deserializer.deserialize_any(KeepEmpty)
+}
+
+fn parse_values<'de, D, T>(values: Vec<String>) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let parsed = values
+        .iter()
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            let deserializer: StrDeserializer<'_, serde::de::value::Error> = value.as_str().into_deserializer();
+            T::deserialize(deserializer).map_err(|e| D::Error::custom(format!("invalid value '{}': {}", value, e)))
+        })
+        .collect::<Result<Vec<T>, D::Error>>()?;
+
+    Ok((!parsed.is_empty()).then_some(parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Uri;
+    use axum_extra::extract::Query;
+
+    #[derive(Deserialize)]
+    struct Filter {
+        #[serde(default, deserialize_with = "deserialize_comma_separated")]
+        status: Option<Vec<String>>,
+        #[serde(default, deserialize_with = "deserialize_repeated")]
+        company: Option<Vec<String>>,
+        #[serde(default, deserialize_with = "deserialize_keep_empty")]
+        cursor: Option<String>,
+    }
+
+    fn query(uri: &'static str) -> Filter {
+        Query::<Filter>::try_from_uri(&Uri::from_static(uri)).unwrap().0
+    }
+
+    #[test]
+    fn free_text_values_keep_their_commas() {
+        let filter = query("/?company=Acme%2C%20Inc.");
+        assert_eq!(filter.company, Some(vec!["Acme, Inc.".to_string()]));
+
+        let filter = query("/?company=Acme%2C%20Inc.&company=Globex");
+        assert_eq!(filter.company, Some(vec!["Acme, Inc.".to_string(), "Globex".to_string()]));
+    }
+
+    #[test]
+    fn fixed_values_are_split_on_commas_or_repeated() {
+        assert_eq!(query("/?status=Interview,Test").status, Some(vec!["Interview".to_string(), "Test".to_string()]));
+        assert_eq!(query("/?status=Interview&status=Test").status, Some(vec!["Interview".to_string(), "Test".to_string()]));
+        assert_eq!(query("/").status, None);
+    }
+
+    #[test]
+    fn json_arrays_are_read_as_is() {
+        let filter: Filter = serde_json::from_str(r#"{"status":["Test"],"company":["Acme, Inc."]}"#).unwrap();
+
+        assert_eq!(filter.status, Some(vec!["Test".to_string()]));
+        assert_eq!(filter.company, Some(vec!["Acme, Inc.".to_string()]));
+    }
+
+    #[test]
+    fn an_empty_value_is_kept_apart_from_a_missing_one() {
+        assert_eq!(query("/?cursor=").cursor, Some(String::new()));
+        assert_eq!(query("/?cursor=abc").cursor, Some("abc".to_string()));
+        assert_eq!(query("/").cursor, None);
+
+        let filter: Filter = serde_json::from_str(r#"{"cursor":null}"#).unwrap();
+        assert_eq!(filter.cursor, None);
+    }
+}
@@ -0,0 +1,17 @@
+/// Longer searches are cut off rather than turned into ever larger queries.
+const MAX_SEARCH_TERMS: usize = 10;
+
+/// Turns free text into a `to_tsquery` expression where every word must match, as a prefix so
+/// results show up while the user is still typing. Only letters and digits are kept, which
+/// also keeps tsquery operators in the input from being interpreted. Returns `None` when
+/// nothing searchable is left.
+pub fn to_prefix_tsquery(search: &str) -> Option<String> {
+    let terms: Vec<String> = search
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" & "))
+}
@@ -0,0 +1,204 @@
+use crate::errors::app_error::AppError;
+use crate::models::mfa::RecoveryCode;
+use chrono::Utc;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const TOTP_ISSUER: &str = "AppliQ";
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP: u64 = 30;
+const TOTP_SKEW: u64 = 1;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// Source of the current Unix time in seconds, injectable so codes can be checked at fixed times.
+pub type Clock = Arc<dyn Fn() -> u64 + Send + Sync>;
+
+pub fn system_clock() -> Clock {
+    Arc::new(|| Utc::now().timestamp() as u64)
+}
+
+pub fn generate_secret() -> String {
+    match Secret::generate_secret().to_encoded() {
+        Secret::Encoded(secret) => secret,
+        Secret::Raw(_) => unreachable!("to_encoded always returns an encoded secret"),
+    }
+}
+
+pub fn build_totp(secret: &str, account_name: &str) -> Result<TOTP, AppError> {
+    let secret_bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| AppError::InternalServerError(format!("Invalid TOTP secret: {}", e)))?;
+
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        TOTP_SKEW as u8,
+        TOTP_STEP,
+        secret_bytes,
+        Some(TOTP_ISSUER.to_string()),
+        account_name.to_string(),
+    )
+    .map_err(|e| AppError::InternalServerError(format!("Failed to build TOTP: {}", e)))
+}
+
+pub fn render_qr_svg(data: &str) -> Result<String, AppError> {
+    let code = QrCode::new(data.as_bytes())
+        .map_err(|e| AppError::InternalServerError(format!("Failed to build QR code: {}", e)))?;
+
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build())
+}
+
+/// Checks `code` against the steps around `unix_time`, allowing for clock skew.
+/// Returns the matched time step so callers can reject replays of the same code.
+pub fn verify_code(totp: &TOTP, code: &str, unix_time: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let current_step = unix_time / TOTP_STEP;
+    (current_step.saturating_sub(TOTP_SKEW)..=current_step + TOTP_SKEW)
+        .find(|step| totp.generate(step * TOTP_STEP) == code)
+}
+
+/// Like [`verify_code`], but also rejects a code whose step is not after `last_used_step`,
+/// so a code cannot be replayed within its validity window.
+pub fn verify_unused_code(
+    totp: &TOTP,
+    code: &str,
+    unix_time: u64,
+    last_used_step: Option<i64>,
+) -> Option<u64> {
+    verify_code(totp, code, unix_time)
+        .filter(|step| last_used_step.is_none_or(|last| *step as i64 > last))
+}
+
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::rng();
+
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let chars: String = (0..10)
+                .map(|_| RECOVERY_CODE_ALPHABET[rng.random_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+/// Finds the unused recovery code matching `code`, ignoring case and separators.
+pub fn find_recovery_code<'a>(codes: &'a [RecoveryCode], code: &str) -> Option<&'a RecoveryCode> {
+    let code_hash = hash_recovery_code(code);
+    codes
+        .iter()
+        .find(|recovery_code| recovery_code.used_at.is_none() && recovery_code.code_hash == code_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    // RFC 6238 test secret ("12345678901234567890" in base32).
+    const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    const NOW: u64 = 1_700_000_000;
+
+    fn totp() -> TOTP {
+        build_totp(SECRET, "user@example.com").unwrap()
+    }
+
+    fn code_at(unix_time: u64) -> String {
+        totp().generate(unix_time)
+    }
+
+    fn recovery_code(id: i64, code: &str) -> RecoveryCode {
+        RecoveryCode {
+            id,
+            user_id: 1,
+            code_hash: hash_recovery_code(code),
+            used_at: None,
+            created_at: Local::now(),
+        }
+    }
+
+    #[test]
+    fn verify_code_accepts_one_step_of_skew() {
+        let step = NOW / TOTP_STEP;
+
+        assert_eq!(verify_code(&totp(), &code_at(NOW), NOW), Some(step));
+        assert_eq!(verify_code(&totp(), &code_at(NOW - TOTP_STEP), NOW), Some(step - 1));
+        assert_eq!(verify_code(&totp(), &code_at(NOW + TOTP_STEP), NOW), Some(step + 1));
+    }
+
+    #[test]
+    fn verify_code_rejects_codes_outside_the_skew() {
+        assert_eq!(verify_code(&totp(), &code_at(NOW - 2 * TOTP_STEP), NOW), None);
+        assert_eq!(verify_code(&totp(), &code_at(NOW + 2 * TOTP_STEP), NOW), None);
+    }
+
+    #[test]
+    fn verify_code_rejects_malformed_codes() {
+        let code = code_at(NOW);
+
+        assert_eq!(verify_code(&totp(), &code[..5], NOW), None);
+        assert_eq!(verify_code(&totp(), "12345a", NOW), None);
+        assert_eq!(verify_code(&totp(), &format!(" {} ", code), NOW), Some(NOW / TOTP_STEP));
+    }
+
+    #[test]
+    fn verify_unused_code_rejects_replays() {
+        let code = code_at(NOW);
+        let step = verify_unused_code(&totp(), &code, NOW, None).unwrap();
+
+        assert_eq!(verify_unused_code(&totp(), &code, NOW, Some(step as i64)), None);
+        assert_eq!(verify_unused_code(&totp(), &code, NOW + TOTP_STEP, Some(step as i64)), None);
+        assert_eq!(
+            verify_unused_code(&totp(), &code_at(NOW - TOTP_STEP), NOW, Some(step as i64)),
+            None
+        );
+        assert_eq!(
+            verify_unused_code(&totp(), &code_at(NOW + TOTP_STEP), NOW + TOTP_STEP, Some(step as i64)),
+            Some(step + 1)
+        );
+    }
+
+    #[test]
+    fn recovery_codes_are_single_use() {
+        let mut codes = vec![recovery_code(1, "abcde-fghjk"), recovery_code(2, "mnpqr-stuvw")];
+
+        let found = find_recovery_code(&codes, "ABCDE FGHJK").map(|code| code.id);
+        assert_eq!(found, Some(1));
+
+        codes[0].used_at = Some(Local::now());
+        assert!(find_recovery_code(&codes, "abcde-fghjk").is_none());
+        assert_eq!(find_recovery_code(&codes, "mnpqr-stuvw").map(|code| code.id), Some(2));
+    }
+
+    #[test]
+    fn generated_recovery_codes_are_distinct() {
+        let codes = generate_recovery_codes();
+        let hashes: std::collections::HashSet<_> = codes.iter().map(|c| hash_recovery_code(c)).collect();
+
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(hashes.len(), RECOVERY_CODE_COUNT);
+    }
+}
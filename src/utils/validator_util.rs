@@ -1,6 +1,16 @@
+use chrono_tz::Tz;
 use lazy_static::lazy_static;
 use regex::Regex;
+use validator::ValidationError;
 
 lazy_static! {
     pub static ref PHONE_REGEX: Regex = Regex::new(r"^\+\d{1,3}\d{10,15}$").unwrap();
-}
\ No newline at end of file
+    pub static ref LOCALE_REGEX: Regex = Regex::new(r"^[a-z]{2,3}(-[A-Z]{2})?$").unwrap();
+}
+
+pub fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    timezone
+        .parse::<Tz>()
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("timezone").with_message("Timezone must be a valid IANA timezone, e.g. Europe/Berlin".into()))
+}
@@ -0,0 +1,364 @@
+use crate::errors::app_error::AppError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ciborium::Value;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use rand::RngCore;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+pub const CLIENT_DATA_TYPE_CREATE: &str = "webauthn.create";
+pub const CLIENT_DATA_TYPE_GET: &str = "webauthn.get";
+
+/// COSE algorithm identifier for ECDSA with P-256 and SHA-256, the only algorithm we accept.
+pub const COSE_ALG_ES256: i64 = -7;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+#[derive(Deserialize)]
+pub struct ClientData {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub challenge: String,
+    pub origin: String,
+}
+
+pub struct AttestedCredential {
+    pub aaguid: String,
+    pub credential_id: Vec<u8>,
+    /// Uncompressed SEC1 encoding of the P-256 public key.
+    pub public_key: Vec<u8>,
+}
+
+pub struct AuthenticatorData {
+    pub rp_id_hash: Vec<u8>,
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+
+    pub fn user_verified(&self) -> bool {
+        self.flags & FLAG_USER_VERIFIED != 0
+    }
+
+    pub fn matches_rp_id(&self, rp_id: &str) -> bool {
+        self.rp_id_hash == Sha256::digest(rp_id.as_bytes()).as_slice()
+    }
+}
+
+pub fn generate_challenge() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    encode_base64url(&bytes)
+}
+
+pub fn encode_base64url(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+pub fn decode_base64url(input: &str) -> Result<Vec<u8>, AppError> {
+    URL_SAFE_NO_PAD
+        .decode(input.trim_end_matches('='))
+        .map_err(|_| AppError::BadRequest("Invalid base64url encoding.".into()))
+}
+
+pub fn parse_client_data(
+    client_data_json: &[u8],
+    expected_type: &str,
+    expected_origin: &str,
+) -> Result<ClientData, AppError> {
+    let client_data: ClientData = serde_json::from_slice(client_data_json)
+        .map_err(|_| AppError::BadRequest("Invalid client data.".into()))?;
+
+    if client_data.type_ != expected_type {
+        return Err(AppError::BadRequest("Unexpected client data type.".into()));
+    }
+
+    if client_data.origin != expected_origin {
+        return Err(AppError::BadRequest("Unexpected origin.".into()));
+    }
+
+    Ok(client_data)
+}
+
+/// Parses a `none`-conveyance attestation object. The attestation statement is not verified
+/// because registration options request no attestation; only the authenticator data is used.
+pub fn parse_attestation_object(attestation_object: &[u8]) -> Result<AuthenticatorData, AppError> {
+    let value: Value = ciborium::de::from_reader(attestation_object)
+        .map_err(|_| AppError::BadRequest("Invalid attestation object.".into()))?;
+
+    let auth_data = map_get(&value, &Value::Text("authData".into()))
+        .and_then(Value::as_bytes)
+        .ok_or_else(|| AppError::BadRequest("Attestation object is missing authData.".into()))?;
+
+    parse_authenticator_data(auth_data)
+}
+
+pub fn parse_authenticator_data(data: &[u8]) -> Result<AuthenticatorData, AppError> {
+    let invalid = || AppError::BadRequest("Invalid authenticator data.".into());
+
+    if data.len() < 37 {
+        return Err(invalid());
+    }
+
+    let rp_id_hash = data[..32].to_vec();
+    let flags = data[32];
+    let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+
+    let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL_DATA != 0 {
+        let rest = &data[37..];
+        if rest.len() < 18 {
+            return Err(invalid());
+        }
+
+        let aaguid = Uuid::from_slice(&rest[..16]).map_err(|_| invalid())?.to_string();
+        let id_len = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+        let credential_id = rest.get(18..18 + id_len).ok_or_else(invalid)?.to_vec();
+
+        let mut cose_key = &rest[18 + id_len..];
+        let key: Value = ciborium::de::from_reader(&mut cose_key).map_err(|_| invalid())?;
+
+        Some(AttestedCredential {
+            aaguid,
+            credential_id,
+            public_key: parse_cose_es256_key(&key)?,
+        })
+    } else {
+        None
+    };
+
+    Ok(AuthenticatorData {
+        rp_id_hash,
+        flags,
+        sign_count,
+        attested_credential,
+    })
+}
+
+/// Verifies an ES256 assertion signature over `authenticatorData || SHA-256(clientDataJSON)`.
+pub fn verify_signature(
+    public_key: &[u8],
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature: &[u8],
+) -> Result<(), AppError> {
+    let key = VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|_| AppError::InternalServerError("Stored passkey public key is invalid.".into()))?;
+    let signature = Signature::from_der(signature)
+        .map_err(|_| AppError::AuthError("Invalid passkey signature.".into()))?;
+
+    let mut signed_data = authenticator_data.to_vec();
+    signed_data.extend_from_slice(&Sha256::digest(client_data_json));
+
+    key.verify(&signed_data, &signature)
+        .map_err(|_| AppError::AuthError("Invalid passkey signature.".into()))
+}
+
+fn parse_cose_es256_key(key: &Value) -> Result<Vec<u8>, AppError> {
+    let unsupported = || AppError::BadRequest("Only ES256 passkeys are supported.".into());
+    let int = |label: i64| map_get(key, &Value::Integer(label.into()));
+
+    // kty = EC2 (2), alg = ES256 (-7), crv = P-256 (1)
+    let kty = int(1).and_then(Value::as_integer).map(i128::from);
+    let alg = int(3).and_then(Value::as_integer).map(i128::from);
+    let crv = int(-1).and_then(Value::as_integer).map(i128::from);
+
+    if kty != Some(2) || alg != Some(COSE_ALG_ES256 as i128) || crv != Some(1) {
+        return Err(unsupported());
+    }
+
+    let x = int(-2).and_then(Value::as_bytes).ok_or_else(unsupported)?;
+    let y = int(-3).and_then(Value::as_bytes).ok_or_else(unsupported)?;
+    if x.len() != 32 || y.len() != 32 {
+        return Err(unsupported());
+    }
+
+    let mut public_key = Vec::with_capacity(65);
+    public_key.push(0x04);
+    public_key.extend_from_slice(x);
+    public_key.extend_from_slice(y);
+
+    VerifyingKey::from_sec1_bytes(&public_key).map_err(|_| unsupported())?;
+    Ok(public_key)
+}
+
+fn map_get<'a>(value: &'a Value, key: &Value) -> Option<&'a Value> {
+    value
+        .as_map()?
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v)
+}
+
+#[cfg(test)]
+pub(crate) mod software_authenticator {
+    use super::*;
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::SigningKey;
+
+    pub const RP_ID: &str = "app.example.com";
+    pub const ORIGIN: &str = "https://app.example.com";
+    pub const CREDENTIAL_ID: &[u8] = b"software-authenticator-credential";
+
+    /// A software authenticator holding a single ES256 credential.
+    pub struct Authenticator {
+        key: SigningKey,
+    }
+
+    impl Authenticator {
+        pub fn new() -> Self {
+            let mut secret = [0u8; 32];
+            rand::rng().fill_bytes(&mut secret);
+            Self { key: SigningKey::from_slice(&secret).unwrap() }
+        }
+
+        pub fn public_key(&self) -> Vec<u8> {
+            self.key.verifying_key().to_encoded_point(false).as_bytes().to_vec()
+        }
+
+        fn cose_key(&self, alg: i64) -> Vec<u8> {
+            let point = self.key.verifying_key().to_encoded_point(false);
+            let key = Value::Map(vec![
+                (Value::Integer(1.into()), Value::Integer(2.into())),
+                (Value::Integer(3.into()), Value::Integer(alg.into())),
+                (Value::Integer((-1).into()), Value::Integer(1.into())),
+                (Value::Integer((-2).into()), Value::Bytes(point.x().unwrap().to_vec())),
+                (Value::Integer((-3).into()), Value::Bytes(point.y().unwrap().to_vec())),
+            ]);
+
+            let mut bytes = Vec::new();
+            ciborium::ser::into_writer(&key, &mut bytes).unwrap();
+            bytes
+        }
+
+        fn authenticator_data(&self, flags: u8, sign_count: u32) -> Vec<u8> {
+            let mut data = Sha256::digest(RP_ID.as_bytes()).to_vec();
+            data.push(flags);
+            data.extend_from_slice(&sign_count.to_be_bytes());
+            data
+        }
+
+        pub fn attestation_object(&self, alg: i64) -> Vec<u8> {
+            let mut auth_data = self.authenticator_data(
+                FLAG_USER_PRESENT | FLAG_USER_VERIFIED | FLAG_ATTESTED_CREDENTIAL_DATA,
+                0,
+            );
+            auth_data.extend_from_slice(&[0u8; 16]);
+            auth_data.extend_from_slice(&(CREDENTIAL_ID.len() as u16).to_be_bytes());
+            auth_data.extend_from_slice(CREDENTIAL_ID);
+            auth_data.extend_from_slice(&self.cose_key(alg));
+
+            let object = Value::Map(vec![
+                (Value::Text("fmt".into()), Value::Text("none".into())),
+                (Value::Text("attStmt".into()), Value::Map(Vec::new())),
+                (Value::Text("authData".into()), Value::Bytes(auth_data)),
+            ]);
+
+            let mut bytes = Vec::new();
+            ciborium::ser::into_writer(&object, &mut bytes).unwrap();
+            bytes
+        }
+
+        /// Returns `(authenticatorData, clientDataJSON, signature)` for a `webauthn.get` ceremony.
+        pub fn assert(&self, challenge: &str, sign_count: u32) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+            let authenticator_data = self.authenticator_data(FLAG_USER_PRESENT | FLAG_USER_VERIFIED, sign_count);
+            let client_data_json = serde_json::json!({
+                "type": CLIENT_DATA_TYPE_GET,
+                "challenge": challenge,
+                "origin": ORIGIN,
+            })
+            .to_string()
+            .into_bytes();
+
+            let mut signed_data = authenticator_data.clone();
+            signed_data.extend_from_slice(&Sha256::digest(&client_data_json));
+            let signature: Signature = self.key.sign(&signed_data);
+
+            (authenticator_data, client_data_json, signature.to_der().as_bytes().to_vec())
+        }
+
+        /// Returns the `clientDataJSON` of a `webauthn.create` ceremony.
+        pub fn create(&self, challenge: &str) -> Vec<u8> {
+            serde_json::json!({
+                "type": CLIENT_DATA_TYPE_CREATE,
+                "challenge": challenge,
+                "origin": ORIGIN,
+            })
+            .to_string()
+            .into_bytes()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::software_authenticator::{Authenticator, CREDENTIAL_ID, ORIGIN, RP_ID};
+    use super::*;
+
+    #[test]
+    fn registration_extracts_the_credential() {
+        let authenticator = Authenticator::new();
+
+        let data = parse_attestation_object(&authenticator.attestation_object(COSE_ALG_ES256)).unwrap();
+        let credential = data.attested_credential.as_ref().unwrap();
+
+        assert!(data.matches_rp_id(RP_ID));
+        assert!(!data.matches_rp_id("evil.example.com"));
+        assert!(data.user_present() && data.user_verified());
+        assert_eq!(credential.credential_id, CREDENTIAL_ID);
+        assert_eq!(credential.public_key, authenticator.public_key());
+    }
+
+    #[test]
+    fn registration_rejects_other_algorithms() {
+        let authenticator = Authenticator::new();
+
+        assert!(parse_attestation_object(&authenticator.attestation_object(-257)).is_err());
+    }
+
+    #[test]
+    fn assertion_signed_by_the_credential_verifies() {
+        let authenticator = Authenticator::new();
+        let challenge = generate_challenge();
+        let (authenticator_data, client_data_json, signature) = authenticator.assert(&challenge, 1);
+
+        let client_data = parse_client_data(&client_data_json, CLIENT_DATA_TYPE_GET, ORIGIN).unwrap();
+        let data = parse_authenticator_data(&authenticator_data).unwrap();
+
+        assert_eq!(client_data.challenge, challenge);
+        assert!(data.matches_rp_id(RP_ID));
+        assert_eq!(data.sign_count, 1);
+        assert!(verify_signature(&authenticator.public_key(), &authenticator_data, &client_data_json, &signature).is_ok());
+    }
+
+    #[test]
+    fn assertion_is_rejected_when_tampered_or_signed_by_another_key() {
+        let authenticator = Authenticator::new();
+        let (authenticator_data, client_data_json, signature) = authenticator.assert(&generate_challenge(), 1);
+
+        let mut tampered = authenticator_data.clone();
+        tampered[36] = 2;
+        assert!(verify_signature(&authenticator.public_key(), &tampered, &client_data_json, &signature).is_err());
+
+        let other = Authenticator::new();
+        assert!(verify_signature(&other.public_key(), &authenticator_data, &client_data_json, &signature).is_err());
+    }
+
+    #[test]
+    fn client_data_from_another_origin_is_rejected() {
+        let authenticator = Authenticator::new();
+        let (_, client_data_json, _) = authenticator.assert(&generate_challenge(), 1);
+
+        assert!(parse_client_data(&client_data_json, CLIENT_DATA_TYPE_GET, "https://evil.example.com").is_err());
+        assert!(parse_client_data(&client_data_json, CLIENT_DATA_TYPE_CREATE, ORIGIN).is_err());
+    }
+}